@@ -0,0 +1,394 @@
+//! Declarative command registration.
+//!
+//! [`CommandBuilder`] describes a command's positional arguments and flags,
+//! generates its usage text, and parses the words passed to the command before calling your callback.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::hook::{ArgsView, Eat, HookHandle, Priority};
+use crate::str::{HexStr, HexString};
+use crate::PluginHandle;
+
+/// Describes a command's arguments, for registration with [`CommandBuilder::register`].
+///
+/// Positional arguments are matched in order. Flags are written as `--name` and may appear anywhere;
+/// a lone `--` causes all following words to be treated as positional arguments.
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::PluginHandle;
+/// use hexavalent::command::CommandBuilder;
+/// use hexavalent::hook::{Eat, Priority};
+///
+/// fn add_greet_command<P>(ph: PluginHandle<'_, P>) {
+///     CommandBuilder::new("greet")
+///         .description("prints a greeting locally")
+///         .arg("name")
+///         .optional_arg("greeting")
+///         .flag("loud")
+///         .register(ph, Priority::Normal, |plugin, ph, args| {
+///             let greeting = args.get("greeting").unwrap_or("Hello");
+///             let mut message = format!("{} {}!", greeting, args.get("name").unwrap());
+///             if args.flag("loud") {
+///                 message = message.to_uppercase();
+///             }
+///             ph.print(message);
+///             Eat::All
+///         });
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CommandBuilder {
+    name: String,
+    description: Option<String>,
+    args: Vec<ArgSpec>,
+    flags: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct ArgSpec {
+    name: String,
+    required: bool,
+}
+
+impl CommandBuilder {
+    /// Starts describing a command with the given name, used as `/name`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            description: None,
+            args: Vec::new(),
+            flags: Vec::new(),
+        }
+    }
+
+    /// Sets a short description of the command, shown after the usage text in `/help`.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    /// Adds a required positional argument.
+    ///
+    /// # Panics
+    ///
+    /// If an optional argument has already been added, since required arguments must come first.
+    pub fn arg(mut self, name: &str) -> Self {
+        assert!(
+            self.args.iter().all(|arg| arg.required),
+            "Required argument `{}` must come before optional arguments",
+            name
+        );
+        self.args.push(ArgSpec {
+            name: name.to_owned(),
+            required: true,
+        });
+        self
+    }
+
+    /// Adds an optional positional argument.
+    pub fn optional_arg(mut self, name: &str) -> Self {
+        self.args.push(ArgSpec {
+            name: name.to_owned(),
+            required: false,
+        });
+        self
+    }
+
+    /// Adds a flag, which is set by passing `--name`.
+    pub fn flag(mut self, name: &str) -> Self {
+        self.flags.push(name.to_owned());
+        self
+    }
+
+    /// Generates usage text for the command, e.g. `Usage: GREET [--loud] <name> [greeting], prints a greeting locally`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::command::CommandBuilder;
+    ///
+    /// let cmd = CommandBuilder::new("greet").arg("name").optional_arg("greeting").flag("loud");
+    /// assert_eq!(cmd.usage(), "Usage: GREET [--loud] <name> [greeting]");
+    /// ```
+    pub fn usage(&self) -> String {
+        let mut usage = format!("Usage: {}", self.name.to_uppercase());
+        for flag in &self.flags {
+            usage.push_str(&format!(" [--{}]", flag));
+        }
+        for arg in &self.args {
+            if arg.required {
+                usage.push_str(&format!(" <{}>", arg.name));
+            } else {
+                usage.push_str(&format!(" [{}]", arg.name));
+            }
+        }
+        if let Some(description) = &self.description {
+            usage.push_str(", ");
+            usage.push_str(description);
+        }
+        usage
+    }
+
+    /// Parses command arguments, not including the command name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::command::CommandBuilder;
+    ///
+    /// let cmd = CommandBuilder::new("greet").arg("name").flag("loud");
+    /// let args = cmd.parse(["--loud", "alice"]).unwrap();
+    /// assert_eq!(args.get("name"), Some("alice"));
+    /// assert!(args.flag("loud"));
+    ///
+    /// assert!(cmd.parse([]).is_err());
+    /// ```
+    pub fn parse<'a>(
+        &self,
+        words: impl IntoIterator<Item = &'a str>,
+    ) -> Result<ParsedArgs<'a>, ParseError> {
+        let mut positional = Vec::new();
+        let mut flags = Vec::new();
+        let mut only_positional = false;
+
+        for word in words {
+            match word.strip_prefix("--") {
+                Some("") if !only_positional => only_positional = true,
+                Some(flag) if !only_positional => match self.flags.iter().find(|f| *f == flag) {
+                    Some(flag) => flags.push(flag.clone()),
+                    None => return Err(ParseError::UnknownFlag(flag.to_owned())),
+                },
+                _ => positional.push(word),
+            }
+        }
+
+        if positional.len() > self.args.len() {
+            return Err(ParseError::UnexpectedArg(
+                positional[self.args.len()].to_owned(),
+            ));
+        }
+
+        let mut args = Vec::new();
+        for (i, spec) in self.args.iter().enumerate() {
+            match positional.get(i) {
+                Some(value) => args.push((spec.name.clone(), *value)),
+                None if spec.required => return Err(ParseError::MissingArg(spec.name.clone())),
+                None => {}
+            }
+        }
+
+        Ok(ParsedArgs { args, flags })
+    }
+
+    /// Parses the `words` passed to a [`PluginHandle::hook_command`] callback.
+    ///
+    /// Behaves the same as [`CommandBuilder::parse`], but skips the command name in `words[0]`
+    /// and the empty strings which HexChat pads `words` with.
+    ///
+    /// This allows a command registered with [`PluginHandle::hook_command`] to share its arguments with its help text,
+    /// by passing [`CommandBuilder::usage`] as the help text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::command::CommandBuilder;
+    /// use hexavalent::hook::{Eat, Priority};
+    ///
+    /// fn greet_command() -> CommandBuilder {
+    ///     CommandBuilder::new("greet").arg("name")
+    /// }
+    ///
+    /// fn add_greet_command<P: 'static>(ph: PluginHandle<'_, P>) {
+    ///     ph.hook_command(c"greet", greet_command().usage(), Priority::Normal, |plugin, ph, words| {
+    ///         match greet_command().parse_words(words) {
+    ///             Ok(args) => ph.print(format!("Hello {}!", args.get("name").unwrap())),
+    ///             Err(e) => ph.print(format!("greet: {}", e)),
+    ///         }
+    ///         Eat::All
+    ///     });
+    /// }
+    /// ```
+    pub fn parse_words<'a>(&self, words: &'a [&'a HexStr]) -> Result<ParsedArgs<'a>, ParseError> {
+        let words = ArgsView::new(words);
+        self.parse(words.as_slice().iter().skip(1).map(|word| word.as_str()))
+    }
+
+    /// Registers the command with HexChat.
+    ///
+    /// `callback` is called with the parsed arguments.
+    /// If the arguments are invalid, the error and usage text are printed instead, and the command is eaten.
+    ///
+    /// Analogous to [`hexchat_hook_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_command).
+    pub fn register<P, F>(
+        self,
+        ph: PluginHandle<'_, P>,
+        priority: Priority,
+        callback: F,
+    ) -> HookHandle
+    where
+        F: Fn(&P, PluginHandle<'_, P>, &ParsedArgs<'_>) -> Eat + 'static,
+    {
+        let name = self.name.clone();
+        let usage = self.usage();
+        ph.hook_command_boxed(
+            name,
+            usage.clone(),
+            priority,
+            move |plugin, ph, words| match self.parse_words(words) {
+                Ok(args) => callback(plugin, ph, &args),
+                Err(e) => {
+                    ph.print(format!("{}: {}", self.name, e));
+                    ph.print(&*usage);
+                    Eat::All
+                }
+            },
+        )
+    }
+}
+
+/// Arguments parsed by a [`CommandBuilder`].
+#[derive(Debug, Clone)]
+pub struct ParsedArgs<'a> {
+    args: Vec<(String, &'a str)>,
+    flags: Vec<String>,
+}
+
+impl<'a> ParsedArgs<'a> {
+    /// Gets the value of a positional argument, or `None` if it is optional and was not provided.
+    pub fn get(&self, name: &str) -> Option<&'a str> {
+        self.args
+            .iter()
+            .find(|(arg, _)| arg == name)
+            .map(|&(_, value)| value)
+    }
+
+    /// Whether a flag was passed.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+}
+
+/// An error from parsing command arguments.
+///
+/// Returned from [`CommandBuilder::parse`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A required argument was not provided.
+    MissingArg(String),
+    /// More arguments were provided than the command accepts.
+    UnexpectedArg(String),
+    /// A flag was passed which the command does not accept.
+    UnknownFlag(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingArg(name) => write!(f, "missing argument <{}>", name),
+            Self::UnexpectedArg(value) => write!(f, "unexpected argument '{}'", value),
+            Self::UnknownFlag(name) => write!(f, "unknown flag --{}", name),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// An error detected while executing a command.
+///
+/// Returned from [`PluginHandle::command_checked`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// A `Server Error` event was printed, with the given text.
+    ServerError(HexString),
+    /// A `Generic Message` event was printed, with the given left and right text.
+    GenericMessage(HexString, HexString),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ServerError(text) => write!(f, "{}", text),
+            Self::GenericMessage(left, right) if left.is_empty() => write!(f, "{}", right),
+            Self::GenericMessage(left, right) => write!(f, "{} {}", left, right),
+        }
+    }
+}
+
+impl Error for CommandError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cmd() -> CommandBuilder {
+        CommandBuilder::new("greet")
+            .arg("name")
+            .optional_arg("greeting")
+            .flag("loud")
+    }
+
+    #[test]
+    fn parses_positional_and_flags() {
+        let args = cmd().parse(["alice", "--loud", "hi"]).unwrap();
+        assert_eq!(args.get("name"), Some("alice"));
+        assert_eq!(args.get("greeting"), Some("hi"));
+        assert!(args.flag("loud"));
+
+        let args = cmd().parse(["bob"]).unwrap();
+        assert_eq!(args.get("greeting"), None);
+        assert!(!args.flag("loud"));
+    }
+
+    #[test]
+    fn double_dash_ends_flags() {
+        let args = cmd().parse(["--", "--loud"]).unwrap();
+        assert_eq!(args.get("name"), Some("--loud"));
+        assert!(!args.flag("loud"));
+    }
+
+    #[test]
+    fn parses_hook_command_words() {
+        let words = [
+            HexStr::from_bytes_with_nul(b"greet\0").unwrap(),
+            HexStr::from_bytes_with_nul(b"alice\0").unwrap(),
+            HexStr::from_bytes_with_nul(b"\0").unwrap(),
+            HexStr::from_bytes_with_nul(b"\0").unwrap(),
+        ];
+        let args = cmd().parse_words(&words).unwrap();
+        assert_eq!(args.get("name"), Some("alice"));
+        assert_eq!(args.get("greeting"), None);
+
+        assert_eq!(
+            cmd().parse_words(&words[..1]).unwrap_err(),
+            ParseError::MissingArg("name".to_owned())
+        );
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(
+            cmd().parse([]).unwrap_err(),
+            ParseError::MissingArg("name".to_owned())
+        );
+        assert_eq!(
+            cmd().parse(["a", "b", "c"]).unwrap_err(),
+            ParseError::UnexpectedArg("c".to_owned())
+        );
+        assert_eq!(
+            cmd().parse(["--quiet"]).unwrap_err(),
+            ParseError::UnknownFlag("quiet".to_owned())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn required_after_optional() {
+        let _ = CommandBuilder::new("x").optional_arg("a").arg("b");
+    }
+}
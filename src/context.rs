@@ -1,10 +1,13 @@
 //! Server/channel contexts.
 
+use std::error::Error;
+use std::fmt;
 use std::marker::PhantomData;
 use std::ptr::NonNull;
 
 use crate::ffi::hexchat_context;
-use crate::str::{HexString, IntoCStr};
+use crate::rfc1459::IrcName;
+use crate::str::{HexStr, HexString, IntoCStr};
 
 /// Criteria used to find a server/channel context.
 ///
@@ -63,6 +66,193 @@ impl<S: IntoCStr> Context<S> {
     }
 }
 
+/// Owned criteria used to find a server/channel context, which can be stored across callbacks.
+///
+/// Unlike [`ContextHandle`], which is only valid during a single callback, a `SavedContext` holds the server and channel names,
+/// and is re-resolved each time it is used.
+///
+/// Used with [`PluginHandle::enter_saved`](crate::PluginHandle::enter_saved).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::cell::RefCell;
+/// use hexavalent::PluginHandle;
+/// use hexavalent::context::SavedContext;
+///
+/// #[derive(Default)]
+/// struct MyPlugin {
+///     log_context: RefCell<Option<SavedContext>>,
+/// }
+///
+/// fn remember_current(plugin: &MyPlugin, ph: PluginHandle<'_, MyPlugin>) {
+///     *plugin.log_context.borrow_mut() = Some(ph.save_context());
+/// }
+///
+/// fn log_message(plugin: &MyPlugin, ph: PluginHandle<'_, MyPlugin>, msg: &str) {
+///     if let Some(saved) = &*plugin.log_context.borrow() {
+///         if let Err(()) = ph.enter_saved(saved, || ph.print(msg)) {
+///             ph.print(c"Log context is gone!");
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SavedContext {
+    servname: Option<HexString>,
+    channel: Option<HexString>,
+}
+
+impl SavedContext {
+    /// The specified channel in the current server, or if no matching channel exists, in any server.
+    ///
+    /// See [`Context::channel`].
+    ///
+    /// # Panics
+    ///
+    /// If `channel` is not valid UTF-8.
+    pub fn channel(channel: impl IntoCStr) -> Self {
+        Self {
+            servname: None,
+            channel: Some(to_hex_string(channel)),
+        }
+    }
+
+    /// The frontmost channel in the specified server.
+    ///
+    /// See [`Context::frontmost`].
+    ///
+    /// # Panics
+    ///
+    /// If `servname` is not valid UTF-8.
+    pub fn frontmost(servname: impl IntoCStr) -> Self {
+        Self {
+            servname: Some(to_hex_string(servname)),
+            channel: None,
+        }
+    }
+
+    /// The specified channel in the specified server.
+    ///
+    /// See [`Context::fully_qualified`].
+    ///
+    /// # Panics
+    ///
+    /// If `servname` or `channel` is not valid UTF-8.
+    pub fn fully_qualified(servname: impl IntoCStr, channel: impl IntoCStr) -> Self {
+        Self {
+            servname: Some(to_hex_string(servname)),
+            channel: Some(to_hex_string(channel)),
+        }
+    }
+
+    /// The server name, if any.
+    pub fn servname(&self) -> Option<&HexStr> {
+        self.servname.as_deref()
+    }
+
+    /// The channel name, if any.
+    pub fn channel_name(&self) -> Option<&HexStr> {
+        self.channel.as_deref()
+    }
+
+    /// Borrows these criteria as a [`Context`], for use with [`PluginHandle::find_context`](crate::PluginHandle::find_context).
+    pub fn as_context(&self) -> Context<&HexStr> {
+        Context {
+            servname: self.servname.as_deref(),
+            channel: self.channel.as_deref(),
+        }
+    }
+}
+
+/// An owned identity for a channel on a network, for keying per-channel state across callbacks.
+///
+/// Channel names are compared and hashed using [RFC1459 casemapping](crate::rfc1459),
+/// so `#Rust` and `#rust` on the same network have the same key. Network names are compared exactly.
+///
+/// Returned from [`PluginHandle::current_context_key`](crate::PluginHandle::current_context_key).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::cell::RefCell;
+/// use std::collections::HashMap;
+/// use hexavalent::PluginHandle;
+/// use hexavalent::context::ContextKey;
+///
+/// #[derive(Default)]
+/// struct MyPlugin {
+///     messages: RefCell<HashMap<ContextKey, u32>>,
+/// }
+///
+/// fn count_message(plugin: &MyPlugin, ph: PluginHandle<'_, MyPlugin>) {
+///     if let Some(key) = ph.current_context_key() {
+///         *plugin.messages.borrow_mut().entry(key).or_default() += 1;
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ContextKey {
+    network: HexString,
+    channel: IrcName,
+}
+
+impl ContextKey {
+    /// Creates a key for the specified channel on the specified network.
+    pub fn new(network: HexString, channel: HexString) -> Self {
+        Self {
+            network,
+            channel: IrcName::new(channel),
+        }
+    }
+
+    /// The network name.
+    pub fn network(&self) -> &HexStr {
+        &self.network
+    }
+
+    /// The channel name, in its original case.
+    pub fn channel_name(&self) -> &HexStr {
+        self.channel.as_hexstr()
+    }
+
+    /// Borrows this key as a [`Context`], for use with [`PluginHandle::find_context`](crate::PluginHandle::find_context).
+    pub fn as_context(&self) -> Context<&HexStr> {
+        Context::fully_qualified(self.network(), self.channel_name())
+    }
+}
+
+fn to_hex_string(s: impl IntoCStr) -> HexString {
+    let s = s.into_cstr();
+    HexStr::from_cstr(&s)
+        .unwrap_or_else(|e| panic!("Invalid UTF8 in saved context: {}", e))
+        .to_owned()
+}
+
+/// An error that occurred while entering a server/channel context.
+///
+/// Returned from [`PluginHandle::try_with_context`](crate::PluginHandle::try_with_context)
+/// and [`PluginHandle::in_channel`](crate::PluginHandle::in_channel).
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ContextError {
+    /// No context matched the criteria.
+    NotFound,
+    /// HexChat refused to switch to the context, e.g. because it was closed.
+    Invalidated,
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => f.write_str("context not found"),
+            Self::Invalidated => f.write_str("context invalidated"),
+        }
+    }
+}
+
+impl Error for ContextError {}
+
 /// A handle to a server/channel context in HexChat.
 ///
 /// Returned from [`PluginHandle::find_context`](crate::PluginHandle::find_context).
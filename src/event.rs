@@ -1,17 +1,40 @@
 //! Print and server events.
 
+use std::fmt;
 use std::marker::PhantomData;
 
 use time::OffsetDateTime;
 
+use crate::str::{HexStr, HexString};
+
 /// Attributes associated with an event.
 ///
 /// Used with [`PluginHandle::emit_print_attrs`](crate::PluginHandle::emit_print_attrs),
 /// [`PluginHandle::hook_print_attrs`](crate::PluginHandle::hook_print_attrs),
 /// and [`PluginHandle::hook_server_attrs`](crate::PluginHandle::hook_server_attrs).
 ///
+/// `EventAttrs` are ordered by their timestamp, so that buffered events can be sorted by server time.
+///
 /// Analogous to [`hexchat_event_attrs`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_emit_print_attrs).
-#[derive(Debug, Copy, Clone)]
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::event::EventAttrs;
+/// use time::OffsetDateTime;
+///
+/// # #[cfg(not(feature = "__unstable_ircv3_line_in_event_attrs"))]
+/// # {
+/// let earlier = EventAttrs::new(OffsetDateTime::from_unix_timestamp(1).unwrap());
+/// let later = EventAttrs::new(OffsetDateTime::from_unix_timestamp(2).unwrap());
+///
+/// let mut attrs = vec![later, earlier];
+/// attrs.sort();
+/// assert_eq!(attrs, [earlier, later]);
+/// # }
+/// ```
+// Note: field order matters for the derived `Ord`, which compares by time first.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct EventAttrs<'a> {
     time: OffsetDateTime,
     #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
@@ -56,12 +79,108 @@ impl<'a> EventAttrs<'a> {
     }
 }
 
+/// Copies the arguments of an event into owned strings, so they can be stored after the hook callback returns.
+///
+/// Event arguments borrow strings owned by HexChat, which are only valid during the callback.
+/// This is useful for deferring handling of the event, e.g. to a timer.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::cell::RefCell;
+/// use hexavalent::PluginHandle;
+/// use hexavalent::event::to_owned_args;
+/// use hexavalent::event::server::Privmsg;
+/// use hexavalent::hook::{Eat, Priority};
+/// use hexavalent::str::HexString;
+///
+/// #[derive(Default)]
+/// struct MyPlugin {
+///     pending: RefCell<Vec<[HexString; 4]>>,
+/// }
+///
+/// fn queue_privmsgs(ph: PluginHandle<'_, MyPlugin>) {
+///     ph.hook_server(Privmsg, Priority::Normal, |plugin, ph, args| {
+///         plugin.pending.borrow_mut().push(to_owned_args(args));
+///         Eat::None
+///     });
+/// }
+/// ```
+pub fn to_owned_args<const N: usize>(args: [&HexStr; N]) -> [HexString; N] {
+    args.map(ToOwned::to_owned)
+}
+
+/// The name and number of arguments of an event type, for listing events at runtime.
+///
+/// See [`print::ALL`] and [`server::ALL`].
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::event::{print, server};
+///
+/// for event in print::ALL.iter().chain(server::ALL) {
+///     println!("{} ({} args)", event.name(), event.arity());
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct EventInfo {
+    name: &'static str,
+    arity: usize,
+}
+
+impl EventInfo {
+    pub(crate) const fn new(name: &'static str, arity: usize) -> Self {
+        Self { name, arity }
+    }
+
+    /// The name of the event, as used by HexChat.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::event::print;
+    ///
+    /// assert!(print::ALL.iter().any(|event| event.name() == "Channel Message"));
+    /// ```
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The number of arguments the event has.
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+}
+
 /// Trait implemented by all event types.
 ///
 /// See the [`PrintEvent`](print::PrintEvent) and [`ServerEvent`](server::ServerEvent) traits for usage.
 ///
 /// This trait is sealed and cannot be implemented outside of `hexavalent`.
-pub trait Event<const ARGS: usize>: Default + private::EventImpl<ARGS> {}
+pub trait Event<const ARGS: usize>: Default + fmt::Display + private::EventImpl<ARGS> {
+    /// The name of this event, as used by HexChat.
+    ///
+    /// Event types also implement `Display`, which writes the same name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::event::Event;
+    /// use hexavalent::event::print::ChannelMessage;
+    /// use hexavalent::event::server::Privmsg;
+    ///
+    /// assert_eq!(ChannelMessage.name(), "Channel Message");
+    /// assert_eq!(Privmsg.name(), "PRIVMSG");
+    /// assert_eq!(ChannelMessage.to_string(), "Channel Message");
+    /// ```
+    fn name(&self) -> &'static str {
+        match Self::NAME.to_str() {
+            Ok(name) => name,
+            Err(_) => unreachable!("event names are ASCII"),
+        }
+    }
+}
 
 pub(crate) mod private {
     use std::ffi::CStr;
@@ -109,11 +228,11 @@ macro_rules! count {
 
 macro_rules! event {
     (
-        $struct_name:ident,
+        $struct_name:ident => $args_name:ident,
         $event_name:literal,
         $event_doc:literal,
-        $($index:tt : $field_name:literal),*
-        $(; eol $eol_index:tt : $eol_name:literal)?
+        $($index:tt $field_ident:ident : $field_name:literal),*
+        $(; eol $eol_index:tt $eol_ident:ident : $eol_name:literal)?
     ) => {
         #[doc = "`"]
         #[doc = $event_name]
@@ -139,6 +258,52 @@ macro_rules! event {
 
         impl crate::event::Event<{ count!($($index)* $($eol_index)?) }> for $struct_name {}
 
+        impl $struct_name {
+            pub(crate) const INFO: crate::event::EventInfo =
+                crate::event::EventInfo::new($event_name, count!($($index)* $($eol_index)?));
+
+            #[doc = concat!("Names the arguments of this event, as passed to hook callbacks. See [`", stringify!($args_name), "`].")]
+            pub fn parse(args: [&crate::str::HexStr; { count!($($index)* $($eol_index)?) }]) -> $args_name<'_> {
+                $args_name::from_args(args)
+            }
+        }
+
+        #[doc = concat!("The arguments of [`", stringify!($struct_name), "`], by name.")]
+        #[derive(Debug, Copy, Clone)]
+        pub struct $args_name<'a> {
+            $(
+                #[doc = "`"]
+                #[doc = $field_name]
+                #[doc = "`"]
+                pub $field_ident: &'a crate::str::HexStr,
+            )*
+            $(
+                #[doc = "`"]
+                #[doc = $eol_name]
+                #[doc = "`"]
+                pub $eol_ident: &'a crate::str::HexStr,
+            )?
+            _marker: ::std::marker::PhantomData<&'a crate::str::HexStr>,
+        }
+
+        impl<'a> $args_name<'a> {
+            /// Names the arguments of this event, as passed to hook callbacks.
+            pub fn from_args(args: [&'a crate::str::HexStr; { count!($($index)* $($eol_index)?) }]) -> Self {
+                let [$($field_ident,)* $($eol_ident)?] = args;
+                Self {
+                    $($field_ident,)*
+                    $($eol_ident,)?
+                    _marker: ::std::marker::PhantomData,
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for $struct_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str($event_name)
+            }
+        }
+
         impl crate::event::private::EventImpl<{ count!($($index)* $($eol_index)?) }> for $struct_name {
             const NAME: &'static ::std::ffi::CStr = match ::std::ffi::CStr::from_bytes_with_nul(concat!($event_name, "\0").as_bytes()) {
                 Ok(name) => name,
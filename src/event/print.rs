@@ -4,7 +4,34 @@
 
 use crate::event::Event;
 
-/// Trait implemented by all print event types.
+/// Trait implemented by all print event types which can be hooked,
+/// i.e. all [`PrintEvent`]s and [`HookOnlyPrintEvent`]s.
+///
+/// Used with [`PluginHandle::hook_print`](crate::PluginHandle::hook_print)
+/// and [`PluginHandle::hook_print_attrs`](crate::PluginHandle::hook_print_attrs).
+///
+/// This trait is sealed and cannot be implemented outside of `hexavalent`.
+pub trait HookablePrintEvent<const ARGS: usize>: Event<ARGS> {}
+
+/// Trait implemented by [special] print event types, which can only be hooked, not emitted.
+///
+/// These events do not implement [`PrintEvent`], so passing them to emission functions is a compile error.
+///
+/// This trait is sealed and cannot be implemented outside of `hexavalent`.
+///
+/// # Examples
+///
+/// ```rust,compile_fail
+/// use hexavalent::PluginHandle;
+/// use hexavalent::event::print::special::FocusTab;
+///
+/// fn emit_focus_tab<P>(ph: PluginHandle<'_, P>) -> Result<(), ()> {
+///     ph.emit_print(FocusTab, ())
+/// }
+/// ```
+pub trait HookOnlyPrintEvent<const ARGS: usize>: HookablePrintEvent<ARGS> {}
+
+/// Trait implemented by all print event types which can be emitted.
 ///
 /// Used with [`PluginHandle::emit_print`](crate::PluginHandle::emit_print),
 /// [`PluginHandle::emit_print_attrs`](crate::PluginHandle::emit_print_attrs),
@@ -43,19 +70,69 @@ use crate::event::Event;
 ///     });
 /// }
 /// ```
-pub trait PrintEvent<const ARGS: usize>: Event<ARGS> {}
+///
+/// Naming the arguments of a print event, instead of destructuring them by position.
+///
+/// ```rust
+/// use hexavalent::PluginHandle;
+/// use hexavalent::event::print::ChannelMessage;
+/// use hexavalent::hook::{Eat, Priority};
+///
+/// fn hook_message<P>(ph: PluginHandle<'_, P>) {
+///     ph.hook_print(ChannelMessage, Priority::Normal, |plugin, ph, args| {
+///         let msg = ChannelMessage::parse(args);
+///         ph.print(format!("Message from {}: {}", msg.nick, msg.text));
+///         Eat::HexChat
+///     });
+/// }
+/// ```
+pub trait PrintEvent<const ARGS: usize>: HookablePrintEvent<ARGS> {}
+
+/// Trait implemented by print events which have no fields and no text format.
+///
+/// Emitting these events prints nothing; they exist only for their side effects and for print hooks.
+///
+/// Used with [`PluginHandle::emit_signal`](crate::PluginHandle::emit_signal).
+///
+/// This trait is sealed and cannot be implemented outside of `hexavalent`.
+pub trait SignalPrintEvent: PrintEvent<0> {}
 
 macro_rules! print_event {
     (
-        $struct_name:ident,
+        $struct_name:ident => $args_name:ident,
         $event_name:literal,
         $event_doc:literal,
-        $($index:tt : $field_name:literal),*
+        $($index:tt $field_ident:ident : $field_name:literal),*
     ) => {
-        event!($struct_name, $event_name, $event_doc, $($index : $field_name),*);
+        event!($struct_name => $args_name, $event_name, $event_doc, $($index $field_ident : $field_name),*);
+
+        impl crate::event::print::HookablePrintEvent<{ count!($($index)*) }> for $struct_name {}
 
         impl crate::event::print::PrintEvent<{ count!($($index)*) }> for $struct_name {}
     };
+    (
+        signal;
+        $struct_name:ident => $args_name:ident,
+        $event_name:literal,
+        $event_doc:literal,
+    ) => {
+        print_event!($struct_name => $args_name, $event_name, $event_doc,);
+
+        impl crate::event::print::SignalPrintEvent for $struct_name {}
+    };
+    (
+        hook_only;
+        $struct_name:ident => $args_name:ident,
+        $event_name:literal,
+        $event_doc:literal,
+        $($index:tt $field_ident:ident : $field_name:literal),*
+    ) => {
+        event!($struct_name => $args_name, $event_name, $event_doc, $($index $field_ident : $field_name),*);
+
+        impl crate::event::print::HookablePrintEvent<{ count!($($index)*) }> for $struct_name {}
+
+        impl crate::event::print::HookOnlyPrintEvent<{ count!($($index)*) }> for $struct_name {}
+    };
 }
 
 mod impls;
@@ -64,7 +141,8 @@ pub use impls::*;
 
 /// Special print event types which can only be hooked, not emitted.
 ///
-/// Attempting to emit these events with emission functions such as [`PluginHandle::emit_print`](crate::PluginHandle::emit_print) will always fail.
+/// These events implement [`HookOnlyPrintEvent`] instead of [`PrintEvent`],
+/// so they cannot be passed to emission functions such as [`PluginHandle::emit_print`](crate::PluginHandle::emit_print).
 ///
 /// Analogous to the special print events documented for [`hexchat_hook_print`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_print).
 pub mod special;
@@ -48,7 +48,122 @@ function nameToCamelCase(spacedName) {
 	}).join('');
 }
 
+const FIELD_IDENTS = {
+	'Acknowledged Capabilities': 'capabilities',
+	'Account': 'account',
+	'Away reason': 'reason',
+	'Away Reason': 'reason',
+	'Ban time': 'time',
+	'Banmask': 'mask',
+	'CPS': 'cps',
+	'Channel': 'channel',
+	'Channel Membership/"is an IRC operator"': 'membership',
+	'Channel Name': 'channel',
+	'DCC String': 'dcc_string',
+	'DCC Type': 'dcc_type',
+	'Destination filename': 'destination',
+	'Error': 'error',
+	'Filename': 'file',
+	'Full name': 'real_name',
+	'Host': 'host',
+	'Hostmask': 'hostmask',
+	'Hostname': 'hostname',
+	'IP': 'ip',
+	'IP address': 'ip',
+	'Identified text': 'identified',
+	'Idle time': 'idle_time',
+	'Left message': 'left',
+	'Mechanism': 'mechanism',
+	'Message': 'message',
+	'Mode char': 'mode',
+	'Modes string': 'modes',
+	'Network': 'network',
+	'New Filename': 'new_file',
+	'New nickname': 'new_nick',
+	'Nick': 'nick',
+	'Nick being tried': 'new_nick',
+	'Nick of person who changed the topic': 'nick',
+	'Nick of person who have been invited': 'target',
+	'Nick of person who invited you': 'nick',
+	'Nickname': 'nick',
+	'Nickname in use': 'nick',
+	'Number of notify items': 'count',
+	'Numeric': 'numeric',
+	'Old Filename': 'old_file',
+	'Old nickname': 'old_nick',
+	'PID': 'pid',
+	'Pathname': 'path',
+	'Port': 'port',
+	'Position': 'position',
+	'Raw Numeric or Identifier': 'numeric',
+	'Real IP': 'real_ip',
+	'Real user@host': 'real_host',
+	'Reason': 'reason',
+	'Receiver': 'target',
+	'Removed Capabilities': 'capabilities',
+	'Requested Capabilities': 'capabilities',
+	'Right message': 'right',
+	'Seconds': 'seconds',
+	'Server Capabilities': 'capabilities',
+	'Server Information': 'info',
+	'Server Name': 'server',
+	'Signon time': 'signon_time',
+	'Size': 'size',
+	'Text': 'text',
+	'The CTCP event': 'ctcp',
+	'The Channel it\'s going to': 'channel',
+	'The Packet': 'packet',
+	'The account of the person': 'account',
+	'The action': 'action',
+	'The ban mask': 'mask',
+	'The channel': 'channel',
+	'The channel being joined': 'channel',
+	'The channel it\'s being set on': 'channel',
+	'The creator': 'creator',
+	'The exempt mask': 'mask',
+	'The host of the person': 'host',
+	'The invite mask': 'mask',
+	'The key': 'key',
+	'The limit': 'limit',
+	'The message': 'message',
+	'The mode letter': 'mode',
+	'The mode\'s sign (+/-)': 'sign',
+	'The nick of the joining person': 'nick',
+	'The nick of the person': 'nick',
+	'The nick of the person leaving': 'nick',
+	'The nick who removed the key': 'nick',
+	'The nick who removed the limit': 'nick',
+	'The nickname of the kicker': 'kicker',
+	'The person being kicked': 'kicked',
+	'The quiet mask': 'mask',
+	'The reason': 'reason',
+	'The sound': 'sound',
+	'The text': 'text',
+	'The time': 'time',
+	'The time in x.x format (see below)': 'time',
+	'Topic': 'topic',
+	'URL': 'url',
+	'Username': 'user',
+	'Users': 'users',
+	'Who it\'s from': 'nick',
+	'Who set the ban': 'setter',
+};
+
+function fieldToIdent(field) {
+	const unescaped = field.replace(/\\"/g, '"');
+	if (unescaped in FIELD_IDENTS) {
+		return FIELD_IDENTS[unescaped];
+	}
+	// e.g. "The nick of the person who did the op'ing" and "The nick of the person who has been op'ed"
+	if ((/^The nick of the person (who|setting|removed)/).test(unescaped)) {
+		return (/who has been/).test(unescaped) ? 'target' : 'nick';
+	}
+	throw new Error(`No identifier for field: ${field}`);
+}
+
 function* generateRustLines() {
+	const names = [];
+
 	const field_descriptions = Object.create(null);
 
 	field_descriptions.pevt_generic_none_help = [];
@@ -58,8 +173,24 @@ function* generateRustLines() {
 	}
 
 	for (const { name, fields_key, format } of readTextEvents()) {
-		yield `print_event!(${nameToCamelCase(name)}, "${name}", "\`${format}\`", ${field_descriptions[fields_key].map((field, i) => `${i}: "${field}"`).join(', ')});`;
+		const structName = nameToCamelCase(name);
+		names.push(structName);
+		const signal = format === '' ? 'signal; ' : '';
+		const fields = field_descriptions[fields_key];
+		const idents = fields.map(fieldToIdent);
+		if (new Set(idents).size !== idents.length) {
+			throw new Error(`Duplicate field identifiers in ${name}: ${idents}`);
+		}
+		yield `print_event!(${signal}${structName} => ${structName}Args, "${name}", "\`${format}\`", ${fields.map((field, i) => `${i} ${idents[i]}: "${field}"`).join(', ')});`;
+	}
+
+	yield '';
+	yield '/// Information about every print event in this module, not including [special](crate::event::print::special) events.';
+	yield 'pub const ALL: &[crate::event::EventInfo] = &[';
+	for (const name of names) {
+		yield `    ${name}::INFO,`;
 	}
+	yield '];';
 }
 
 function main() {
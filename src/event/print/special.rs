@@ -1,22 +1,36 @@
 print_event!(
-    OpenContext,
+    hook_only;
+    OpenContext => OpenContextArgs,
     "Open Context",
     "Called when a new hexchat_context is created.",
 );
 print_event!(
-    CloseContext,
+    hook_only;
+    CloseContext => CloseContextArgs,
     "Close Context",
     "Called when a hexchat_context pointer is closed.",
 );
 print_event!(
-    FocusTab,
+    hook_only;
+    FocusTab => FocusTabArgs,
     "Focus Tab",
     "Called when a tab is brought to front.",
 );
 print_event!(
-    FocusWindow,
+    hook_only;
+    FocusWindow => FocusWindowArgs,
     "Focus Window",
     "Called a toplevel window is focused, or the main tab-window is focused by the window manager.",
 );
-print_event!(DccChatText, "DCC Chat Text", "Called when some text from a DCC Chat arrives.", 0: "Address", 1: "Port", 2: "Nick", 3: "The Message");
-print_event!(KeyPress, "Key Press", "Called when some keys are pressed in the input box.", 0: "Key Value", 1: "State Bitfield (shift, capslock, alt)", 2: "String version of the key", 3: "Length of the string (may be 0 for unprintable keys)");
+print_event!(hook_only; DccChatText => DccChatTextArgs, "DCC Chat Text", "Called when some text from a DCC Chat arrives.", 0 address: "Address", 1 port: "Port", 2 nick: "Nick", 3 message: "The Message");
+print_event!(hook_only; KeyPress => KeyPressArgs, "Key Press", "Called when some keys are pressed in the input box.", 0 key_value: "Key Value", 1 state: "State Bitfield (shift, capslock, alt)", 2 key_string: "String version of the key", 3 key_string_len: "Length of the string (may be 0 for unprintable keys)");
+
+/// Information about every special print event.
+pub const ALL: &[crate::event::EventInfo] = &[
+    OpenContext::INFO,
+    CloseContext::INFO,
+    FocusTab::INFO,
+    FocusWindow::INFO,
+    DccChatText::INFO,
+    KeyPress::INFO,
+];
@@ -1,4 +1,14 @@
 //! Server event types.
+//!
+//! The last argument of events which carry free text (message bodies, quit and part reasons, topics, etc.)
+//! is taken from `word_eol`, so it contains the untruncated rest of the line, including spaces.
+//! These are: [`Authenticate`], [`Away`], [`Cap`], [`Invite`], [`Join`], [`Kick`], [`Kill`], [`Mode`],
+//! [`Nick`], [`Notice`], [`Part`], [`Ping`], [`Pong`], [`Privmsg`], [`Quit`], [`Topic`], and [`Wallops`].
+//!
+//! Note that the leading `:` of a trailing parameter is not removed.
+//!
+//! To observe every line received from the server, hook [`special::RawLine`].
+//! Its last argument is also taken from `word_eol`, so the original line can be logged as `"{sender} {command} {arguments}"`.
 
 use crate::event::Event;
 
@@ -29,13 +39,13 @@ pub trait ServerEvent<const ARGS: usize>: Event<ARGS> {}
 
 macro_rules! server_event {
     (
-        $struct_name:ident,
+        $struct_name:ident => $args_name:ident,
         $event_name:literal,
         $event_doc:literal,
-        $($index:tt : $field_name:literal),*
-        $(; eol $eol_index:tt : $eol_name:literal)?
+        $($index:tt $field_ident:ident : $field_name:literal),*
+        $(; eol $eol_index:tt $eol_ident:ident : $eol_name:literal)?
     ) => {
-        event!($struct_name, $event_name, $event_doc, $($index : $field_name),* $(; eol $eol_index : $eol_name)?);
+        event!($struct_name => $args_name, $event_name, $event_doc, $($index $field_ident : $field_name),* $(; eol $eol_index $eol_ident : $eol_name)?);
 
         impl crate::event::server::ServerEvent<{ count!($($index)* $($eol_index)?) }> for $struct_name {}
     };
@@ -1,15 +1,42 @@
-server_event!(Away,    "AWAY",    "", 0: "Sender", 1: "\"AWAY\"";    eol 2: "Reason");
-server_event!(Invite,  "INVITE",  "", 0: "Sender", 1: "\"INVITE\"",      2: "Target";  eol 3: "Channel");
-server_event!(Join,    "JOIN",    "", 0: "Sender", 1: "\"JOIN\"",        2: "Channel",     3: "Account"; eol 4: "Realname");
-server_event!(Kick,    "KICK",    "", 0: "Sender", 1: "\"KICK\"",        2: "Channel",     3: "Target";  eol 4: "Reason");
-server_event!(Kill,    "KILL",    "", 0: "Sender", 1: "\"KILL\"",        2: "Target";  eol 3: "Reason");
-server_event!(Mode,    "MODE",    "", 0: "Sender", 1: "\"MODE\"",        2: "Target",      3: "Modes";   eol 4: "Arguments");
-server_event!(Nick,    "NICK",    "", 0: "Sender", 1: "\"NICK\"";    eol 2: "Nickname");
-server_event!(Notice,  "NOTICE",  "", 0: "Sender", 1: "\"NOTICE\"",      2: "Target";  eol 3: "Text");
-server_event!(Part,    "PART",    "", 0: "Sender", 1: "\"PART\"",        2: "Channel"; eol 3: "Reason");
-server_event!(Ping,    "PING",    "", 0: "Sender", 1: "\"PING\"";    eol 2: "Server");
-server_event!(Pong,    "PONG",    "", 0: "Sender", 1: "\"PONG\"",        2: "Server";  eol 3: "Timestamp");
-server_event!(Privmsg, "PRIVMSG", "", 0: "Sender", 1: "\"PRIVMSG\"",     2: "Target";  eol 3: "Text");
-server_event!(Quit,    "QUIT",    "", 0: "Sender", 1: "\"QUIT\"";    eol 2: "Reason");
-server_event!(Topic,   "TOPIC",   "", 0: "Sender", 1: "\"TOPIC\"",       2: "Target";  eol 3: "Topic");
-server_event!(Wallops, "WALLOPS", "", 0: "Sender", 1: "\"WALLOPS\""; eol 2: "Text");
+server_event!(Account      => AccountArgs,      "ACCOUNT",      "", 0 sender: "Sender", 1 command: "\"ACCOUNT\"",     2 account: "Account");
+server_event!(Authenticate => AuthenticateArgs, "AUTHENTICATE", "", 0 sender: "Sender", 1 command: "\"AUTHENTICATE\""; eol 2 data: "Data");
+server_event!(Away         => AwayArgs,         "AWAY",         "", 0 sender: "Sender", 1 command: "\"AWAY\"";         eol 2 reason: "Reason");
+server_event!(Cap          => CapArgs,          "CAP",          "", 0 sender: "Sender", 1 command: "\"CAP\"",         2 target: "Target",         3 subcommand: "Subcommand"; eol 4 capabilities: "Capabilities");
+server_event!(Chghost      => ChghostArgs,      "CHGHOST",      "", 0 sender: "Sender", 1 command: "\"CHGHOST\"",     2 new_user: "New user",     3 new_host: "New host");
+server_event!(Invite       => InviteArgs,       "INVITE",       "", 0 sender: "Sender", 1 command: "\"INVITE\"",      2 target: "Target";   eol 3 channel: "Channel");
+server_event!(Join         => JoinArgs,         "JOIN",         "", 0 sender: "Sender", 1 command: "\"JOIN\"",        2 channel: "Channel",       3 account: "Account";       eol 4 real_name: "Realname");
+server_event!(Kick         => KickArgs,         "KICK",         "", 0 sender: "Sender", 1 command: "\"KICK\"",        2 channel: "Channel",       3 target: "Target";         eol 4 reason: "Reason");
+server_event!(Kill         => KillArgs,         "KILL",         "", 0 sender: "Sender", 1 command: "\"KILL\"",        2 target: "Target";   eol 3 reason: "Reason");
+server_event!(Mode         => ModeArgs,         "MODE",         "", 0 sender: "Sender", 1 command: "\"MODE\"",        2 target: "Target",         3 modes: "Modes";           eol 4 arguments: "Arguments");
+server_event!(Nick         => NickArgs,         "NICK",         "", 0 sender: "Sender", 1 command: "\"NICK\"";         eol 2 nick: "Nickname");
+server_event!(Notice       => NoticeArgs,       "NOTICE",       "", 0 sender: "Sender", 1 command: "\"NOTICE\"",      2 target: "Target";   eol 3 text: "Text");
+server_event!(Part         => PartArgs,         "PART",         "", 0 sender: "Sender", 1 command: "\"PART\"",        2 channel: "Channel"; eol 3 reason: "Reason");
+server_event!(Ping         => PingArgs,         "PING",         "", 0 sender: "Sender", 1 command: "\"PING\"";         eol 2 server: "Server");
+server_event!(Pong         => PongArgs,         "PONG",         "", 0 sender: "Sender", 1 command: "\"PONG\"",        2 server: "Server";   eol 3 timestamp: "Timestamp");
+server_event!(Privmsg      => PrivmsgArgs,      "PRIVMSG",      "", 0 sender: "Sender", 1 command: "\"PRIVMSG\"",     2 target: "Target";   eol 3 text: "Text");
+server_event!(Quit         => QuitArgs,         "QUIT",         "", 0 sender: "Sender", 1 command: "\"QUIT\"";         eol 2 reason: "Reason");
+server_event!(Topic        => TopicArgs,        "TOPIC",        "", 0 sender: "Sender", 1 command: "\"TOPIC\"",       2 target: "Target";   eol 3 topic: "Topic");
+server_event!(Wallops      => WallopsArgs,      "WALLOPS",      "", 0 sender: "Sender", 1 command: "\"WALLOPS\"";      eol 2 text: "Text");
+
+/// Information about every server event in this module, not including [special](crate::event::server::special) events.
+pub const ALL: &[crate::event::EventInfo] = &[
+    Account::INFO,
+    Authenticate::INFO,
+    Away::INFO,
+    Cap::INFO,
+    Chghost::INFO,
+    Invite::INFO,
+    Join::INFO,
+    Kick::INFO,
+    Kill::INFO,
+    Mode::INFO,
+    Nick::INFO,
+    Notice::INFO,
+    Part::INFO,
+    Ping::INFO,
+    Pong::INFO,
+    Privmsg::INFO,
+    Quit::INFO,
+    Topic::INFO,
+    Wallops::INFO,
+];
@@ -1,8 +1,11 @@
 server_event!(
-    RawLine,
+    RawLine => RawLineArgs,
     "RAW LINE",
-    "Every line that comes from the IRC server.",
-    0: "Sender",
-    1: "Command";
-    eol 2: "Arguments"
+    "Every line that comes from the IRC server. The arguments are the untruncated rest of the line after the command.",
+    0 sender: "Sender",
+    1 command: "Command";
+    eol 2 arguments: "Arguments"
 );
+
+/// Information about every special server event.
+pub const ALL: &[crate::event::EventInfo] = &[RawLine::INFO];
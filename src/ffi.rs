@@ -1,11 +1,14 @@
+use std::borrow::Borrow;
+use std::cell::RefCell;
 use std::ffi::CStr;
 use std::marker::PhantomData;
 use std::os::raw::{c_char, c_int};
 use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use time::OffsetDateTime;
 
-use crate::str::HexStr;
+use crate::str::{HexStr, HexString};
 
 #[allow(missing_debug_implementations, missing_docs, unreachable_pub)]
 mod binding;
@@ -43,8 +46,61 @@ pub(crate) fn result_to_int(res: Result<(), ()>) -> c_int {
     }
 }
 
+/// Whether invalid UTF-8 from HexChat is decoded lossily, instead of panicking.
+static LOSSY_UTF8: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_lossy_utf8(lossy: bool) {
+    LOSSY_UTF8.store(lossy, Ordering::Relaxed);
+}
+
+/// Storage for strings from HexChat which were decoded lossily, because they were not valid UTF-8.
+///
+/// Strings which are valid UTF-8 are borrowed directly, so this only allocates for invalid strings.
+#[derive(Debug, Default)]
+pub(crate) struct LossyArena {
+    strings: RefCell<Vec<HexString>>,
+}
+
+impl LossyArena {
+    /// Converts a string from HexChat to a `HexStr`.
+    ///
+    /// # Panics
+    ///
+    /// If `cstr` is not valid UTF-8 and lossy decoding is not enabled. `source` is included in the panic message.
+    pub(crate) fn hexstr<'a>(&'a self, cstr: &'a CStr, source: &str) -> &'a HexStr {
+        self.hexstr_with(cstr, source, LOSSY_UTF8.load(Ordering::Relaxed))
+    }
+
+    /// Converts a string from HexChat to a `HexStr`, decoding invalid UTF-8 lossily if `lossy` is set.
+    ///
+    /// # Panics
+    ///
+    /// If `cstr` is not valid UTF-8 and `lossy` is not set. `source` is included in the panic message.
+    fn hexstr_with<'a>(&'a self, cstr: &'a CStr, source: &str, lossy: bool) -> &'a HexStr {
+        match HexStr::from_cstr(cstr) {
+            Ok(str) => str,
+            Err(e) if !lossy => {
+                panic!("Invalid UTF8 from `{}` in {:?}: {}", source, cstr, e)
+            }
+            Err(_) => {
+                let mut string = String::from_utf8_lossy(cstr.to_bytes()).into_owned();
+                string.push('\0');
+                // Safety: `string` came from a `CStr`, so it has no interior null bytes, and we added a trailing null byte
+                let string = unsafe { HexString::from_null_terminated_string(string) };
+                let str: *const HexStr = Borrow::<HexStr>::borrow(&string);
+                self.strings.borrow_mut().push(string);
+                // Safety: the string's heap allocation does not move when the `HexString` is moved,
+                //         and strings are never removed from the arena, so it lives as long as `self`
+                unsafe { &*str }
+            }
+        }
+    }
+}
+
 /// Converts `word` or `word_eol` to an iterator over `&HexStr`.
 ///
+/// Invalid UTF-8 is decoded into `arena` if lossy decoding is enabled.
+///
 /// # Safety
 ///
 /// `word` must be a `word` or `word_eol` pointer from HexChat.
@@ -53,6 +109,7 @@ pub(crate) fn result_to_int(res: Result<(), ()>) -> c_int {
 #[allow(clippy::trivially_copy_pass_by_ref)]
 pub(crate) unsafe fn word_to_iter<'a>(
     word: &'a *mut *mut c_char,
+    arena: &'a LossyArena,
 ) -> impl Iterator<Item = &'a HexStr> {
     // make it obvious that this is a non-raw-pointer deref
     let word: *mut *mut c_char = *word;
@@ -63,6 +120,7 @@ pub(crate) unsafe fn word_to_iter<'a>(
 
     struct WordIter<'a> {
         word: *mut *mut c_char,
+        arena: &'a LossyArena,
         _lifetime: PhantomData<&'a *mut c_char>,
     }
 
@@ -80,10 +138,7 @@ pub(crate) unsafe fn word_to_iter<'a>(
                 // Safety: word points to valid strings; words does not outlive 'a
                 let str = unsafe { CStr::from_ptr::<'a>(elem) };
 
-                let str = HexStr::from_cstr(str)
-                    .unwrap_or_else(|e| panic!("Invalid UTF8 in {:?}: {}", str, e));
-
-                Some(str)
+                Some(self.arena.hexstr(str, "word"))
             }
         }
 
@@ -105,16 +160,44 @@ pub(crate) unsafe fn word_to_iter<'a>(
 
     WordIter::<'a> {
         word,
+        arena,
         _lifetime: PhantomData,
     }
 }
 
+/// Collects `word` into a slice and passes it to `f`.
+///
+/// The slice is padded with empty strings to at least 32 elements, so indexing missing arguments does not panic.
+/// If there are more than 32 words, all of them are included.
+pub(crate) fn with_padded_words<'a, R>(
+    word: impl Iterator<Item = &'a HexStr>,
+    f: impl FnOnce(&[&'a HexStr]) -> R,
+) -> R {
+    let mut word = word.fuse();
+    let mut words = [HexStr::EMPTY; 32];
+
+    for (ws, w) in words.iter_mut().zip(&mut word) {
+        *ws = w;
+    }
+
+    match word.next() {
+        None => f(&words),
+        Some(next) => {
+            let mut words = words.to_vec();
+            words.push(next);
+            words.extend(word);
+            f(&words)
+        }
+    }
+}
+
 #[allow(unreachable_pub)]
 #[derive(Debug)]
 pub struct ListElem<'a> {
     raw: RawPluginHandle<'a>,
     /// Always points to a valid list element.
     list_ptr: NonNull<hexchat_list>,
+    arena: LossyArena,
 }
 
 impl<'a> ListElem<'a> {
@@ -130,7 +213,11 @@ impl<'a> ListElem<'a> {
     /// but may also include other operations (e.g. switching channels). To be safe, do not call
     /// any HexChat functions while a `ListElem` exists.
     pub(crate) unsafe fn new(raw: RawPluginHandle<'a>, list_ptr: NonNull<hexchat_list>) -> Self {
-        Self { raw, list_ptr }
+        Self {
+            raw,
+            list_ptr,
+            arena: LossyArena::default(),
+        }
     }
 
     pub(crate) fn string<'elem>(&'elem self, name: &CStr) -> Option<&'elem HexStr> {
@@ -147,10 +234,18 @@ impl<'a> ListElem<'a> {
         // Safety: hexchat_list_str gets a valid string or null, temporary does not outlive the list elem
         let str = unsafe { CStr::from_ptr(ptr) };
 
-        let str = HexStr::from_cstr(str)
-            .unwrap_or_else(|e| panic!("Invalid UTF8 from `hexchat_list_str` in {:?}: {}", str, e));
+        Some(self.arena.hexstr(str, "hexchat_list_str"))
+    }
 
-        Some(str)
+    /// Reads the `context` field, which `hexchat_list_str` returns as a context pointer rather than a string.
+    pub(crate) fn context(&self) -> Option<NonNull<hexchat_context>> {
+        // Safety: list_ptr is valid per ListElem precondition, name is a null-terminated string
+        let ptr = unsafe {
+            self.raw
+                .hexchat_list_str(self.list_ptr.as_ptr(), c"context".as_ptr())
+        };
+
+        NonNull::new(ptr as *mut hexchat_context)
     }
 
     pub(crate) fn int(&self, name: &CStr) -> i32 {
@@ -168,7 +263,79 @@ impl<'a> ListElem<'a> {
                 .hexchat_list_time(self.list_ptr.as_ptr(), name.as_ptr())
         };
 
-        OffsetDateTime::from_unix_timestamp(time)
-            .unwrap_or_else(|e| panic!("Invalid timestamp from `hexchat_list_time`: {}", e))
+        timestamp_to_datetime(time, "hexchat_list_time")
+    }
+}
+
+/// Converts a timestamp from HexChat to `OffsetDateTime`.
+///
+/// Out-of-range timestamps (e.g. from a misbehaving server) are replaced with the Unix epoch,
+/// and a warning mentioning `source` is logged, so that they don't cause a panic.
+pub(crate) fn timestamp_to_datetime(timestamp: i64, source: &str) -> OffsetDateTime {
+    OffsetDateTime::from_unix_timestamp(timestamp).unwrap_or_else(|e| {
+        eprintln!(
+            "WARNING: `hexavalent` replaced invalid timestamp from `{}` with the Unix epoch: {}",
+            source, e
+        );
+        OffsetDateTime::UNIX_EPOCH
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp_out_of_range() {
+        assert_eq!(timestamp_to_datetime(86400, "test").unix_timestamp(), 86400);
+        assert_eq!(
+            timestamp_to_datetime(i64::MAX, "test"),
+            OffsetDateTime::UNIX_EPOCH
+        );
+        assert_eq!(
+            timestamp_to_datetime(i64::MIN, "test"),
+            OffsetDateTime::UNIX_EPOCH
+        );
+    }
+
+    #[test]
+    fn padded_words_short() {
+        let a = HexStr::from_cstr(c"a").unwrap();
+        with_padded_words([a, a].into_iter(), |words| {
+            assert_eq!(words.len(), 32);
+            assert_eq!(words[1], a);
+            assert_eq!(words[2], HexStr::EMPTY);
+        });
+    }
+
+    #[test]
+    fn padded_words_long() {
+        let a = HexStr::from_cstr(c"a").unwrap();
+        let b = HexStr::from_cstr(c"b").unwrap();
+        let word = [a; 32].into_iter().chain([b, b]);
+        with_padded_words(word, |words| {
+            assert_eq!(words.len(), 34);
+            assert_eq!(words[31], a);
+            assert_eq!(words[33], b);
+        });
+    }
+
+    #[test]
+    fn lossy_arena() {
+        let arena = LossyArena::default();
+        assert_eq!(arena.hexstr_with(c"abc", "test", true).as_str(), "abc");
+        assert!(arena.strings.borrow().is_empty());
+
+        let str = arena.hexstr_with(c"a\xffb", "test", true);
+        assert_eq!(arena.strings.borrow().len(), 1);
+        assert_eq!(str.as_str(), "a\u{FFFD}b");
+        assert_eq!(str.as_bytes_with_nul(), "a\u{FFFD}b\0".as_bytes());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid UTF8 from `test`")]
+    fn lossy_arena_strict() {
+        let arena = LossyArena::default();
+        arena.hexstr_with(c"a\xffb", "test", false);
     }
 }
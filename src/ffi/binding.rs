@@ -3,7 +3,6 @@
 #![allow(dead_code)]
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
-#![allow(missing_docs)]
 use libc::time_t;
 
 pub const HEXCHAT_PRI_HIGHEST: u32 = 127;
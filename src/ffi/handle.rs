@@ -115,6 +115,12 @@ impl RawPluginHandle<'_> {
         }
     }
 
+    // `hexchat_hook_fd` and `hexchat_read_fd` are intentionally not wrapped.
+    // `hexchat_read_fd` reads from the `GIOChannel` that HexChat creates internally for an fd hook,
+    // but fd hook callbacks only receive the raw fd, so plugins have no way to get a valid `src` pointer.
+    // Without `hexchat_read_fd`, an fd hook is no more useful than reading the socket directly from a thread
+    // (see `crate::thread::MainThreadQueue`), so neither is exposed.
+
     pub(crate) unsafe fn hexchat_unhook(self, hook: *mut hexchat_hook) -> *mut c_void {
         // Safety: forwarded to caller
         unsafe { ((*self.handle.as_ptr()).hexchat_unhook)(self.handle.as_ptr(), hook) }
@@ -183,6 +189,11 @@ impl RawPluginHandle<'_> {
         unsafe { ((*self.handle.as_ptr()).hexchat_list_free)(self.handle.as_ptr(), xlist) }
     }
 
+    pub(crate) unsafe fn hexchat_list_fields(self, name: *const c_char) -> *const *const c_char {
+        // Safety: forwarded to caller
+        unsafe { ((*self.handle.as_ptr()).hexchat_list_fields)(self.handle.as_ptr(), name) }
+    }
+
     pub(crate) unsafe fn hexchat_list_next(self, xlist: *mut hexchat_list) -> c_int {
         // Safety: forwarded to caller
         unsafe { ((*self.handle.as_ptr()).hexchat_list_next)(self.handle.as_ptr(), xlist) }
@@ -241,6 +252,14 @@ impl RawPluginHandle<'_> {
         a4: *const c_char,
         a5: *const c_char,
     ) -> c_int {
+        #[cfg(feature = "testing")]
+        // Safety: forwarded to caller
+        if let Some(ret) = unsafe {
+            crate::testing::intercept_emit_print(self.handle, event_name, [a1, a2, a3, a4, a5])
+        } {
+            return ret;
+        }
+
         // Safety: forwarded to caller
         unsafe {
             ((*self.handle.as_ptr()).hexchat_emit_print)(
@@ -405,6 +424,14 @@ impl RawPluginHandle<'_> {
         a4: *const c_char,
         a5: *const c_char,
     ) -> c_int {
+        #[cfg(feature = "testing")]
+        // Safety: forwarded to caller
+        if let Some(ret) = unsafe {
+            crate::testing::intercept_emit_print(self.handle, event_name, [a1, a2, a3, a4, a5])
+        } {
+            return ret;
+        }
+
         // Safety: forwarded to caller
         unsafe {
             ((*self.handle.as_ptr()).hexchat_emit_print_attrs)(
@@ -0,0 +1,368 @@
+//! Adding mIRC formatting to text.
+//!
+//! This is the inverse of [`strip`](crate::strip): it inserts formatting codes, e.g. before passing text to
+//! [`PluginHandle::emit_print`](crate::PluginHandle::emit_print).
+//!
+//! This module also contains [`truncate`], for limiting the length of text before it is printed.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use crate::str::{HexString, HexStringBuilder};
+
+const COLOR: char = '\x03';
+const RESET: char = '\x0f';
+
+/// An mIRC color.
+///
+/// Used with [`highlight`].
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MircColor {
+    /// Color `0`.
+    White = 0,
+    /// Color `1`.
+    Black = 1,
+    /// Color `2`.
+    Blue = 2,
+    /// Color `3`.
+    Green = 3,
+    /// Color `4`.
+    Red = 4,
+    /// Color `5`.
+    Brown = 5,
+    /// Color `6`.
+    Purple = 6,
+    /// Color `7`.
+    Orange = 7,
+    /// Color `8`.
+    Yellow = 8,
+    /// Color `9`.
+    LightGreen = 9,
+    /// Color `10`.
+    Cyan = 10,
+    /// Color `11`.
+    LightCyan = 11,
+    /// Color `12`.
+    LightBlue = 12,
+    /// Color `13`.
+    Pink = 13,
+    /// Color `14`.
+    Grey = 14,
+    /// Color `15`.
+    LightGrey = 15,
+}
+
+/// A foreground and optional background color set by a `\x03` code.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct ColorState {
+    fg: u8,
+    bg: Option<u8>,
+}
+
+impl ColorState {
+    /// Writes the color code, always using two digits so that following digits cannot be mistaken for part of the code.
+    ///
+    /// Following text starting with `,` could still be mistaken for a background color; see [`write_separator`].
+    fn write_to(self, out: &mut HexStringBuilder) {
+        let _ = match self.bg {
+            Some(bg) => write!(out, "{}{:02},{:02}", COLOR, self.fg, bg),
+            None => write!(out, "{}{:02}", COLOR, self.fg),
+        };
+    }
+}
+
+/// Writes an empty pair of bold toggles if `next` could be mistaken for part of the color code `code`,
+/// where `None` is a bare `\x03` reset.
+///
+/// A bare reset would consume following digits, and a code with no background would consume a following `,` and digits.
+/// The toggles display nothing, but end the color code.
+fn write_separator(out: &mut HexStringBuilder, code: Option<ColorState>, next: &str) {
+    let ambiguous = match code {
+        None => next.starts_with(|c: char| c.is_ascii_digit() || c == ','),
+        Some(ColorState { bg: None, .. }) => next.starts_with(','),
+        Some(ColorState { bg: Some(_), .. }) => false,
+    };
+    if ambiguous {
+        out.push_str("\x02\x02");
+    }
+}
+
+/// Colors each match of the given keywords in `text`.
+///
+/// Keywords are matched literally, ignoring ASCII case. Where matches overlap, the one which starts first is used;
+/// if several start at the same position, the one listed first in `keywords` is used.
+///
+/// Existing color codes in `text` are preserved: after each match, the color that was active before it is restored.
+/// Keywords are only matched in text between formatting codes, so they never match inside a color code.
+/// If the text after an inserted color code starts with a digit or `,`, which HexChat would read as part of the code,
+/// an empty pair of bold toggles (`\x02\x02`) is inserted to separate them.
+///
+/// # Panics
+///
+/// If `text` contains a null byte.
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::format::{highlight, MircColor};
+///
+/// let text = highlight("hello Alice and bob", &[("alice", MircColor::Red), ("bob", MircColor::Blue)]);
+/// assert_eq!(text.as_str(), "hello \x0304Alice\x03 and \x0302bob\x03");
+///
+/// // the existing green color is restored after the match
+/// let text = highlight("\x033green alice text", &[("alice", MircColor::Red)]);
+/// assert_eq!(text.as_str(), "\x033green \x0304alice\x03\x0303 text");
+/// ```
+pub fn highlight(text: &str, keywords: &[(&str, MircColor)]) -> HexString {
+    let mut out = HexStringBuilder::with_capacity(text.len());
+    let mut color = None;
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let plain_len = rest
+            .find(|c: char| c.is_ascii_control() && c != '\t')
+            .unwrap_or(rest.len());
+        let (plain, after) = rest.split_at(plain_len);
+        highlight_plain(&mut out, plain, keywords, color);
+
+        let mut chars = after.chars();
+        rest = match chars.next() {
+            None => "",
+            Some(COLOR) => {
+                let (state, code_len) = parse_color_code(chars.as_str());
+                color = state;
+                let (code, rest) = after.split_at(1 + code_len);
+                out.push_str(code);
+                rest
+            }
+            Some(c) => {
+                if c == RESET {
+                    color = None;
+                }
+                out.push(c);
+                chars.as_str()
+            }
+        };
+    }
+
+    out.build()
+}
+
+/// A conservative limit on the length of a printed line, in bytes.
+///
+/// HexChat does not limit the length of printed lines, but very long lines are slow to wrap and render,
+/// and multi-megabyte lines can hang the GUI. This is much longer than any line received from an IRC server (512 bytes),
+/// so it only affects text from other sources, such as files or web APIs.
+///
+/// Used with [`PluginHandle::print_truncated`](crate::PluginHandle::print_truncated).
+pub const MAX_PRINT_BYTES: usize = 8192;
+
+/// Appended to text shortened by [`truncate`].
+const ELLIPSIS: &str = "\u{2026}";
+
+/// Shortens `text` to at most `max_bytes` bytes, ending in an ellipsis (`…`) if anything was removed.
+///
+/// Text is cut at a character boundary, so the result is always valid UTF-8.
+/// The ellipsis counts towards `max_bytes`, unless `max_bytes` is too small to fit it.
+///
+/// Used by [`PluginHandle::print_truncated`](crate::PluginHandle::print_truncated).
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::format::truncate;
+///
+/// assert_eq!(truncate("short", 10), "short");
+/// assert_eq!(truncate("a longer line", 10), "a longe\u{2026}");
+/// assert_eq!(truncate("caf\u{e9} au lait", 7), "caf\u{2026}");
+/// ```
+pub fn truncate(text: &str, max_bytes: usize) -> Cow<'_, str> {
+    if text.len() <= max_bytes {
+        return Cow::Borrowed(text);
+    }
+
+    let (keep, ellipsis) = match max_bytes.checked_sub(ELLIPSIS.len()) {
+        Some(keep) => (keep, ELLIPSIS),
+        None => (max_bytes, ""),
+    };
+
+    let mut end = keep;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    let mut truncated = String::with_capacity(end + ellipsis.len());
+    truncated.push_str(&text[..end]);
+    truncated.push_str(ellipsis);
+    Cow::Owned(truncated)
+}
+
+/// Highlights keywords in text which contains no formatting codes.
+fn highlight_plain(
+    out: &mut HexStringBuilder,
+    mut plain: &str,
+    keywords: &[(&str, MircColor)],
+    color: Option<ColorState>,
+) {
+    while let Some((start, len, keyword_color)) = first_match(plain, keywords) {
+        out.push_str(&plain[..start]);
+        let keyword_code = ColorState {
+            fg: keyword_color as u8,
+            bg: None,
+        };
+        keyword_code.write_to(out);
+        let keyword = &plain[start..start + len];
+        write_separator(out, Some(keyword_code), keyword);
+        out.push_str(keyword);
+        out.push(COLOR);
+        if let Some(color) = color {
+            color.write_to(out);
+        }
+        plain = &plain[start + len..];
+        write_separator(out, color, plain);
+    }
+    out.push_str(plain);
+}
+
+/// Finds the earliest match of any keyword, returning its start, length, and color.
+fn first_match(text: &str, keywords: &[(&str, MircColor)]) -> Option<(usize, usize, MircColor)> {
+    let bytes = text.as_bytes();
+    for start in (0..text.len()).filter(|&i| text.is_char_boundary(i)) {
+        for &(keyword, color) in keywords {
+            let keyword = keyword.as_bytes();
+            if keyword.is_empty() {
+                continue;
+            }
+            if let Some(candidate) = bytes.get(start..start + keyword.len()) {
+                if candidate.eq_ignore_ascii_case(keyword) {
+                    return Some((start, keyword.len(), color));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Parses the digits following a `\x03` byte, returning the color state and the length of the digits.
+///
+/// A `\x03` with no digits resets the color.
+fn parse_color_code(s: &str) -> (Option<ColorState>, usize) {
+    fn digits(s: &str) -> (Option<u8>, usize) {
+        let len = s.bytes().take(2).take_while(u8::is_ascii_digit).count();
+        (s[..len].parse().ok(), len)
+    }
+
+    let (fg, fg_len) = digits(s);
+    let fg = match fg {
+        Some(fg) => fg,
+        None => return (None, 0),
+    };
+
+    let after_fg = &s[fg_len..];
+    if let Some(after_comma) = after_fg.strip_prefix(',') {
+        if let (Some(bg), bg_len) = digits(after_comma) {
+            return (Some(ColorState { fg, bg: Some(bg) }), fg_len + 1 + bg_len);
+        }
+    }
+
+    (Some(ColorState { fg, bg: None }), fg_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_at_char_boundary() {
+        assert_eq!(truncate("", 0), "");
+        assert_eq!(truncate("exactly", 7), "exactly");
+        assert_eq!(truncate("abcdef", 4), "a\u{2026}");
+        assert_eq!(truncate("abcdef", 2), "ab");
+        // `é` is 2 bytes, so cutting in the middle of it drops the whole character
+        assert_eq!(truncate("\u{e9}\u{e9}\u{e9}", 4), "\u{2026}");
+        assert_eq!(truncate("\u{e9}\u{e9}\u{e9}", 5), "\u{e9}\u{2026}");
+        assert_eq!(truncate("\u{e9}\u{e9}", 1), "");
+    }
+
+    #[test]
+    fn parses_color_codes() {
+        assert_eq!(
+            parse_color_code("4text"),
+            (Some(ColorState { fg: 4, bg: None }), 1)
+        );
+        assert_eq!(
+            parse_color_code("04,12text"),
+            (
+                Some(ColorState {
+                    fg: 4,
+                    bg: Some(12)
+                }),
+                5
+            )
+        );
+        assert_eq!(
+            parse_color_code("123"),
+            (Some(ColorState { fg: 12, bg: None }), 2)
+        );
+        assert_eq!(
+            parse_color_code("4,text"),
+            (Some(ColorState { fg: 4, bg: None }), 1)
+        );
+        assert_eq!(parse_color_code("text"), (None, 0));
+    }
+
+    #[test]
+    fn overlapping_matches_take_first() {
+        let keywords = [("cde", MircColor::Blue), ("abcd", MircColor::Red)];
+        assert_eq!(highlight("abcdef", &keywords).as_str(), "\x0304abcd\x03ef");
+
+        let keywords = [("ab", MircColor::Blue), ("abcd", MircColor::Red)];
+        assert_eq!(highlight("abcdef", &keywords).as_str(), "\x0302ab\x03cdef");
+    }
+
+    #[test]
+    fn restores_colors_and_skips_codes() {
+        let keywords = [("12", MircColor::Red)];
+        assert_eq!(
+            highlight("\x0312,01x 12", &keywords).as_str(),
+            "\x0312,01x \x030412\x03\x0312,01"
+        );
+        assert_eq!(
+            highlight("\x034a\x0f 12", &keywords).as_str(),
+            "\x034a\x0f \x030412\x03"
+        );
+    }
+
+    #[test]
+    fn separates_digits_after_codes() {
+        let keywords = [("bob", MircColor::Blue)];
+        assert_eq!(
+            highlight("bob1", &keywords).as_str(),
+            "\x0302bob\x03\x02\x021"
+        );
+        assert_eq!(
+            highlight("\x033bob,5", &keywords).as_str(),
+            "\x033\x0302bob\x03\x0303\x02\x02,5"
+        );
+        assert_eq!(
+            highlight("\x033,4bob,5", &keywords).as_str(),
+            "\x033,4\x0302bob\x03\x0303,04,5"
+        );
+
+        let keywords = [(",1", MircColor::Red)];
+        assert_eq!(
+            highlight("a,1", &keywords).as_str(),
+            "a\x0304\x02\x02,1\x03"
+        );
+    }
+
+    #[test]
+    fn ignores_ascii_case_and_handles_unicode() {
+        let keywords = [("ÄBC", MircColor::Green)];
+        assert_eq!(
+            highlight("xäbc ÄbC", &keywords).as_str(),
+            "xäbc \x0303ÄbC\x03"
+        );
+    }
+}
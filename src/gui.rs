@@ -1,14 +1,18 @@
 //! Fake plugins.
 
 use std::ffi::c_void;
+use std::fmt::{self, Debug, Formatter};
+use std::mem::ManuallyDrop;
 use std::ptr::NonNull;
 
+use crate::PluginHandle;
+
 /// A handle to a fake plugin in HexChat.
 ///
 /// Returned from [`PluginHandle::plugingui_add`](crate::PluginHandle::plugingui_add).
 ///
 /// Must be passed to [`PluginHandle::plugingui_remove`](crate::PluginHandle::plugingui_remove)
-/// to remove the fake plugin.
+/// to remove the fake plugin. To remove it automatically, use [`PluginHandle::plugingui_add_scoped`](crate::PluginHandle::plugingui_add_scoped) instead.
 #[must_use = "fake plugins are not removed automatically, you must call `plugingui_remove` yourself"]
 #[derive(Debug)]
 pub struct FakePluginHandle {
@@ -33,3 +37,59 @@ impl FakePluginHandle {
         self.handle
     }
 }
+
+/// A fake plugin in HexChat which is removed when this guard is dropped.
+///
+/// Returned from [`PluginHandle::plugingui_add_scoped`](crate::PluginHandle::plugingui_add_scoped).
+///
+/// The guard holds the [`PluginHandle`] that added the fake plugin, so it cannot outlive the callback it was created in.
+/// To keep a fake plugin across callbacks, use [`FakePluginGuard::into_handle`]
+/// and pass the handle to [`PluginHandle::plugingui_remove`](crate::PluginHandle::plugingui_remove) later.
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::PluginHandle;
+///
+/// fn run_script<P>(ph: PluginHandle<'_, P>, filename: &str) {
+///     let _gui = ph.plugingui_add_scoped(filename, "my script", "a script", "1.0.0");
+///     // ... run the script; the fake plugin is removed when `_gui` is dropped
+/// }
+/// ```
+#[must_use = "the fake plugin is removed immediately if the guard is dropped"]
+pub struct FakePluginGuard<'ph, P: 'static> {
+    ph: PluginHandle<'ph, P>,
+    handle: ManuallyDrop<FakePluginHandle>,
+}
+
+impl<'ph, P> FakePluginGuard<'ph, P> {
+    pub(crate) fn new(ph: PluginHandle<'ph, P>, handle: FakePluginHandle) -> Self {
+        Self {
+            ph,
+            handle: ManuallyDrop::new(handle),
+        }
+    }
+
+    /// Converts this guard into a [`FakePluginHandle`], so the fake plugin is no longer removed on drop.
+    pub fn into_handle(self) -> FakePluginHandle {
+        let mut this = ManuallyDrop::new(self);
+        // Safety: `this` is never dropped, so `handle` is not used again
+        unsafe { ManuallyDrop::take(&mut this.handle) }
+    }
+}
+
+impl<P> Drop for FakePluginGuard<'_, P> {
+    fn drop(&mut self) {
+        // Safety: this is the last use of `handle`, since we are being dropped
+        let handle = unsafe { ManuallyDrop::take(&mut self.handle) };
+        self.ph.plugingui_remove(handle);
+    }
+}
+
+impl<P> Debug for FakePluginGuard<'_, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FakePluginGuard")
+            .field("handle", &*self.handle)
+            .finish()
+    }
+}
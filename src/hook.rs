@@ -1,5 +1,8 @@
 //! Hook callbacks.
 
+use std::error::Error;
+use std::fmt;
+use std::ops::ControlFlow;
 use std::ptr::NonNull;
 
 use crate::ffi::hexchat_hook;
@@ -7,6 +10,8 @@ use crate::ffi::{
     HEXCHAT_EAT_ALL, HEXCHAT_EAT_HEXCHAT, HEXCHAT_EAT_NONE, HEXCHAT_EAT_PLUGIN, HEXCHAT_PRI_HIGH,
     HEXCHAT_PRI_HIGHEST, HEXCHAT_PRI_LOW, HEXCHAT_PRI_LOWEST, HEXCHAT_PRI_NORM,
 };
+use crate::state::{is_live_hook, register_live_hook};
+use crate::str::{HexStr, HexString};
 
 /// Determines the order in which hook callbacks are called.
 ///
@@ -19,26 +24,88 @@ pub enum Priority {
     /// Callbacks with the lowest priority run after callbacks with any other priority.
     ///
     /// Analogous to [`HEXCHAT_PRI_LOWEST`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.HEXCHAT_PRI_LOWEST).
-    Lowest = HEXCHAT_PRI_LOWEST as isize,
+    Lowest,
     /// Analogous to [`HEXCHAT_PRI_LOW`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.HEXCHAT_PRI_LOW).
-    Low = HEXCHAT_PRI_LOW as isize,
+    Low,
     /// Most callbacks should use normal priority.
     ///
     /// Analogous to [`HEXCHAT_PRI_NORM`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.HEXCHAT_PRI_NORM).
-    Normal = HEXCHAT_PRI_NORM as isize,
+    Normal,
     /// Analogous to [`HEXCHAT_PRI_HIGH`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.HEXCHAT_PRI_HIGH).
-    High = HEXCHAT_PRI_HIGH as isize,
+    High,
     /// Callbacks with the highest priority run before callbacks with any other priority.
     ///
     /// Analogous to [`HEXCHAT_PRI_HIGHEST`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.HEXCHAT_PRI_HIGHEST).
-    Highest = HEXCHAT_PRI_HIGHEST as isize,
+    Highest,
+    /// A priority between the named priorities, for ordering relative to another plugin's callbacks.
+    ///
+    /// Higher values run first. The named priorities span the full range of `i8`,
+    /// from `Lowest` (-128) to `Highest` (127).
+    Custom(i8),
+}
+
+impl Priority {
+    /// Creates a priority from its numeric value.
+    ///
+    /// Values which correspond to one of the named priorities are converted to that priority.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::hook::Priority;
+    ///
+    /// // runs just before other plugins' `Priority::High` callbacks
+    /// let priority = Priority::from_raw(65);
+    /// assert!(matches!(priority, Priority::Custom(65)));
+    ///
+    /// assert!(matches!(Priority::from_raw(64), Priority::High));
+    /// ```
+    pub fn from_raw(priority: i8) -> Self {
+        [
+            Self::Lowest,
+            Self::Low,
+            Self::Normal,
+            Self::High,
+            Self::Highest,
+        ]
+        .into_iter()
+        .find(|named| named.as_raw() == priority)
+        .unwrap_or(Self::Custom(priority))
+    }
+
+    /// Gets the numeric value of this priority.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::hook::Priority;
+    ///
+    /// assert_eq!(Priority::Normal.as_raw(), 0);
+    /// assert_eq!(Priority::Custom(-5).as_raw(), -5);
+    /// ```
+    pub fn as_raw(self) -> i8 {
+        let raw = match self {
+            Self::Lowest => HEXCHAT_PRI_LOWEST as isize,
+            Self::Low => HEXCHAT_PRI_LOW as isize,
+            Self::Normal => HEXCHAT_PRI_NORM as isize,
+            Self::High => HEXCHAT_PRI_HIGH as isize,
+            Self::Highest => HEXCHAT_PRI_HIGHEST as isize,
+            Self::Custom(priority) => return priority,
+        };
+        // all named priorities are in the range of `i8`
+        raw as i8
+    }
 }
 
 /// Whether the event that triggered a hook callback should be "eaten".
 ///
 /// Used with hook registration functions such as [`PluginHandle::hook_command`](crate::PluginHandle::hook_command).
+///
+/// Values are ordered by how much they eat: `None` eats nothing, `All` eats everything,
+/// and `HexChat` and `Plugin` each eat one half.
+/// Use [`Eat::or`] to combine the results of several checks.
 #[non_exhaustive]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Eat {
     /// Let this event continue uneaten.
     ///
@@ -58,6 +125,81 @@ pub enum Eat {
     All = HEXCHAT_EAT_ALL as isize,
 }
 
+impl Eat {
+    /// Combines two `Eat` values, eating the event from everything that either value eats it from.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::hook::Eat;
+    ///
+    /// assert_eq!(Eat::None.or(Eat::Plugin), Eat::Plugin);
+    /// assert_eq!(Eat::HexChat.or(Eat::Plugin), Eat::All);
+    /// assert_eq!(Eat::All.or(Eat::None), Eat::All);
+    /// ```
+    pub fn or(self, other: Self) -> Self {
+        let eat_hexchat = self.eats_hexchat() || other.eats_hexchat();
+        let eat_plugin = self.eats_plugin() || other.eats_plugin();
+        match (eat_hexchat, eat_plugin) {
+            (false, false) => Self::None,
+            (true, false) => Self::HexChat,
+            (false, true) => Self::Plugin,
+            (true, true) => Self::All,
+        }
+    }
+
+    /// Eats the event from everything if `cond` is true, otherwise lets it continue uneaten.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::event::print::ChannelMessage;
+    /// use hexavalent::hook::{Eat, Priority};
+    ///
+    /// fn hide_bot_messages<P>(ph: PluginHandle<'_, P>) {
+    ///     ph.hook_print(ChannelMessage, Priority::Normal, |plugin, ph, [nick, ..]| {
+    ///         Eat::when(nick.ends_with("bot"))
+    ///     });
+    /// }
+    /// ```
+    pub fn when(cond: bool) -> Self {
+        if cond {
+            Self::All
+        } else {
+            Self::None
+        }
+    }
+
+    fn eats_hexchat(self) -> bool {
+        matches!(self, Self::HexChat | Self::All)
+    }
+
+    fn eats_plugin(self) -> bool {
+        matches!(self, Self::Plugin | Self::All)
+    }
+}
+
+/// An error that occurred while registering a hook.
+///
+/// Returned from fallible hook registration functions such as [`PluginHandle::try_hook_command`](crate::PluginHandle::try_hook_command).
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HookError {
+    /// HexChat did not return a hook handle.
+    Null,
+}
+
+impl fmt::Display for HookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("hook handle was null"),
+        }
+    }
+}
+
+impl Error for HookError {}
+
 /// Whether a timer callback should continue running.
 ///
 /// Used with [`PluginHandle::hook_timer`](crate::PluginHandle::hook_timer).
@@ -71,6 +213,37 @@ pub enum Timer {
     Stop = 0,
 }
 
+/// Converts [`ControlFlow::Continue`] to [`Timer::Continue`], and [`ControlFlow::Break`] to [`Timer::Stop`].
+///
+/// # Examples
+///
+/// ```rust
+/// use std::ops::ControlFlow;
+/// use std::time::Duration;
+/// use hexavalent::PluginHandle;
+/// use hexavalent::hook::Timer;
+///
+/// fn check_connection<P>(ph: PluginHandle<'_, P>) -> ControlFlow<()> {
+///     if ph.current_server_id().is_none() {
+///         return ControlFlow::Break(());
+///     }
+///     ph.command("PING keepalive");
+///     ControlFlow::Continue(())
+/// }
+///
+/// fn add_keepalive_timer<P: 'static>(ph: PluginHandle<'_, P>) {
+///     ph.hook_timer(Duration::from_secs(60), |plugin, ph| Timer::from(check_connection(ph)));
+/// }
+/// ```
+impl From<ControlFlow<(), ()>> for Timer {
+    fn from(flow: ControlFlow<(), ()>) -> Self {
+        match flow {
+            ControlFlow::Continue(()) => Self::Continue,
+            ControlFlow::Break(()) => Self::Stop,
+        }
+    }
+}
+
 /// A handle to a hook registered with HexChat.
 ///
 /// Returned from hook registration functions such as [`PluginHandle::hook_command`](crate::PluginHandle::hook_command).
@@ -128,40 +301,156 @@ pub enum Timer {
 /// ```
 #[derive(Debug)]
 pub struct HookHandle {
-    /// Always points to a valid instance of `hexchat_hook`
+    /// Points to a valid instance of `hexchat_hook` while the hook is registered.
     handle: NonNull<hexchat_hook>,
+    /// Distinguishes this hook from earlier hooks which HexChat allocated at the same address.
+    id: u64,
 }
 
 impl HookHandle {
-    /// Creates a new `HookHandle` from a native `hexchat_hook`.
+    /// Creates a new `HookHandle` from a newly-registered native `hexchat_hook`.
     ///
     /// # Safety
     ///
     /// `hook_handle` must point to a valid instance of `hexchat_hook`.
     ///
     /// This function takes ownership of `hook_handle`; it must not be used afterwards.
+    ///
+    /// # Panics
+    ///
+    /// If not called from within a plugin callback.
     pub(crate) unsafe fn new(hook_handle: NonNull<hexchat_hook>) -> Self {
         Self {
             handle: hook_handle,
+            id: register_live_hook(hook_handle),
         }
     }
 
-    /// Converts this `HookHandle` back into a native `hexchat_hook`.
-    pub(crate) fn into_raw(self) -> NonNull<hexchat_hook> {
-        self.handle
+    /// Gets the native `hexchat_hook`, if the hook is still registered.
+    ///
+    /// # Panics
+    ///
+    /// If not called from within a plugin callback.
+    pub(crate) fn as_live_raw(&self) -> Option<NonNull<hexchat_hook>> {
+        if is_live_hook(self.handle, self.id) {
+            Some(self.handle)
+        } else {
+            None
+        }
+    }
+}
+
+/// A view of hook arguments which distinguishes missing arguments from empty ones.
+///
+/// HexChat pads the words passed to hook callbacks (e.g. [`PluginHandle::hook_command`](crate::PluginHandle::hook_command))
+/// with empty strings, so indexing past the last argument yields `""` rather than panicking.
+/// `ArgsView` treats these trailing empty strings as absent, so [`ArgsView::get`] returns `None` for them.
+///
+/// Note that an argument which is genuinely empty at the end of the list is indistinguishable from padding.
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::PluginHandle;
+/// use hexavalent::hook::{ArgsView, Eat, Priority};
+///
+/// fn add_echo_command<P>(ph: PluginHandle<'_, P>) {
+///     ph.hook_command(c"echo", c"Usage: ECHO [text]", Priority::Normal, |plugin, ph, words| {
+///         let args = ArgsView::new(words);
+///         match args.get(1) {
+///             Some(text) => ph.print(text),
+///             None => ph.print(c"Nothing to echo!"),
+///         }
+///         Eat::All
+///     });
+/// }
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct ArgsView<'a> {
+    args: &'a [&'a HexStr],
+}
+
+impl<'a> ArgsView<'a> {
+    /// Creates a view of `args`, ignoring any trailing empty strings.
+    pub fn new(args: &'a [&'a HexStr]) -> Self {
+        let len = args
+            .iter()
+            .rposition(|arg| !arg.is_empty())
+            .map_or(0, |last| last + 1);
+        Self { args: &args[..len] }
+    }
+
+    /// Gets the argument at `index`, or `None` if it is past the last non-empty argument.
+    pub fn get(&self, index: usize) -> Option<&'a HexStr> {
+        self.args.get(index).copied()
+    }
+
+    /// The number of arguments, up to and including the last non-empty argument.
+    pub fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    /// Whether there are no non-empty arguments.
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    /// Returns the arguments, up to and including the last non-empty argument.
+    pub fn as_slice(&self) -> &'a [&'a HexStr] {
+        self.args
+    }
+
+    /// Copies the arguments into owned strings, so they can be stored after the hook callback returns.
+    pub fn to_owned_vec(&self) -> Vec<HexString> {
+        self.args.iter().map(|&arg| arg.to_owned()).collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::mem;
-
     use super::*;
 
     #[test]
     fn one_byte_enums() {
-        assert_eq!(mem::size_of::<Priority>(), 1);
-        assert_eq!(mem::size_of::<Eat>(), 1);
-        assert_eq!(mem::size_of::<Timer>(), 1);
+        assert_eq!(size_of::<Eat>(), 1);
+        assert_eq!(size_of::<Timer>(), 1);
+    }
+
+    #[test]
+    fn eat_or_matches_bitwise_or() {
+        let all = [Eat::None, Eat::HexChat, Eat::Plugin, Eat::All];
+        for a in all {
+            for b in all {
+                assert_eq!(a.or(b) as i32, a as i32 | b as i32);
+                assert_eq!(a.or(b), b.or(a));
+            }
+        }
+    }
+
+    #[test]
+    fn two_byte_priority() {
+        assert_eq!(size_of::<Priority>(), 2);
+    }
+
+    #[test]
+    fn priority_raw_roundtrip() {
+        for raw in i8::MIN..=i8::MAX {
+            assert_eq!(Priority::from_raw(raw).as_raw(), raw);
+        }
+        assert_eq!(Priority::Lowest.as_raw(), i8::MIN);
+        assert_eq!(Priority::Highest.as_raw(), i8::MAX);
+    }
+
+    #[test]
+    fn args_view_trims_padding() {
+        let a = HexStr::from_cstr(c"a").unwrap();
+        let words = [a, HexStr::EMPTY, a, HexStr::EMPTY, HexStr::EMPTY];
+        let args = ArgsView::new(&words);
+        assert_eq!(args.len(), 3);
+        assert_eq!(args.get(1), Some(HexStr::EMPTY));
+        assert_eq!(args.get(2), Some(a));
+        assert_eq!(args.get(3), None);
+
+        assert!(ArgsView::new(&[HexStr::EMPTY; 32]).is_empty());
     }
 }
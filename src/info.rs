@@ -1,5 +1,7 @@
 //! Context info.
 
+use std::fmt;
+
 use crate::str::{HexStr, HexString};
 
 /// Info about the current [context](crate::PluginHandle::find_context).
@@ -13,7 +15,7 @@ where
 {
     /// The info's type.
     ///
-    /// Can be `String`, or `Option<String>`.
+    /// Can be `HexString`, `Option<HexString>`, or `bool` (for infos which are only checked for presence).
     // todo with GATs, it _might_ be nice to have Type/BorrowedType<'a>, so that we can avoid allocation
     //  (but we'd probably have to make get_info_with unsafe due to invalidation of the string)
     type Type: 'static;
@@ -47,6 +49,13 @@ impl private::FromInfoValue for Option<HexString> {
     }
 }
 
+/// Infos of type `bool` are `true` if HexChat returns a value, regardless of what the value is.
+impl private::FromInfoValue for bool {
+    fn from_info_value(info: Option<&HexStr>) -> Self {
+        info.is_some()
+    }
+}
+
 macro_rules! info {
     ($struct_name:ident, $info_name:literal, $ty:ty, $description:literal) => {
         #[doc = "`"]
@@ -71,6 +80,126 @@ macro_rules! info {
     };
 }
 
+/// A parsed HexChat version number, e.g. `2.16.0`.
+///
+/// Returned from [`PluginHandle::hexchat_version`](crate::PluginHandle::hexchat_version).
+///
+/// Versions are ordered by component, so they can be compared to gate features on newer versions of HexChat.
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::info::HexchatVersion;
+///
+/// let version = HexchatVersion::parse("2.16.1").unwrap();
+/// assert_eq!(version, HexchatVersion::new(2, 16, 1));
+/// assert!(version >= HexchatVersion::new(2, 9, 6));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HexchatVersion {
+    /// The major version.
+    pub major: u32,
+    /// The minor version.
+    pub minor: u32,
+    /// The patch version.
+    pub patch: u32,
+}
+
+impl HexchatVersion {
+    /// Creates a version from its components.
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parses a version string like `"2.16.0"`, as returned by the [`Version`] info.
+    ///
+    /// A missing patch component is treated as `0`,
+    /// and any suffix after the last component (e.g. `"-dev"`) is ignored.
+    ///
+    /// Returns `None` if the string does not start with a valid version.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::info::HexchatVersion;
+    ///
+    /// assert_eq!(HexchatVersion::parse("2.9.6-dev"), Some(HexchatVersion::new(2, 9, 6)));
+    /// assert_eq!(HexchatVersion::parse("2.12"), Some(HexchatVersion::new(2, 12, 0)));
+    /// assert_eq!(HexchatVersion::parse("unknown"), None);
+    /// ```
+    pub fn parse(version: &str) -> Option<Self> {
+        let end = version
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(version.len());
+        let mut parts = version[..end].split('.');
+
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = match parts.next() {
+            Some(patch) => patch.parse().ok()?,
+            None => 0,
+        };
+
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for HexchatVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Connection-related info about the current [context](crate::PluginHandle::find_context), gathered at once.
+///
+/// Returned from [`PluginHandle::connection_info`](crate::PluginHandle::connection_info).
+///
+/// Each field is `None` if HexChat does not provide a value, e.g. when not connected to a server.
+#[non_exhaustive]
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionInfo {
+    /// Your current nickname, i.e. [`Nick`].
+    pub nick: Option<HexString>,
+    /// Current network name, i.e. [`Network`].
+    pub network: Option<HexString>,
+    /// Current server name (what the server claims to be), i.e. [`Server`].
+    pub server: Option<HexString>,
+    /// Real hostname of the server you are connected to, i.e. [`Hostname`].
+    pub hostname: Option<HexString>,
+    /// Your current away reason, i.e. [`AwayReason`].
+    pub away_reason: Option<HexString>,
+}
+
 mod impls;
 
 pub use impls::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_version() {
+        assert_eq!(
+            HexchatVersion::parse("2.16.0"),
+            Some(HexchatVersion::new(2, 16, 0))
+        );
+        assert_eq!(
+            HexchatVersion::parse("2.14.3.1"),
+            Some(HexchatVersion::new(2, 14, 3))
+        );
+        assert_eq!(HexchatVersion::parse("2"), None);
+        assert_eq!(HexchatVersion::parse("2..1"), None);
+        assert_eq!(HexchatVersion::parse(""), None);
+    }
+
+    #[test]
+    fn version_ordering() {
+        assert!(HexchatVersion::new(2, 10, 0) > HexchatVersion::new(2, 9, 6));
+        assert!(HexchatVersion::new(3, 0, 0) > HexchatVersion::new(2, 16, 1));
+    }
+}
@@ -31,6 +31,16 @@ info!(
     "topic", Option::<HexString>, "Current channel topic."
 );
 
+// presence of another info
+info!(
+    IsAway,
+    "away", bool, "Whether you are marked as away, i.e. whether [`AwayReason`] is set."
+);
+info!(
+    IsConnected,
+    "server", bool, "Whether you are connected to a server, i.e. whether [`Server`] is set."
+);
+
 // less useful ones
 info!(
     Inputbox,
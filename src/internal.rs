@@ -38,3 +38,26 @@ pub unsafe fn hexchat_plugin_deinit<P: Plugin>(plugin_handle: *mut hexchat_plugi
     // Safety: safety requirement forwarded to caller
     unsafe { state::hexchat_plugin_deinit::<P>(plugin_handle) }
 }
+
+/// UNSTABLE: do not call this function.
+///
+/// Used by the `hexchat_plugin` macro.
+///
+/// Copies `s` into a null-terminated array at compile time; `N` must be `s.len() + 1`.
+#[doc(hidden)]
+pub const fn nul_terminate<const N: usize>(s: &str) -> [u8; N] {
+    let bytes = s.as_bytes();
+    assert!(
+        bytes.len() + 1 == N,
+        "buffer must be one byte longer than the string"
+    );
+
+    let mut buf = [0; N];
+    let mut i = 0;
+    while i < bytes.len() {
+        assert!(bytes[i] != 0, "plugin metadata must not contain null bytes");
+        buf[i] = bytes[i];
+        i += 1;
+    }
+    buf
+}
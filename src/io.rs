@@ -0,0 +1,174 @@
+//! Printing via `std::io`.
+
+use std::fmt::{self, Debug, Formatter};
+use std::io;
+
+use crate::PluginHandle;
+
+/// Adapts [`PluginHandle::print`] to [`std::io::Write`], so byte-oriented output can be redirected into HexChat.
+///
+/// Output is buffered until a complete line is written; each line is printed separately.
+/// A trailing `\r` is removed from each line.
+///
+/// Invalid UTF-8 and null bytes are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+/// Multi-byte characters split across writes are reassembled before decoding.
+///
+/// Flushing does not print a partial line, since HexChat would display it as a line of its own;
+/// any remaining partial line is printed when the writer is dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::io::Write;
+/// use hexavalent::PluginHandle;
+/// use hexavalent::io::IoPrintWriter;
+///
+/// fn print_report<P>(ph: PluginHandle<'_, P>, bytes: &[u8]) {
+///     let mut writer = IoPrintWriter::new(ph);
+///     let _ = writer.write_all(bytes);
+///     let _ = writeln!(writer, "({} bytes total)", bytes.len());
+/// }
+/// ```
+pub struct IoPrintWriter<'ph, P: 'static> {
+    ph: PluginHandle<'ph, P>,
+    buf: LineBuffer,
+}
+
+impl<'ph, P> IoPrintWriter<'ph, P> {
+    /// Creates a writer that prints to the current [context](crate::PluginHandle::find_context).
+    ///
+    /// The context is not captured; each line is printed to whichever context is current when the line is completed.
+    pub fn new(ph: PluginHandle<'ph, P>) -> Self {
+        Self {
+            ph,
+            buf: LineBuffer::default(),
+        }
+    }
+}
+
+impl<P> io::Write for IoPrintWriter<'_, P> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.push(buf);
+        while let Some(line) = self.buf.pop_line() {
+            self.ph.print(line);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // partial lines stay buffered until they are completed or the writer is dropped
+        Ok(())
+    }
+}
+
+impl<P> Drop for IoPrintWriter<'_, P> {
+    fn drop(&mut self) {
+        if let Some(rest) = self.buf.pop_all() {
+            self.ph.print(rest);
+        }
+    }
+}
+
+impl<P> Debug for IoPrintWriter<'_, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IoPrintWriter")
+            .field("buffered", &self.buf.len())
+            .finish()
+    }
+}
+
+#[derive(Debug, Default)]
+struct LineBuffer {
+    bytes: Vec<u8>,
+    /// Offset of the first byte which has not been popped yet.
+    start: usize,
+}
+
+impl LineBuffer {
+    /// Appends `bytes`, first discarding any lines which have already been popped.
+    fn push(&mut self, bytes: &[u8]) {
+        self.bytes.drain(..self.start);
+        self.start = 0;
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    /// Removes and decodes the first complete line, if any.
+    fn pop_line(&mut self) -> Option<String> {
+        let rest = &self.bytes[self.start..];
+        let newline = rest.iter().position(|&b| b == b'\n')?;
+        let line = &rest[..newline];
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let line = decode(line);
+        self.start += newline + 1;
+        Some(line)
+    }
+
+    /// Removes and decodes everything.
+    fn pop_all(&mut self) -> Option<String> {
+        let rest = &self.bytes[self.start..];
+        if rest.is_empty() {
+            return None;
+        }
+        let rest = decode(rest);
+        self.bytes.clear();
+        self.start = 0;
+        Some(rest)
+    }
+
+    /// The number of bytes which have not been popped yet.
+    fn len(&self) -> usize {
+        self.bytes.len() - self.start
+    }
+}
+
+fn decode(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).replace('\0', "\u{FFFD}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_lines() {
+        let mut buf = LineBuffer::default();
+        buf.push(b"one\ntwo\r\nthr");
+        assert_eq!(buf.pop_line().as_deref(), Some("one"));
+        assert_eq!(buf.pop_line().as_deref(), Some("two"));
+        assert_eq!(buf.pop_line(), None);
+        buf.push(b"ee\n");
+        assert_eq!(buf.pop_line().as_deref(), Some("three"));
+        assert_eq!(buf.pop_all(), None);
+    }
+
+    #[test]
+    fn compacts_popped_lines_on_push() {
+        let mut buf = LineBuffer::default();
+        buf.push(b"one\ntwo\nth");
+        assert_eq!(buf.pop_line().as_deref(), Some("one"));
+        assert_eq!(buf.pop_line().as_deref(), Some("two"));
+        assert_eq!(buf.len(), 2);
+        buf.push(b"ree");
+        assert_eq!(buf.bytes, b"three");
+        assert_eq!(buf.pop_all().as_deref(), Some("three"));
+        assert_eq!(buf.len(), 0);
+    }
+
+    #[test]
+    fn reassembles_split_chars() {
+        let mut buf = LineBuffer::default();
+        let snowman = "☃".as_bytes();
+        buf.push(&snowman[..1]);
+        assert_eq!(buf.pop_line(), None);
+        buf.push(&snowman[1..]);
+        buf.push(b"\n");
+        assert_eq!(buf.pop_line().as_deref(), Some("☃"));
+    }
+
+    #[test]
+    fn replaces_invalid_and_null() {
+        let mut buf = LineBuffer::default();
+        buf.push(b"a\xffb\0c");
+        assert_eq!(buf.pop_all().as_deref(), Some("a\u{FFFD}b\u{FFFD}c"));
+    }
+}
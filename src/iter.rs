@@ -1,9 +1,51 @@
-pub(crate) trait CurriedItem<'a> {
+//! Iterators whose items borrow from the iterator itself.
+//!
+//! Lists from HexChat are only valid until the next element is requested,
+//! so they can't be exposed with [`Iterator`], which allows items to outlive the next call to `next`.
+//!
+//! [`LendingIterator`] works around this (pending generic associated types in `std`) by expressing the item type
+//! as a [`CurriedItem`], which is given the lifetime of each borrow of the iterator.
+
+/// An item type which is parameterized by the lifetime of a borrow.
+///
+/// Usually used as `dyn for<'a> CurriedItem<'a, Item = Foo<'a>>`, which names the family of types `Foo<'_>`.
+pub trait CurriedItem<'a> {
+    /// The item type for the lifetime `'a`.
     type Item;
 }
 
-pub(crate) trait LendingIterator {
+/// An iterator whose items may borrow from the iterator, so each item must be dropped before the next is requested.
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::iter::{CurriedItem, LendingIterator};
+///
+/// struct Windows {
+///     buf: Vec<u8>,
+///     pos: usize,
+/// }
+///
+/// impl LendingIterator for Windows {
+///     type Item = dyn for<'a> CurriedItem<'a, Item = &'a mut [u8]>;
+///
+///     fn next(&mut self) -> Option<&mut [u8]> {
+///         let window = self.buf.get_mut(self.pos..self.pos + 2)?;
+///         self.pos += 1;
+///         Some(window)
+///     }
+/// }
+///
+/// let mut windows = Windows { buf: vec![1, 2, 3], pos: 0 };
+/// while let Some(window) = windows.next() {
+///     window[1] += window[0];
+/// }
+/// assert_eq!(windows.buf, [1, 3, 6]);
+/// ```
+pub trait LendingIterator {
+    /// The family of item types, e.g. `dyn for<'a> CurriedItem<'a, Item = Foo<'a>>`.
     type Item: ?Sized + for<'a> CurriedItem<'a>;
 
+    /// Advances the iterator and returns the next item, which borrows from the iterator.
     fn next(&mut self) -> Option<<Self::Item as CurriedItem<'_>>::Item>;
 }
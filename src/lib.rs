@@ -4,7 +4,8 @@
 //! - Make a library crate with [`crate-type = "cdylib"`](https://doc.rust-lang.org/cargo/reference/manifest.html#building-dynamic-or-static-libraries).
 //! - Define a type, e.g. `struct MyPlugin`, to hold any state your plugin needs.
 //! - Implement the [`Plugin`] trait for `MyPlugin`.
-//! - Call [`export_plugin`] with the type `MyPlugin`, its name, description, and version.
+//! - Call [`export_plugin`] with the type `MyPlugin`, its name, description, and version
+//!   (or set [`Plugin::NAME`], [`Plugin::DESCRIPTION`], and [`Plugin::VERSION`], and pass only the type).
 //!
 //! On Windows, it is recommended to add `-C target-feature=+crt-static` to your `RUSTFLAGS`,
 //! for example in [`<project root>/.cargo/config`](https://doc.rust-lang.org/cargo/reference/config.html).
@@ -109,25 +110,33 @@
 mod macros;
 
 mod ffi;
-mod iter;
 mod plugin;
 mod state;
 
 #[doc(hidden)]
 pub mod internal;
 
+pub mod command;
 pub mod context;
 pub mod event;
+pub mod format;
 pub mod gui;
 pub mod hook;
 pub mod info;
+pub mod io;
+pub mod iter;
 pub mod list;
 pub mod mode;
 pub mod pref;
+pub mod rfc1459;
 pub mod str;
 pub mod strip;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod thread;
+pub mod whois;
 
-pub use plugin::{Plugin, PluginHandle};
+pub use plugin::{InitError, Plugin, PluginHandle};
 
 /// Defines the necessary exports for HexChat to load your plugin.
 ///
@@ -173,13 +182,51 @@ pub use plugin::{Plugin, PluginHandle};
 ///     env!("CARGO_PKG_VERSION"),
 /// );
 /// ```
+///
+/// If only the type is passed, the name, description, and version are read from
+/// [`Plugin::NAME`], [`Plugin::DESCRIPTION`], and [`Plugin::VERSION`].
+/// In this case, `NAME` must be non-empty, which is checked at compile time.
+///
+/// ```rust
+/// use hexavalent::{Plugin, PluginHandle, export_plugin};
+///
+/// #[derive(Default)]
+/// struct NoopPlugin;
+///
+/// impl Plugin for NoopPlugin {
+///     const NAME: &'static str = "No-op";
+///     const DESCRIPTION: &'static str = "Doesn't do anything";
+///     const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+///
+///     fn init(&self, ph: PluginHandle<'_, Self>) {
+///         ph.print("Hello world!");
+///     }
+/// }
+///
+/// export_plugin!(NoopPlugin);
+/// ```
+///
+/// ```rust,compile_fail
+/// use hexavalent::{Plugin, PluginHandle, export_plugin};
+///
+/// #[derive(Default)]
+/// struct NoopPlugin;
+///
+/// impl Plugin for NoopPlugin {
+///     fn init(&self, ph: PluginHandle<'_, Self>) {}
+/// }
+///
+/// // fails because `NoopPlugin::NAME` is empty
+/// export_plugin!(NoopPlugin);
+/// ```
 #[macro_export]
 macro_rules! export_plugin {
     (
+        @export
         $plugin_ty:ty,
         $name:expr,
         $desc:expr,
-        $version:expr $(,)?
+        $version:expr
     ) => {
         #[no_mangle]
         pub unsafe extern "C" fn hexchat_plugin_init(
@@ -189,23 +236,11 @@ macro_rules! export_plugin {
             plugin_version: *mut *const ::std::os::raw::c_char,
             _arg: *mut ::std::os::raw::c_char,
         ) -> ::std::os::raw::c_int {
-            const NAME: &::std::ffi::CStr =
-                match ::std::ffi::CStr::from_bytes_with_nul(concat!($name, "\0").as_bytes()) {
-                    Ok(x) => x,
-                    Err(_) => unreachable!(),
-                };
-            const DESC: &::std::ffi::CStr =
-                match ::std::ffi::CStr::from_bytes_with_nul(concat!($desc, "\0").as_bytes()) {
-                    Ok(x) => x,
-                    Err(_) => unreachable!(),
-                };
-            const VERSION: &::std::ffi::CStr =
-                match ::std::ffi::CStr::from_bytes_with_nul(concat!($version, "\0").as_bytes()) {
-                    Ok(x) => x,
-                    Err(_) => unreachable!(),
-                };
+            const NAME: &::std::ffi::CStr = $name;
+            const DESC: &::std::ffi::CStr = $desc;
+            const VERSION: &::std::ffi::CStr = $version;
 
-            // Safety: these literals are null-terminated and 'static
+            // Safety: these constants are null-terminated and 'static
             *plugin_name = NAME.as_ptr();
             *plugin_desc = DESC.as_ptr();
             *plugin_version = VERSION.as_ptr();
@@ -220,4 +255,54 @@ macro_rules! export_plugin {
             $crate::internal::hexchat_plugin_deinit::<$plugin_ty>(plugin_handle)
         }
     };
+    (
+        @literal
+        $lit:expr
+    ) => {
+        match ::std::ffi::CStr::from_bytes_with_nul(concat!($lit, "\0").as_bytes()) {
+            Ok(x) => x,
+            Err(_) => unreachable!(),
+        }
+    };
+    (
+        @const
+        $value:expr
+    ) => {{
+        const VALUE: &str = $value;
+        const BYTES: &[u8] = &$crate::internal::nul_terminate::<{ VALUE.len() + 1 }>(VALUE);
+        match ::std::ffi::CStr::from_bytes_with_nul(BYTES) {
+            Ok(x) => x,
+            Err(_) => unreachable!(),
+        }
+    }};
+    (
+        $plugin_ty:ty $(,)?
+    ) => {
+        const _: () = assert!(
+            !<$plugin_ty as $crate::Plugin>::NAME.is_empty(),
+            "`Plugin::NAME` must be set to use `export_plugin!` without a name",
+        );
+
+        $crate::export_plugin!(
+            @export
+            $plugin_ty,
+            $crate::export_plugin!(@const <$plugin_ty as $crate::Plugin>::NAME),
+            $crate::export_plugin!(@const <$plugin_ty as $crate::Plugin>::DESCRIPTION),
+            $crate::export_plugin!(@const <$plugin_ty as $crate::Plugin>::VERSION)
+        );
+    };
+    (
+        $plugin_ty:ty,
+        $name:expr,
+        $desc:expr,
+        $version:expr $(,)?
+    ) => {
+        $crate::export_plugin!(
+            @export
+            $plugin_ty,
+            $crate::export_plugin!(@literal $name),
+            $crate::export_plugin!(@literal $desc),
+            $crate::export_plugin!(@literal $version)
+        );
+    };
 }
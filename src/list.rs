@@ -1,10 +1,21 @@
 //! Info lists.
 
 use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::iter::Filter;
+use std::marker::PhantomData;
 use std::ops::Deref;
+use std::ptr::NonNull;
 use std::str::Split;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
-use crate::str::{HexStr, HexString};
+use time::OffsetDateTime;
+
+use crate::ffi::{hexchat_context, ListElem};
+use crate::str::{HexStr, IntoCStr};
+use private::Interner;
 
 /// A list that can be retrieved from HexChat.
 ///
@@ -21,9 +32,125 @@ where
     type Elem: 'static;
 }
 
+/// An error that occurred while getting a list.
+///
+/// Returned from [`PluginHandle::get_list`](crate::PluginHandle::get_list).
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ListError {
+    /// HexChat did not recognize the list name.
+    Unknown,
+}
+
+impl fmt::Display for ListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown => f.write_str("unknown list"),
+        }
+    }
+}
+
+impl Error for ListError {}
+
+/// A snapshot of a list, which can be queried repeatedly without calling into HexChat.
+///
+/// Returned from [`PluginHandle::get_list_snapshot`](crate::PluginHandle::get_list_snapshot).
+///
+/// Since list contents may change whenever HexChat runs, a snapshot borrows the lifetime of the [`PluginHandle`](crate::PluginHandle)
+/// it was created from, so it cannot outlive the current callback.
+/// Like the list it was read from, it may be specific to the context that was current when it was created.
+///
+/// `ListSnapshot` [`derefs`](Deref) to a slice of the list's elements.
+pub struct ListSnapshot<'ph, L: List> {
+    elems: Vec<L::Elem>,
+    _lifetime: PhantomData<&'ph ()>,
+}
+
+impl<'ph, L: List> ListSnapshot<'ph, L> {
+    pub(crate) fn new(elems: Vec<L::Elem>) -> Self {
+        Self {
+            elems,
+            _lifetime: PhantomData,
+        }
+    }
+}
+
+impl<L: List> Deref for ListSnapshot<'_, L> {
+    type Target = [L::Elem];
+
+    fn deref(&self) -> &Self::Target {
+        &self.elems
+    }
+}
+
+impl<L: List> fmt::Debug for ListSnapshot<'_, L>
+where
+    L::Elem: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.elems).finish()
+    }
+}
+
+/// A single element of a list, allowing fields to be read by name.
+///
+/// Used with [`PluginHandle::get_list_with_fields`](crate::PluginHandle::get_list_with_fields),
+/// to read fields which are not (yet) exposed by the list's element type, e.g. those added in newer versions of HexChat.
+///
+/// See the [HexChat documentation](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_list_fields)
+/// for the names and types of available fields.
+#[derive(Debug)]
+pub struct RawListElem<'a> {
+    elem: ListElem<'a>,
+}
+
+impl<'a> RawListElem<'a> {
+    pub(crate) fn new(elem: ListElem<'a>) -> Self {
+        Self { elem }
+    }
+
+    pub(crate) fn into_inner(self) -> ListElem<'a> {
+        self.elem
+    }
+
+    pub(crate) fn context(&self) -> Option<NonNull<hexchat_context>> {
+        self.elem.context()
+    }
+
+    /// Reads a string field.
+    ///
+    /// Returns `None` if the field is null or does not exist.
+    ///
+    /// Analogous to [`hexchat_list_str`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_list_str).
+    pub fn string(&self, name: impl IntoCStr) -> Option<&HexStr> {
+        self.elem.string(&name.into_cstr())
+    }
+
+    /// Reads an integer field.
+    ///
+    /// Returns `-1` if the field does not exist, which cannot be distinguished from a field whose value is `-1`.
+    ///
+    /// Analogous to [`hexchat_list_int`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_list_int).
+    pub fn int(&self, name: impl IntoCStr) -> i32 {
+        self.elem.int(&name.into_cstr())
+    }
+
+    /// Reads a time field.
+    ///
+    /// Returns one second before the Unix epoch (a timestamp of `-1`) if the field does not exist.
+    ///
+    /// Analogous to [`hexchat_list_time`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_list_time).
+    pub fn time(&self, name: impl IntoCStr) -> OffsetDateTime {
+        self.elem.time(&name.into_cstr())
+    }
+}
+
 pub(crate) mod private {
     use crate::ffi::ListElem;
+    use crate::str::HexStr;
+    use std::collections::HashSet;
     use std::ffi::CStr;
+    use std::sync::Arc;
 
     pub trait ListImpl {
         const NAME: &'static CStr;
@@ -31,7 +158,42 @@ pub(crate) mod private {
 
     #[allow(unreachable_pub)]
     pub trait FromListElem: Sized {
-        fn from_list_elem(elem: ListElem<'_>) -> Self;
+        fn from_list_elem(elem: ListElem<'_>, interner: &mut Interner) -> Self;
+    }
+
+    /// Deduplicates strings read from a list, so that repeated values share one allocation.
+    ///
+    /// Used by [`PluginHandle::get_list_interned`](crate::PluginHandle::get_list_interned).
+    #[derive(Debug)]
+    #[allow(unreachable_pub)]
+    pub struct Interner {
+        /// `None` if interning is disabled, in which case every string gets its own allocation.
+        strings: Option<HashSet<Arc<HexStr>>>,
+    }
+
+    impl Interner {
+        pub(crate) fn new() -> Self {
+            Self {
+                strings: Some(HashSet::new()),
+            }
+        }
+
+        pub(crate) fn disabled() -> Self {
+            Self { strings: None }
+        }
+
+        pub(super) fn intern(&mut self, s: &HexStr) -> Arc<HexStr> {
+            let strings = match &mut self.strings {
+                Some(strings) => strings,
+                None => return Arc::from(s),
+            };
+            if let Some(existing) = strings.get(s) {
+                return Arc::clone(existing);
+            }
+            let new = Arc::from(s);
+            strings.insert(Arc::clone(&new));
+            new
+        }
     }
 }
 
@@ -87,11 +249,11 @@ macro_rules! list {
         }
 
         impl crate::list::private::FromListElem for $elem_ty {
-            fn from_list_elem(elem: crate::ffi::ListElem<'_>) -> Self {
+            fn from_list_elem(elem: crate::ffi::ListElem<'_>, interner: &mut crate::list::private::Interner) -> Self {
                 Self {
                     $(
                         $rust_field_name: {
-                            let raw_value = list!(@generateFieldExtraction, elem, $( $field_key )? $( $custom )?, $( $field_type )? $( |$elem| $extract )?);
+                            let raw_value = list!(@generateFieldExtraction, elem, interner, $( $field_key )? $( $custom )?, $( $field_type )? $( |$elem| $extract )?);
                             crate::list::FromListElemField::from_list_elem_field(raw_value)
                         },
                     )*
@@ -103,6 +265,7 @@ macro_rules! list {
     (
         @generateFieldExtraction,
         $elem:ident,
+        $interner:ident,
         custom,
         |$elem2:ident| $extract:expr
     ) => {
@@ -115,6 +278,26 @@ macro_rules! list {
     (
         @generateFieldExtraction,
         $elem:ident,
+        $interner:ident,
+        $field_key:literal,
+        string
+    ) => {
+        (list!(@readField, $elem, $field_key, string), &mut *$interner)
+    };
+
+    (
+        @generateFieldExtraction,
+        $elem:ident,
+        $interner:ident,
+        $field_key:literal,
+        $field_type:ident
+    ) => {
+        list!(@readField, $elem, $field_key, $field_type)
+    };
+
+    (
+        @readField,
+        $elem:ident,
         $field_key:literal,
         $field_type:ident
     ) => {
@@ -141,22 +324,22 @@ impl<T> FromListElemField<T> for T {
     }
 }
 
-impl FromListElemField<Option<&HexStr>> for HexString {
-    fn from_list_elem_field(field: Option<&HexStr>) -> Self {
+impl FromListElemField<(Option<&HexStr>, &mut Interner)> for Arc<HexStr> {
+    fn from_list_elem_field((field, interner): (Option<&HexStr>, &mut Interner)) -> Self {
         field
-            .map(ToOwned::to_owned)
+            .map(|s| interner.intern(s))
             .unwrap_or_else(|| panic!("Unexpected null string in list"))
     }
 }
 
-impl FromListElemField<Option<&HexStr>> for Option<HexString> {
-    fn from_list_elem_field(field: Option<&HexStr>) -> Self {
-        field.map(ToOwned::to_owned)
+impl FromListElemField<(Option<&HexStr>, &mut Interner)> for Option<Arc<HexStr>> {
+    fn from_list_elem_field((field, interner): (Option<&HexStr>, &mut Interner)) -> Self {
+        field.map(|s| interner.intern(s))
     }
 }
 
-impl FromListElemField<Option<&HexStr>> for Option<char> {
-    fn from_list_elem_field(field: Option<&HexStr>) -> Self {
+impl FromListElemField<(Option<&HexStr>, &mut Interner)> for Option<char> {
+    fn from_list_elem_field((field, _): (Option<&HexStr>, &mut Interner)) -> Self {
         match field {
             Some(field) => match field.as_bytes() {
                 &[] => None,
@@ -184,8 +367,8 @@ impl FromListElemField<i32> for bool {
     }
 }
 
-impl FromListElemField<Option<&HexStr>> for SplitByCommas {
-    fn from_list_elem_field(field: Option<&HexStr>) -> SplitByCommas {
+impl FromListElemField<(Option<&HexStr>, &mut Interner)> for SplitByCommas {
+    fn from_list_elem_field((field, _): (Option<&HexStr>, &mut Interner)) -> SplitByCommas {
         SplitByCommas(field.map(|s| s.deref().to_owned()).unwrap_or_default())
     }
 }
@@ -200,24 +383,123 @@ impl<'a, T: Copy> ProjectListElemField<'a, T> for T {
     }
 }
 
-impl<'a> ProjectListElemField<'a, &'a HexStr> for HexString {
+impl<'a> ProjectListElemField<'a, &'a HexStr> for Arc<HexStr> {
     fn project_list_elem_field(&self) -> &HexStr {
         self
     }
 }
 
-impl<'a> ProjectListElemField<'a, Option<&'a HexStr>> for Option<HexString> {
+impl<'a> ProjectListElemField<'a, Option<&'a HexStr>> for Option<Arc<HexStr>> {
     fn project_list_elem_field(&self) -> Option<&HexStr> {
         self.as_deref()
     }
 }
 
-impl<'a> ProjectListElemField<'a, Split<'a, char>> for SplitByCommas {
-    fn project_list_elem_field(&'a self) -> Split<'a, char> {
-        self.0.split(',')
+impl<'a> ProjectListElemField<'a, Filter<Split<'a, char>, fn(&&str) -> bool>> for SplitByCommas {
+    fn project_list_elem_field(&'a self) -> Filter<Split<'a, char>, fn(&&str) -> bool> {
+        // an empty field means no networks, not a single empty network name
+        self.0.split(',').filter(|s| !s.is_empty())
+    }
+}
+
+/// Converts a time field to `SystemTime`, for use in `*_system_time` accessors.
+fn to_system_time(time: OffsetDateTime) -> SystemTime {
+    let secs = time.unix_timestamp();
+    let since_epoch = Duration::from_secs(secs.unsigned_abs());
+    if secs >= 0 {
+        SystemTime::UNIX_EPOCH + since_epoch
+    } else {
+        SystemTime::UNIX_EPOCH - since_epoch
+    }
+}
+
+/// Combines the high and low 32 bits of a 64-bit list field, which HexChat returns as two signed integers.
+///
+/// Each half is reinterpreted as unsigned, so that a low half with bit 31 set is not sign-extended.
+fn combine_high_low(high: i32, low: i32) -> u64 {
+    (u64::from(high as u32) << 32) | u64::from(low as u32)
+}
+
+/// Whether `prefix` ranks at least as high as the prefix for `mode`.
+///
+/// `nick_prefixes` and `nick_modes` correspond to each other and are ordered from highest to lowest rank,
+/// e.g. `"~&@%+"` and `"qaohv"`.
+fn prefix_at_least(prefix: char, nick_prefixes: &str, nick_modes: &str, mode: char) -> bool {
+    let rank = match nick_prefixes.chars().position(|p| p == prefix) {
+        Some(rank) => rank,
+        None => return false,
+    };
+    match nick_modes.chars().position(|m| m == mode) {
+        Some(required) => rank <= required,
+        None => false,
     }
 }
 
 mod impls;
 
 pub use impls::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn system_time_around_epoch() {
+        let day = Duration::from_secs(86400);
+        let after = OffsetDateTime::from_unix_timestamp(86400).unwrap();
+        let before = OffsetDateTime::from_unix_timestamp(-86400).unwrap();
+        assert_eq!(to_system_time(after), SystemTime::UNIX_EPOCH + day);
+        assert_eq!(to_system_time(before), SystemTime::UNIX_EPOCH - day);
+        assert_eq!(
+            to_system_time(OffsetDateTime::UNIX_EPOCH),
+            SystemTime::UNIX_EPOCH
+        );
+    }
+
+    #[test]
+    fn interner_shares_equal_strings() {
+        let a = HexStr::from_bytes_with_nul(b"libera\0").unwrap();
+        let b = HexStr::from_bytes_with_nul(b"oftc\0").unwrap();
+
+        let mut interner = Interner::new();
+        let first = interner.intern(a);
+        assert!(Arc::ptr_eq(&first, &interner.intern(a)));
+        assert!(!Arc::ptr_eq(&first, &interner.intern(b)));
+        assert_eq!(&*first, a);
+
+        let mut disabled = Interner::disabled();
+        assert!(!Arc::ptr_eq(&disabled.intern(a), &disabled.intern(a)));
+    }
+
+    #[test]
+    fn split_by_commas() {
+        let networks = SplitByCommas("Libera,OFTC".to_owned());
+        let networks: Filter<Split<'_, char>, _> = networks.project_list_elem_field();
+        assert_eq!(networks.collect::<Vec<_>>(), ["Libera", "OFTC"]);
+
+        let empty = SplitByCommas(String::new());
+        let empty: Filter<Split<'_, char>, _> = empty.project_list_elem_field();
+        assert_eq!(empty.count(), 0);
+    }
+
+    #[test]
+    fn high_low_not_sign_extended() {
+        assert_eq!(combine_high_low(0, 1), 1);
+        assert_eq!(combine_high_low(0, i32::MIN), 0x8000_0000);
+        assert_eq!(combine_high_low(0, -1), 0xffff_ffff);
+        assert_eq!(combine_high_low(1, -1), 0x1_ffff_ffff);
+    }
+
+    #[test]
+    fn prefix_ranks() {
+        assert!(prefix_at_least('@', "@+", "ov", 'o'));
+        assert!(!prefix_at_least('+', "@+", "ov", 'o'));
+        assert!(prefix_at_least('+', "@+", "ov", 'v'));
+        assert!(prefix_at_least('~', "~&@%+", "qaohv", 'o'));
+        assert!(!prefix_at_least('%', "~&@%+", "qaohv", 'o'));
+        assert!(prefix_at_least('%', "~&@%+", "qaohv", 'v'));
+        assert!(!prefix_at_least('@', "!+", "ov", 'o'));
+        assert!(prefix_at_least('!', "!+", "ov", 'o'));
+        assert!(!prefix_at_least('@', "@+", "v", 'o'));
+    }
+}
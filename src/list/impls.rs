@@ -1,10 +1,16 @@
-use std::net::{Ipv4Addr, SocketAddrV4};
+use std::convert::TryFrom;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddrV4};
 use std::num::NonZeroU64;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use bitflags::bitflags;
-use time::OffsetDateTime;
+use time::{OffsetDateTime, UtcOffset};
 
-use crate::str::{HexStr, HexString};
+use crate::context::Context;
+use crate::mode::Hostmask;
+use crate::str::HexStr;
 
 list!(
     Channels,
@@ -12,19 +18,19 @@ list!(
     "List of channels, queries and their servers.",
     "A channel.",
     Channel {
-        ["channel", "Channel or query name.", string] name: HexString => &HexStr,
-        ["channelkey", "Channel key. (HexChat 2.9.6+)", string] key: Option<HexString> => Option<&HexStr>,
-        ["chanmodes", "Available channel modes e.g. `\"beI,k,l\"`. (HexChat 2.12.2+)", string] modes: HexString => &HexStr,
-        ["chantypes", "Available channel types e.g. `\"#!&\"`.", string] types: HexString => &HexStr,
+        ["channel", "Channel or query name.", string] name: Arc<HexStr> => &HexStr,
+        ["channelkey", "Channel key. (HexChat 2.9.6+)", string] key: Option<Arc<HexStr>> => Option<&HexStr>,
+        ["chanmodes", "Available channel modes e.g. `\"beI,k,l\"`. (HexChat 2.12.2+)", string] modes: Arc<HexStr> => &HexStr,
+        ["chantypes", "Available channel types e.g. `\"#!&\"`.", string] types: Arc<HexStr> => &HexStr,
         ["flags", "Info flags.", int] flags: ChannelFlags => ChannelFlags,
         ["id", "Unique server ID.", int] server_id: i32 => i32,
         ["lag", "Lag in milliseconds.", int] lag_ms: i32 => i32,
         ["maxmodes", "Maximum modes per line.", int] max_modes_per_line: u32 => u32,
-        ["network", "Name of network.", string] network: HexString => &HexStr,
-        ["nickprefixes", "Nickname prefixes e.g. `\"@+\"`.", string] nick_prefixes: HexString => &HexStr,
-        ["nickmodes", "Nickname mode chars e.g. `\"ov\"`.", string] nick_modes: HexString => &HexStr,
+        ["network", "Name of network.", string] network: Arc<HexStr> => &HexStr,
+        ["nickprefixes", "Nickname prefixes e.g. `\"@+\"`.", string] nick_prefixes: Arc<HexStr> => &HexStr,
+        ["nickmodes", "Nickname mode chars e.g. `\"ov\"`.", string] nick_modes: Arc<HexStr> => &HexStr,
         ["queue", "Number of bytes in the send-queue.", int] queue: u32 => u32,
-        ["server", "Server name to which this channel belongs.", string] servname: HexString => &HexStr,
+        ["server", "Server name to which this channel belongs.", string] servname: Arc<HexStr> => &HexStr,
         ["type", "Channel type.", int] ty: ChannelType => ChannelType,
         ["users", "Number of users in this channel.", int] num_users: u32 => u32,
     }
@@ -82,7 +88,7 @@ impl super::FromListElemField<i32> for ChannelFlags {
 ///
 /// Part of [`Channel`].
 #[non_exhaustive]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum ChannelType {
     /// A toplevel server "channel".
     Server = 1,
@@ -96,19 +102,111 @@ pub enum ChannelType {
     ServerNotice = 5,
 }
 
-impl super::FromListElemField<i32> for ChannelType {
-    fn from_list_elem_field(field: i32) -> Self {
+impl ChannelType {
+    /// The integer code HexChat uses for this channel type.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::convert::TryFrom;
+    /// use hexavalent::list::ChannelType;
+    ///
+    /// assert_eq!(ChannelType::Dialog.as_int(), 3);
+    /// assert_eq!(ChannelType::try_from(3), Ok(ChannelType::Dialog));
+    /// assert_eq!(ChannelType::try_from(0), Err(()));
+    /// ```
+    pub fn as_int(self) -> i32 {
+        self as i32
+    }
+}
+
+impl TryFrom<i32> for ChannelType {
+    type Error = ();
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
         match () {
-            _ if field == Self::Server as _ => Self::Server,
-            _ if field == Self::Channel as _ => Self::Channel,
-            _ if field == Self::Dialog as _ => Self::Dialog,
-            _ if field == Self::Notice as _ => Self::Notice,
-            _ if field == Self::ServerNotice as _ => Self::ServerNotice,
-            _ => panic!("Unexpected channel type: {}", field),
+            _ if value == Self::Server as i32 => Ok(Self::Server),
+            _ if value == Self::Channel as i32 => Ok(Self::Channel),
+            _ if value == Self::Dialog as i32 => Ok(Self::Dialog),
+            _ if value == Self::Notice as i32 => Ok(Self::Notice),
+            _ if value == Self::ServerNotice as i32 => Ok(Self::ServerNotice),
+            _ => Err(()),
         }
     }
 }
 
+impl fmt::Display for ChannelType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Server => "server",
+            Self::Channel => "channel",
+            Self::Dialog => "dialog",
+            Self::Notice => "notice",
+            Self::ServerNotice => "server notice",
+        })
+    }
+}
+
+impl super::FromListElemField<i32> for ChannelType {
+    fn from_list_elem_field(field: i32) -> Self {
+        Self::try_from(field).unwrap_or_else(|()| panic!("Unexpected channel type: {}", field))
+    }
+}
+
+impl Channel {
+    /// Whether this is a toplevel server "channel", i.e. [`ChannelType::Server`].
+    pub fn is_server(&self) -> bool {
+        matches!(self.ty, ChannelType::Server)
+    }
+
+    /// Whether this is a normal channel, i.e. [`ChannelType::Channel`].
+    pub fn is_channel(&self) -> bool {
+        matches!(self.ty, ChannelType::Channel)
+    }
+
+    /// Whether this is a dialog (direct message) channel, i.e. [`ChannelType::Dialog`].
+    pub fn is_query(&self) -> bool {
+        matches!(self.ty, ChannelType::Dialog)
+    }
+
+    /// Whether this is a notice or server notice channel, i.e. [`ChannelType::Notice`] or [`ChannelType::ServerNotice`].
+    pub fn is_notice(&self) -> bool {
+        matches!(self.ty, ChannelType::Notice | ChannelType::ServerNotice)
+    }
+
+    /// Whether the client is connected to the channel, i.e. [`ChannelFlags::CONNECTED`].
+    pub fn is_connected(&self) -> bool {
+        self.flags.contains(ChannelFlags::CONNECTED)
+    }
+
+    /// Whether the client is connecting to the channel, i.e. [`ChannelFlags::CONNECTING`].
+    pub fn is_connecting(&self) -> bool {
+        self.flags.contains(ChannelFlags::CONNECTING)
+    }
+
+    /// Whether the current user is marked away, i.e. [`ChannelFlags::MARKED_AWAY`].
+    pub fn is_away(&self) -> bool {
+        self.flags.contains(ChannelFlags::MARKED_AWAY)
+    }
+
+    /// Whether the MOTD has ended, i.e. [`ChannelFlags::END_OF_MOTD`].
+    pub fn is_end_of_motd(&self) -> bool {
+        self.flags.contains(ChannelFlags::END_OF_MOTD)
+    }
+
+    /// Whether messages are logged, i.e. [`ChannelFlags::LOGGING`].
+    pub fn is_logging(&self) -> bool {
+        self.flags.contains(ChannelFlags::LOGGING)
+    }
+
+    /// Lag to the server, or `None` if it is not known.
+    ///
+    /// This is [`Channel::lag_ms`] as a [`Duration`], with negative values treated as unknown.
+    pub fn lag(&self) -> Option<Duration> {
+        u64::try_from(self.lag_ms).ok().map(Duration::from_millis)
+    }
+}
+
 list!(
     DccTransfers,
     "dcc",
@@ -121,34 +219,90 @@ list!(
             |elem| SocketAddrV4::new(Ipv4Addr::from(elem.int(c"address32") as u32), elem.int(c"port") as u16)
         ] socket_addr: SocketAddrV4 => SocketAddrV4,
         ["cps", "Bytes per second (speed).", int] bytes_per_second: u32 => u32,
-        ["destfile", "Destination full pathname.", string] dest_file: HexString => &HexStr,
-        ["file", "Filename.", string] file_name: HexString => &HexStr,
-        ["nick", "Nickname of person who the file is from/to.", string] nick: HexString => &HexStr,
+        ["destfile", "Destination full pathname.", string] dest_file: Arc<HexStr> => &HexStr,
+        ["file", "Filename.", string] file_name: Arc<HexStr> => &HexStr,
+        ["nick", "Nickname of person who the file is from/to.", string] nick: Arc<HexStr> => &HexStr,
         [
             custom,
             "Bytes sent/received.",
-            |elem| (elem.int(c"poshigh") as u64) << 32 | (elem.int(c"pos") as u64)
+            |elem| super::combine_high_low(elem.int(c"poshigh"), elem.int(c"pos"))
         ] position: u64 => u64,
         [
             custom,
             "Point at which this file was resumed.",
-            |elem| NonZeroU64::new((elem.int(c"resumehigh") as u64) << 32 | (elem.int(c"resume") as u64))
+            |elem| NonZeroU64::new(super::combine_high_low(elem.int(c"resumehigh"), elem.int(c"resume")))
         ] resumed_at: Option<NonZeroU64> => Option<NonZeroU64>,
         [
             custom,
             "File size in bytes.",
-            |elem| (elem.int(c"sizehigh") as u64) << 32 | (elem.int(c"size") as u64)
-        ] size: u64 => u64
+            |elem| super::combine_high_low(elem.int(c"sizehigh"), elem.int(c"size"))
+        ] size: u64 => u64,
+        ["status", "Transfer status.", int] status: DccStatus => DccStatus,
     }
 );
 
+/// The status of a DCC transfer.
+///
+/// Part of [`DccTransfer`].
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DccStatus {
+    /// The transfer is queued.
+    Queued = 0,
+    /// The transfer is in progress.
+    Active = 1,
+    /// The transfer failed.
+    Failed = 2,
+    /// The transfer completed successfully.
+    Done = 3,
+    /// The transfer is connecting.
+    Connecting = 4,
+    /// The transfer was aborted.
+    Aborted = 5,
+}
+
+impl super::FromListElemField<i32> for DccStatus {
+    fn from_list_elem_field(field: i32) -> Self {
+        match () {
+            _ if field == Self::Queued as i32 => Self::Queued,
+            _ if field == Self::Active as i32 => Self::Active,
+            _ if field == Self::Failed as i32 => Self::Failed,
+            _ if field == Self::Done as i32 => Self::Done,
+            _ if field == Self::Connecting as i32 => Self::Connecting,
+            _ if field == Self::Aborted as i32 => Self::Aborted,
+            _ => panic!("Unexpected DCC status: {}", field),
+        }
+    }
+}
+
+impl DccTransfer {
+    /// Whether the transfer has finished, successfully or not,
+    /// i.e. [`DccStatus::Done`], [`DccStatus::Failed`], or [`DccStatus::Aborted`].
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            DccStatus::Done | DccStatus::Failed | DccStatus::Aborted
+        )
+    }
+
+    /// IP address of the remote user, i.e. the IP of [`DccTransfer::socket_addr`].
+    pub fn peer_ip(&self) -> IpAddr {
+        IpAddr::V4(*self.socket_addr.ip())
+    }
+
+    /// Port of the remote user, i.e. the port of [`DccTransfer::socket_addr`].
+    pub fn peer_port(&self) -> u16 {
+        self.socket_addr.port()
+    }
+}
+
 list!(
     Ignores,
     "ignore",
     "List of ignores.",
     "An ignored mask.",
     Ignore {
-        ["mask", "Ignore mask, e.g. `\"*!*@*.aol.com\"`.", string] mask: HexString => &HexStr,
+        ["mask", "Ignore mask, e.g. `\"*!*@*.aol.com\"`.", string] mask: Arc<HexStr> => &HexStr,
         ["flags", "Info flags.", int] flags: IgnoreFlags => IgnoreFlags,
     }
 );
@@ -184,6 +338,70 @@ impl super::FromListElemField<i32> for IgnoreFlags {
     }
 }
 
+impl Ignore {
+    /// The nick, user (ident), and host components of the mask, as parsed by [`Hostmask`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::list::Ignores;
+    ///
+    /// fn print_ignored_hosts<P>(ph: PluginHandle<'_, P>) {
+    ///     for ignore in ph.get_list(Ignores).into_iter().flatten() {
+    ///         let (nick, user, host) = ignore.mask_parts();
+    ///         if nick == "*" && user == "*" {
+    ///             ph.print(format!("Ignoring host {}", host));
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn mask_parts(&self) -> (&str, &str, &str) {
+        let mask = Hostmask::parse(&self.mask);
+        (mask.nick(), mask.user(), mask.host())
+    }
+
+    /// Whether private messages are ignored, i.e. [`IgnoreFlags::PRIVATE`].
+    pub fn ignores_private(&self) -> bool {
+        self.flags.contains(IgnoreFlags::PRIVATE)
+    }
+
+    /// Whether notice messages are ignored, i.e. [`IgnoreFlags::NOTICE`].
+    pub fn ignores_notice(&self) -> bool {
+        self.flags.contains(IgnoreFlags::NOTICE)
+    }
+
+    /// Whether channel messages are ignored, i.e. [`IgnoreFlags::CHANNEL`].
+    pub fn ignores_channel(&self) -> bool {
+        self.flags.contains(IgnoreFlags::CHANNEL)
+    }
+
+    /// Whether CTCP commands are ignored, i.e. [`IgnoreFlags::CTCP`].
+    pub fn ignores_ctcp(&self) -> bool {
+        self.flags.contains(IgnoreFlags::CTCP)
+    }
+
+    /// Whether invitations are ignored, i.e. [`IgnoreFlags::INVITE`].
+    pub fn ignores_invite(&self) -> bool {
+        self.flags.contains(IgnoreFlags::INVITE)
+    }
+
+    /// Whether DCC transfers are ignored, i.e. [`IgnoreFlags::DCC`].
+    pub fn ignores_dcc(&self) -> bool {
+        self.flags.contains(IgnoreFlags::DCC)
+    }
+
+    /// Whether this is an "unignore" entry, i.e. [`IgnoreFlags::UNIGNORE`].
+    pub fn is_unignore(&self) -> bool {
+        self.flags.contains(IgnoreFlags::UNIGNORE)
+    }
+
+    /// Whether this ignore entry is temporary, i.e. [`IgnoreFlags::NO_SAVE`].
+    pub fn is_temporary(&self) -> bool {
+        self.flags.contains(IgnoreFlags::NO_SAVE)
+    }
+}
+
 list!(
     Notifies,
     "notify",
@@ -191,7 +409,7 @@ list!(
     "A nick on notify.",
     Notify {
         ["networks", "Networks to which this nick applies.", string] networks: super::SplitByCommas => impl Iterator<Item = &str>,
-        ["nick", "Nickname.", string] nick: HexString => &HexStr,
+        ["nick", "Nickname.", string] nick: Arc<HexStr> => &HexStr,
         ["flags", "Info flags.", int] flags: NotifyFlags => NotifyFlags,
         ["on", "Time when user came online.", time] online: OffsetDateTime => OffsetDateTime,
         ["off", "Time when user went offline.", time] offline: OffsetDateTime => OffsetDateTime,
@@ -199,6 +417,38 @@ list!(
     }
 );
 
+impl Notify {
+    /// Time when user came online, as a `SystemTime`.
+    pub fn online_system_time(&self) -> SystemTime {
+        super::to_system_time(self.online)
+    }
+
+    /// Time when user came online, converted to `offset`; see [`User::last_talk_at_offset`].
+    pub fn online_at_offset(&self, offset: UtcOffset) -> OffsetDateTime {
+        self.online.to_offset(offset)
+    }
+
+    /// Time when user went offline, as a `SystemTime`.
+    pub fn offline_system_time(&self) -> SystemTime {
+        super::to_system_time(self.offline)
+    }
+
+    /// Time when user went offline, converted to `offset`; see [`User::last_talk_at_offset`].
+    pub fn offline_at_offset(&self, offset: UtcOffset) -> OffsetDateTime {
+        self.offline.to_offset(offset)
+    }
+
+    /// Time when the user was last verified still online, as a `SystemTime`.
+    pub fn seen_system_time(&self) -> SystemTime {
+        super::to_system_time(self.seen)
+    }
+
+    /// Time when the user was last verified still online, converted to `offset`; see [`User::last_talk_at_offset`].
+    pub fn seen_at_offset(&self, offset: UtcOffset) -> OffsetDateTime {
+        self.seen.to_offset(offset)
+    }
+}
+
 bitflags! {
     /// Flags related to notify state.
     ///
@@ -222,13 +472,128 @@ list!(
     "List of users in the current [context](crate::PluginHandle::find_context).",
     "A user.",
     User {
-        ["account", "Account name. (HexChat 2.9.6+)", string] account: Option<HexString> => Option<&HexStr>,
+        ["account", "Account name. (HexChat 2.9.6+)", string] account: Option<Arc<HexStr>> => Option<&HexStr>,
         ["away", "Away status.", int] is_away: bool => bool,
         ["lasttalk", "Last time the user was seen talking.", time] last_talk: OffsetDateTime => OffsetDateTime,
-        ["nick", "Nickname.", string] nick: HexString => &HexStr,
-        ["host", "Hostname e.g. `\"user@host\"`.", string] host: Option<HexString> => Option<&HexStr>,
+        ["nick", "Nickname.", string] nick: Arc<HexStr> => &HexStr,
+        ["host", "Hostname e.g. `\"user@host\"`.", string] host: Option<Arc<HexStr>> => Option<&HexStr>,
         ["prefix", "Prefix character e.g. `'@'` or `'+'`.", string] prefix: Option<char> => Option<char>,
-        ["realname", "Realname.", string] realname: Option<HexString> => Option<&HexStr>,
+        ["realname", "Realname.", string] realname: Option<Arc<HexStr>> => Option<&HexStr>,
         ["selected", "Selected status in the user list, only works in the focused tab.", int] is_selected: bool => bool,
     }
 );
+
+impl User {
+    /// Last time the user was seen talking, as a `SystemTime`.
+    pub fn last_talk_system_time(&self) -> SystemTime {
+        super::to_system_time(self.last_talk)
+    }
+
+    /// Last time the user was seen talking, converted to `offset`, e.g. the user's local time zone.
+    ///
+    /// The local offset must be supplied by the caller, since `UtcOffset::current_local_offset`
+    /// always fails on some Unix platforms once the process has multiple threads, as HexChat does.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::list::User;
+    /// use time::UtcOffset;
+    ///
+    /// fn describe_last_talk(user: &User, offset: UtcOffset) -> String {
+    ///     let time = user.last_talk_at_offset(offset);
+    ///     format!("{} last spoke at {:02}:{:02}", user.nick(), time.hour(), time.minute())
+    /// }
+    /// ```
+    pub fn last_talk_at_offset(&self, offset: UtcOffset) -> OffsetDateTime {
+        self.last_talk.to_offset(offset)
+    }
+
+    /// Whether the user is a channel operator (or higher) in `channel`.
+    ///
+    /// This uses the channel's [`nick_prefixes`](Channel::nick_prefixes) and [`nick_modes`](Channel::nick_modes)
+    /// rather than assuming `'@'`, so it works on networks with non-standard prefixes,
+    /// and is also true for higher ranks such as `'~'` (owner) or `'&'` (admin).
+    ///
+    /// `channel` should be the channel that this user list was retrieved from.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::list::{Channel, Users};
+    ///
+    /// fn count_ops<P>(ph: PluginHandle<'_, P>, channel: &Channel) -> usize {
+    ///     match ph.get_list(Users) {
+    ///         Ok(users) => users.filter(|user| user.is_op(channel)).count(),
+    ///         Err(_) => 0,
+    ///     }
+    /// }
+    /// ```
+    pub fn is_op(&self, channel: &Channel) -> bool {
+        self.has_rank_at_least(channel, 'o')
+    }
+
+    /// Whether the user is voiced (or higher) in `channel`.
+    ///
+    /// Like [`User::is_op`], this uses the channel's [`nick_prefixes`](Channel::nick_prefixes) and [`nick_modes`](Channel::nick_modes).
+    pub fn is_voiced(&self, channel: &Channel) -> bool {
+        self.has_rank_at_least(channel, 'v')
+    }
+
+    /// Criteria for finding the query (direct message) tab with this user.
+    ///
+    /// A context is only found if a query with this user is already open.
+    /// Like [`Context::channel`], this prefers a query in the current server,
+    /// but falls back to a query with the same nick in any other server.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::list::User;
+    ///
+    /// fn print_in_query<P>(ph: PluginHandle<'_, P>, user: &User, message: &str) {
+    ///     if let Some(ctxt) = ph.find_context(user.query_context()) {
+    ///         ph.with_context(ctxt, || ph.print(message));
+    ///     }
+    /// }
+    /// ```
+    pub fn query_context(&self) -> Context<&HexStr> {
+        Context::channel(&self.nick)
+    }
+
+    fn has_rank_at_least(&self, channel: &Channel, mode: char) -> bool {
+        match self.prefix {
+            Some(prefix) => {
+                super::prefix_at_least(prefix, &channel.nick_prefixes, &channel.nick_modes, mode)
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_talk_at_offset_converts() {
+        let nick = HexStr::from_bytes_with_nul(b"alice\0").unwrap();
+        let user = User {
+            account: None,
+            is_away: false,
+            last_talk: OffsetDateTime::from_unix_timestamp(86400).unwrap(),
+            nick: Arc::from(nick),
+            host: None,
+            prefix: None,
+            realname: None,
+            is_selected: false,
+        };
+        let offset = UtcOffset::from_hms(2, 0, 0).unwrap();
+        let time = user.last_talk_at_offset(offset);
+        assert_eq!(time.offset(), offset);
+        assert_eq!(time.hour(), 2);
+        assert_eq!(time.unix_timestamp(), 86400);
+    }
+}
@@ -1,13 +1,448 @@
-//! Sending modes.
+//! Sending and parsing modes.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Not;
+
+use crate::rfc1459;
 
 /// Whether to add or remove a mode.
 ///
 /// Used with [`PluginHandle::send_modes`](crate::PluginHandle::send_modes).
 #[non_exhaustive]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Sign {
     /// Add the mode.
     Add,
     /// Remove the mode.
     Remove,
 }
+
+impl Sign {
+    /// Converts a `+` or `-` character to a `Sign`.
+    ///
+    /// Returns `None` for any other character.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::mode::Sign;
+    ///
+    /// assert_eq!(Sign::from_char('+'), Some(Sign::Add));
+    /// assert_eq!(Sign::from_char('-'), Some(Sign::Remove));
+    /// assert_eq!(Sign::from_char('o'), None);
+    /// ```
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '+' => Some(Self::Add),
+            '-' => Some(Self::Remove),
+            _ => None,
+        }
+    }
+
+    /// Converts this `Sign` to a `+` or `-` character.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::mode::Sign;
+    ///
+    /// assert_eq!(Sign::Add.as_char(), '+');
+    /// assert_eq!(Sign::Remove.as_char(), '-');
+    /// ```
+    pub fn as_char(self) -> char {
+        match self {
+            Self::Add => '+',
+            Self::Remove => '-',
+        }
+    }
+}
+
+/// Converts `true` to [`Sign::Add`] and `false` to [`Sign::Remove`].
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::mode::Sign;
+///
+/// let grant = false;
+/// assert_eq!(Sign::from(grant), Sign::Remove);
+/// assert_eq!(!Sign::from(grant), Sign::Add);
+/// assert!(bool::from(Sign::Add));
+/// ```
+impl From<bool> for Sign {
+    fn from(add: bool) -> Self {
+        if add {
+            Self::Add
+        } else {
+            Self::Remove
+        }
+    }
+}
+
+/// Converts [`Sign::Add`] to `true` and [`Sign::Remove`] to `false`.
+impl From<Sign> for bool {
+    fn from(sign: Sign) -> Self {
+        match sign {
+            Sign::Add => true,
+            Sign::Remove => false,
+        }
+    }
+}
+
+/// Swaps [`Sign::Add`] and [`Sign::Remove`].
+impl Not for Sign {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        match self {
+            Self::Add => Self::Remove,
+            Self::Remove => Self::Add,
+        }
+    }
+}
+
+impl fmt::Display for Sign {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
+}
+
+/// Parses a mode string like `+ov-b` into a sequence of signs and mode characters.
+///
+/// Mode characters that appear before any sign are treated as being added.
+///
+/// Mode parameters are not handled; only pass the mode characters themselves, e.g. `+ov` from `+ov alice bob`.
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::mode::{parse, Sign};
+///
+/// let modes: Vec<_> = parse("+ov-b").collect();
+/// assert_eq!(
+///     modes,
+///     [(Sign::Add, 'o'), (Sign::Add, 'v'), (Sign::Remove, 'b')],
+/// );
+/// ```
+pub fn parse(modes: &str) -> impl Iterator<Item = (Sign, char)> + '_ {
+    let mut sign = Sign::Add;
+    modes.chars().filter_map(move |c| match Sign::from_char(c) {
+        Some(s) => {
+            sign = s;
+            None
+        }
+        None => Some((sign, c)),
+    })
+}
+
+/// The channel modes assumed by [`ChannelModes::parse`], as described by [RFC 2811](https://tools.ietf.org/html/rfc2811#section-4).
+///
+/// This is in the same format as [`Channel::modes`](crate::list::Channel::modes).
+pub const DEFAULT_CHANMODES: &str = "beI,k,l,imnpst";
+
+/// The active modes of a channel, e.g. `+ntk secret`.
+///
+/// Returned from [`PluginHandle::channel_modes`](crate::PluginHandle::channel_modes).
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::mode::ChannelModes;
+///
+/// let modes = ChannelModes::parse("+ntkl secret 50");
+/// assert!(modes.contains('n'));
+/// assert!(!modes.contains('m'));
+/// assert_eq!(modes.key(), Some("secret"));
+/// assert_eq!(modes.limit(), Some(50));
+/// assert_eq!(modes.flags().collect::<String>(), "nt");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChannelModes {
+    modes: BTreeMap<char, Option<String>>,
+}
+
+impl ChannelModes {
+    /// Parses an active mode string, like HexChat's [`Modes`](crate::info::Modes) info.
+    ///
+    /// Which modes take parameters is determined by [`DEFAULT_CHANMODES`];
+    /// use [`ChannelModes::parse_with_chanmodes`] to use the server's modes instead.
+    pub fn parse(modes: &str) -> Self {
+        Self::parse_with_chanmodes(modes, DEFAULT_CHANMODES)
+    }
+
+    /// Parses an active mode string, using `chanmodes` to determine which modes take parameters.
+    ///
+    /// `chanmodes` is in the format of the `CHANMODES` `ISUPPORT` token, e.g. `"beI,k,l,imnpst"`,
+    /// as returned from [`Channel::modes`](crate::list::Channel::modes).
+    ///
+    /// If a mode takes a parameter but none is present (e.g. a hidden channel key), the mode is still set, without a parameter.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::mode::ChannelModes;
+    ///
+    /// let modes = ChannelModes::parse_with_chanmodes("+fnt #overflow", "beI,kf,l,imnpst");
+    /// assert_eq!(modes.param('f'), Some("#overflow"));
+    /// ```
+    pub fn parse_with_chanmodes(modes: &str, chanmodes: &str) -> Self {
+        let mut categories = chanmodes.split(',');
+        let always_param =
+            categories.next().unwrap_or("").to_owned() + categories.next().unwrap_or("");
+        let param_when_set = categories.next().unwrap_or("");
+
+        let mut words = modes.split_whitespace();
+        let mode_chars = words.next().unwrap_or("");
+        let mut params = words;
+
+        let mut result = BTreeMap::new();
+        for (sign, mode) in parse(mode_chars) {
+            let takes_param =
+                always_param.contains(mode) || (sign == Sign::Add && param_when_set.contains(mode));
+            let param = if takes_param { params.next() } else { None };
+            match sign {
+                Sign::Add => {
+                    result.insert(mode, param.map(ToOwned::to_owned));
+                }
+                Sign::Remove => {
+                    result.remove(&mode);
+                }
+            }
+        }
+
+        Self { modes: result }
+    }
+
+    /// Whether `mode` is set, with or without a parameter.
+    pub fn contains(&self, mode: char) -> bool {
+        self.modes.contains_key(&mode)
+    }
+
+    /// The parameter of `mode`, if it is set and has a parameter.
+    pub fn param(&self, mode: char) -> Option<&str> {
+        self.modes.get(&mode)?.as_deref()
+    }
+
+    /// The channel key (`+k`), if set and known.
+    pub fn key(&self) -> Option<&str> {
+        self.param('k')
+    }
+
+    /// The user limit (`+l`), if set.
+    ///
+    /// Returns `None` if the limit is not a valid number.
+    pub fn limit(&self) -> Option<u32> {
+        self.param('l')?.parse().ok()
+    }
+
+    /// Modes which are set without a parameter, in sorted order.
+    pub fn flags(&self) -> impl Iterator<Item = char> + '_ {
+        self.modes
+            .iter()
+            .filter(|(_, param)| param.is_none())
+            .map(|(&mode, _)| mode)
+    }
+
+    /// All modes which are set, with their parameters, in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = (char, Option<&str>)> + '_ {
+        self.modes
+            .iter()
+            .map(|(&mode, param)| (mode, param.as_deref()))
+    }
+}
+
+/// A `nick!user@host` mask, as used in bans and ignores, split into its components.
+///
+/// Missing components are treated as the wildcard `*`, so `"nick"` is equivalent to `"nick!*@*"`,
+/// and `"user@host"` is equivalent to `"*!user@host"`.
+/// Wildcards (`*` and `?`) within components are preserved.
+///
+/// Used by [`Ignore::mask_parts`](crate::list::Ignore::mask_parts).
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::mode::Hostmask;
+///
+/// let mask = Hostmask::parse("*!*@*.aol.com");
+/// assert_eq!(mask.nick(), "*");
+/// assert_eq!(mask.user(), "*");
+/// assert_eq!(mask.host(), "*.aol.com");
+/// assert!(mask.matches("Alice", "alice", "dialup.aol.com"));
+///
+/// let mask = Hostmask::parse("bob");
+/// assert_eq!(mask.to_string(), "bob!*@*");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Hostmask<'a> {
+    nick: &'a str,
+    user: &'a str,
+    host: &'a str,
+}
+
+impl<'a> Hostmask<'a> {
+    /// Splits a mask into its components.
+    pub fn parse(mask: &'a str) -> Self {
+        let (rest, host) = match mask.rsplit_once('@') {
+            Some((rest, host)) => (rest, Some(host)),
+            None => (mask, None),
+        };
+        let (nick, user) = match rest.split_once('!') {
+            Some((nick, user)) => (nick, Some(user)),
+            // `user@host` without a nick
+            None if host.is_some() => ("*", Some(rest)),
+            None => (rest, None),
+        };
+        Self {
+            nick,
+            user: user.unwrap_or("*"),
+            host: host.unwrap_or("*"),
+        }
+    }
+
+    /// The nick component.
+    pub fn nick(&self) -> &'a str {
+        self.nick
+    }
+
+    /// The user (ident) component.
+    pub fn user(&self) -> &'a str {
+        self.user
+    }
+
+    /// The host component.
+    pub fn host(&self) -> &'a str {
+        self.host
+    }
+
+    /// Whether a user matches this mask.
+    ///
+    /// `*` matches any number of characters and `?` matches exactly one.
+    /// Characters are compared using [RFC1459 casemapping](crate::rfc1459).
+    pub fn matches(&self, nick: &str, user: &str, host: &str) -> bool {
+        wildcard_match(self.nick, nick)
+            && wildcard_match(self.user, user)
+            && wildcard_match(self.host, host)
+    }
+}
+
+impl fmt::Display for Hostmask<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}!{}@{}", self.nick, self.user, self.host)
+    }
+}
+
+/// Matches `text` against `pattern`, where `*` matches any number of characters and `?` matches exactly one.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // position of the last `*` in `pattern`, and the position in `text` it is currently matched up to
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || to_lower(c) == to_lower(text[t]) => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    backtrack = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Lowercases `c` using RFC1459 casemapping, which only affects ASCII characters.
+fn to_lower(c: char) -> char {
+    match u8::try_from(c) {
+        Ok(b) if b.is_ascii() => char::from(rfc1459::to_lower(b)),
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_no_leading_sign() {
+        let modes: Vec<_> = parse("nt").collect();
+        assert_eq!(modes, [(Sign::Add, 'n'), (Sign::Add, 't')]);
+    }
+
+    #[test]
+    fn parse_repeated_signs() {
+        let modes: Vec<_> = parse("+-+o--v").collect();
+        assert_eq!(modes, [(Sign::Add, 'o'), (Sign::Remove, 'v')]);
+    }
+
+    #[test]
+    fn parse_empty() {
+        assert_eq!(parse("").count(), 0);
+        assert_eq!(parse("+-").count(), 0);
+    }
+
+    #[test]
+    fn channel_modes_params() {
+        let modes = ChannelModes::parse("+klnt key");
+        assert_eq!(modes.key(), Some("key"));
+        assert!(modes.contains('l'));
+        assert_eq!(modes.limit(), None);
+        assert_eq!(modes.flags().collect::<String>(), "lnt");
+
+        let modes = ChannelModes::parse("+nt-t+b *!*@*");
+        assert_eq!(
+            modes.iter().collect::<Vec<_>>(),
+            [('b', Some("*!*@*")), ('n', None)]
+        );
+
+        let modes = ChannelModes::parse_with_chanmodes("+jn 3:5", "b,k,jl,n");
+        assert_eq!(modes.param('j'), Some("3:5"));
+        assert_eq!(ChannelModes::parse(""), ChannelModes::default());
+    }
+
+    #[test]
+    fn hostmask_parse() {
+        let parts = |mask| {
+            let mask = Hostmask::parse(mask);
+            (mask.nick(), mask.user(), mask.host())
+        };
+        assert_eq!(parts("nick!user@host"), ("nick", "user", "host"));
+        assert_eq!(parts("nick"), ("nick", "*", "*"));
+        assert_eq!(parts("nick!user"), ("nick", "user", "*"));
+        assert_eq!(parts("user@host"), ("*", "user", "host"));
+        assert_eq!(parts("@host"), ("*", "", "host"));
+        assert_eq!(parts("*!*@*.aol.com"), ("*", "*", "*.aol.com"));
+    }
+
+    #[test]
+    fn hostmask_matches() {
+        let mask = Hostmask::parse("Nick[*]!?ser@*.example.*");
+        assert!(mask.matches("nick{away}", "user", "a.example.com"));
+        assert!(mask.matches("NICK[]", "xser", "b.c.example.org"));
+        assert!(!mask.matches("nick", "user", "a.example.com"));
+        assert!(!mask.matches("nick[]", "uuser", "a.example.com"));
+        assert!(!mask.matches("nick[]", "user", "example.com"));
+
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("a*b*c", "aXbYbZc"));
+        assert!(!wildcard_match("a*b", "aXbY"));
+        assert!(!wildcard_match("", "a"));
+        assert!(wildcard_match("caf?", "café"));
+        assert!(!wildcard_match("caf??", "café"));
+    }
+}
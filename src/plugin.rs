@@ -1,36 +1,68 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::convert::TryInto;
+use std::error::Error;
 use std::ffi::CStr;
+use std::fmt;
 use std::iter;
 use std::marker::PhantomData;
 use std::mem;
+use std::num::NonZeroU32;
+use std::ops::ControlFlow;
 use std::os::raw::{c_char, c_int, c_void};
 use std::ptr::{self, NonNull};
-use std::time::Duration;
-
-use time::OffsetDateTime;
-
-use crate::context::{Context, ContextHandle};
-use crate::event::print::PrintEvent;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::command::CommandError;
+use crate::context::{Context, ContextError, ContextHandle, ContextKey, SavedContext};
+use crate::event::print::{
+    Beep, Disconnected, GenericMessage, HookablePrintEvent, PrintEvent, ServerError,
+    SignalPrintEvent, WhoisAuthenticated, WhoisAwayLine, WhoisChannelOperLine, WhoisEnd,
+    WhoisIdentified, WhoisIdleLine, WhoisIdleLineWithSignon, WhoisNameLine, WhoisRealHost,
+    WhoisServerLine, WhoisSpecial,
+};
 use crate::event::server::ServerEvent;
 use crate::event::EventAttrs;
 use crate::ffi::{
-    hexchat_event_attrs, hexchat_list, int_to_result, word_to_iter, ListElem, RawPluginHandle,
+    hexchat_event_attrs, hexchat_hook, hexchat_list, int_to_result, set_lossy_utf8,
+    timestamp_to_datetime, with_padded_words, word_to_iter, ListElem, LossyArena, RawPluginHandle,
 };
-use crate::gui::FakePluginHandle;
-use crate::hook::{Eat, HookHandle, Priority, Timer};
+use crate::format::truncate;
+use crate::gui::{FakePluginGuard, FakePluginHandle};
+use crate::hook::{Eat, HookError, HookHandle, Priority, Timer};
 use crate::info::private::FromInfoValue;
-use crate::info::Info;
+use crate::info::{
+    AwayReason, ConnectionInfo, HexchatVersion, Hostname, Info, Modes, Network, Nick, Server,
+    Version,
+};
 use crate::iter::{CurriedItem, LendingIterator};
 use crate::list::private::FromListElem;
-use crate::list::List;
-use crate::mode::Sign;
+use crate::list::private::Interner;
+use crate::list::{
+    Channel, Channels, List, ListError, ListSnapshot, Notifies, Notify, RawListElem,
+};
+use crate::mode::{ChannelModes, Sign};
 use crate::pref::private::{FromPrefValue, PrefValue};
+use crate::pref::special::ServerId;
 use crate::pref::Pref;
-use crate::state::{catch_and_log_unwind, with_plugin_state};
-use crate::str::private::AsCStrArray;
-use crate::str::{HexStr, HexString, IntoCStr, IntoCStrArray};
-use crate::strip::{MircColors, StrippedStr, TextAttrs};
+use crate::pref::{PrefData, PrefInfo};
+use crate::rfc1459::{self, IrcName};
+#[cfg(debug_assertions)]
+use crate::state::register_command_hook;
+use crate::state::{
+    catch_and_log_unwind, forget_live_hook, hook_data, prints_to_stderr, record_hook_panic,
+    register_hook_data, release_hook_data, set_deinit_prints_to_stderr, set_hook_data_owner,
+    set_hook_panic_limit, with_plugin_state,
+};
+use crate::str::private::{AsCStrArray, IntoCStrImpl};
+use crate::str::{with_formatted_cstr, HexStr, HexString, IntoCStr, IntoCStrArray};
+use crate::strip::{
+    may_need_stripping, remove_control_chars, MircColors, StripMode, StrippedStr, TextAttrs,
+};
+use crate::whois::WhoisResult;
 
 /// Must be implemented by all HexChat plugins.
 ///
@@ -105,11 +137,48 @@ use crate::strip::{MircColors, StrippedStr, TextAttrs};
 /// }
 /// ```
 pub trait Plugin: Default + 'static {
+    /// The name of your plugin, shown in HexChat's plugin list.
+    ///
+    /// Only used by the single-argument form of [`export_plugin`](crate::export_plugin),
+    /// which requires this to be non-empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::{Plugin, PluginHandle, export_plugin};
+    ///
+    /// #[derive(Default)]
+    /// struct MyPlugin;
+    ///
+    /// impl Plugin for MyPlugin {
+    ///     const NAME: &'static str = env!("CARGO_PKG_NAME");
+    ///     const DESCRIPTION: &'static str = env!("CARGO_PKG_DESCRIPTION");
+    ///     const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+    ///
+    ///     fn init(&self, _: PluginHandle<'_, Self>) {}
+    /// }
+    ///
+    /// export_plugin!(MyPlugin);
+    /// ```
+    const NAME: &'static str = "";
+
+    /// A short description of your plugin, shown in HexChat's plugin list.
+    ///
+    /// Only used by the single-argument form of [`export_plugin`](crate::export_plugin).
+    const DESCRIPTION: &'static str = "";
+
+    /// The version of your plugin, shown in HexChat's plugin list.
+    ///
+    /// Only used by the single-argument form of [`export_plugin`](crate::export_plugin).
+    const VERSION: &'static str = "";
+
     /// Initialize your plugin.
     ///
     /// Use this function to perform any work that should be done when your plugin is loaded,
     /// such as registering hooks or printing startup messages.
     ///
+    /// If initialization can fail, implement [`Plugin::try_init`] instead.
+    ///
     /// Analogous to [`hexchat_plugin_init`](https://hexchat.readthedocs.io/en/latest/plugins.html#sample-plugin).
     ///
     /// # Examples
@@ -126,7 +195,42 @@ pub trait Plugin: Default + 'static {
     ///     }
     /// }
     /// ```
-    fn init(&self, ph: PluginHandle<'_, Self>);
+    fn init(&self, ph: PluginHandle<'_, Self>) {
+        let _ = ph;
+    }
+
+    /// Initialize your plugin, returning an error if it cannot be loaded.
+    ///
+    /// If this returns `Err`, the error is printed and HexChat is told that the plugin failed to load,
+    /// so it will be unloaded without calling [`Plugin::deinit`].
+    /// Any hooks registered before the error are removed by HexChat.
+    ///
+    /// By default, this calls [`Plugin::init`] and returns `Ok`.
+    ///
+    /// Analogous to [`hexchat_plugin_init`](https://hexchat.readthedocs.io/en/latest/plugins.html#sample-plugin).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::fs;
+    /// use hexavalent::{InitError, Plugin, PluginHandle};
+    ///
+    /// #[derive(Default)]
+    /// struct MyPlugin;
+    ///
+    /// impl Plugin for MyPlugin {
+    ///     fn try_init(&self, ph: PluginHandle<'_, Self>) -> Result<(), InitError> {
+    ///         let config = fs::read_to_string("my_plugin.conf")
+    ///             .map_err(|e| InitError::new(format!("failed to read config: {}", e)))?;
+    ///         ph.print(format!("Loaded config with {} lines.", config.lines().count()));
+    ///         Ok(())
+    ///     }
+    /// }
+    /// ```
+    fn try_init(&self, ph: PluginHandle<'_, Self>) -> Result<(), InitError> {
+        self.init(ph);
+        Ok(())
+    }
 
     /// Deinitialize your plugin.
     ///
@@ -159,6 +263,31 @@ pub trait Plugin: Default + 'static {
     }
 }
 
+/// An error which prevents a plugin from loading.
+///
+/// Returned from [`Plugin::try_init`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitError {
+    message: String,
+}
+
+impl InitError {
+    /// Creates an error with a message describing why the plugin failed to load.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for InitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for InitError {}
+
 /// Interacts with HexChat's plugin API.
 ///
 /// Passed into [`Plugin::init`], [`Plugin::deinit`], and hook callbacks such as [`PluginHandle::hook_command`].
@@ -193,261 +322,824 @@ impl<'ph, P> PluginHandle<'ph, P> {
 impl<'ph, P> PluginHandle<'ph, P> {
     /// Prints text to the current [context](crate::PluginHandle::find_context). Text may contain mIRC color codes and formatting.
     ///
+    /// Strings returned from HexChat ([`HexStr`] and [`HexString`]) are already null-terminated,
+    /// so like C strings, they can be printed without allocating; see [`IntoCStr`].
+    ///
     /// Analogous to [`hexchat_print`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_print).
     ///
     /// # Examples
     ///
     /// ```rust
     /// use hexavalent::PluginHandle;
+    /// use hexavalent::info::Channel;
     ///
     /// fn say_hello<P>(ph: PluginHandle<'_, P>) {
     ///     ph.print(c"hello!");
     /// }
+    ///
+    /// fn print_channel_name<P>(ph: PluginHandle<'_, P>) {
+    ///     // `HexString` is passed to HexChat without copying
+    ///     ph.print(ph.get_info(Channel));
+    /// }
     /// ```
     pub fn print(self, text: impl IntoCStr) {
         let text = text.into_cstr();
+
+        if prints_to_stderr() {
+            eprintln!("{}", text.to_string_lossy());
+            return;
+        }
+
         // Safety: `text` is a null-terminated C string
         unsafe {
             self.raw.hexchat_print(text.as_ptr());
         }
     }
 
-    /// Executes a command in the current [context](crate::PluginHandle::find_context) as if it were typed into HexChat's input box after a `/`.
+    /// Prints multi-line text to the current [context](crate::PluginHandle::find_context), one message per line.
     ///
-    /// Analogous to [`hexchat_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_command).
+    /// Lines are split on `\n` (and `\r\n`); a single trailing newline does not produce an empty message.
+    ///
+    /// Analogous to calling [`hexchat_print`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_print) for each line.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use hexavalent::PluginHandle;
-    /// use hexavalent::str::HexStr;
     ///
-    /// fn op_user<P>(ph: PluginHandle<'_, P>, username: &HexStr) {
-    ///     // do not include the leading slash
-    ///     ph.command(format!("OP {}", username));
+    /// fn print_help<P>(ph: PluginHandle<'_, P>) {
+    ///     ph.print_lines("Commands:\n  /foo\n  /bar\n");
     /// }
     /// ```
-    pub fn command(self, cmd: impl IntoCStr) {
-        let cmd = cmd.into_cstr();
-        // Safety: `cmd` is a null-terminated C string
-        unsafe {
-            self.raw.hexchat_command(cmd.as_ptr());
+    pub fn print_lines(self, text: &str) {
+        for line in text.lines() {
+            self.print(line);
         }
     }
 
-    /// Emits a print event in the current [context](crate::PluginHandle::find_context).
+    /// Prints untrusted text to the current [context](crate::PluginHandle::find_context), so that it is displayed literally.
     ///
-    /// See the [`event::print`](crate::event::print) submodule for a list of print events.
-    ///
-    /// Note that this triggers any print hooks registered for the event, so be careful to avoid infinite recursion
-    /// when calling this function from hook callbacks such as [`PluginHandle::hook_print`].
+    /// Formatting control characters (colors, bold, etc.) are removed with [`remove_control_chars`] before printing,
+    /// so text from other users cannot change how it, or anything printed after it on the same line, is displayed.
+    /// Note that `%` is not interpreted by [`PluginHandle::print`], so it does not need to be escaped.
     ///
-    /// Analogous to [`hexchat_emit_print`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_emit_print).
+    /// Analogous to [`hexchat_print`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_print).
     ///
     /// # Examples
     ///
     /// ```rust
     /// use hexavalent::PluginHandle;
-    /// use hexavalent::event::print::ChannelMessage;
-    /// use hexavalent::str::HexStr;
     ///
-    /// fn print_fake_message<P>(ph: PluginHandle<'_, P>, user: &HexStr, text: &str) -> Result<(), ()> {
-    ///     ph.emit_print(ChannelMessage, (user, text, c"@", c"$"))
+    /// fn print_topic<P>(ph: PluginHandle<'_, P>, topic: &str) {
+    ///     ph.print_plain(&format!("Topic: {}", topic));
     /// }
     /// ```
-    pub fn emit_print<E: PrintEvent<N>, const N: usize>(
-        self,
-        event: E,
-        // todo this should just pass in E::ARGS directly, but you can't use type params in consts
-        args: impl IntoCStrArray<N>,
-    ) -> Result<(), ()> {
-        let _ = event;
-
-        let args = args.into_cstrs();
-        let args = args.as_cstr_array();
-
-        assert!(
-            args.len() <= 4,
-            "bug in hexavalent - more than 4 args from PrintEvent"
-        );
-
-        let args: [*const c_char; 4] = [
-            args.get(0).map_or_else(ptr::null, |a| a.as_ptr()),
-            args.get(1).map_or_else(ptr::null, |a| a.as_ptr()),
-            args.get(2).map_or_else(ptr::null, |a| a.as_ptr()),
-            args.get(3).map_or_else(ptr::null, |a| a.as_ptr()),
-        ];
+    pub fn print_plain(self, text: &str) {
+        self.print(remove_control_chars(text).as_ref());
+    }
 
-        // Safety: `NAME` and `args` are null-terminated C strings; vararg list is null-terminated
-        int_to_result(unsafe {
-            self.raw.hexchat_emit_print(
-                E::NAME.as_ptr(),
-                args[0],
-                args[1],
-                args[2],
-                args[3],
-                ptr::null::<c_char>(),
-            )
-        })
+    /// Prints text to the current [context](crate::PluginHandle::find_context), truncating it if it is longer than `max_bytes`.
+    ///
+    /// Text is shortened with [`format::truncate`](crate::format::truncate), which cuts at a character boundary and appends an ellipsis (`…`).
+    /// Use this when printing text from external sources, which could be long enough to hang HexChat's GUI;
+    /// [`MAX_PRINT_BYTES`](crate::format::MAX_PRINT_BYTES) is a reasonable default limit.
+    ///
+    /// Analogous to [`hexchat_print`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_print).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::format::MAX_PRINT_BYTES;
+    ///
+    /// fn print_api_response<P>(ph: PluginHandle<'_, P>, body: &str) {
+    ///     ph.print_truncated(body, MAX_PRINT_BYTES);
+    /// }
+    /// ```
+    pub fn print_truncated(self, text: &str, max_bytes: usize) {
+        self.print(truncate(text, max_bytes).as_ref());
     }
 
-    /// Emits a print event in the current [context](crate::PluginHandle::find_context), specifying its attributes.
+    /// Prints text to the current [context](crate::PluginHandle::find_context), specifying its attributes.
     ///
-    /// See the [`event::print`](crate::event::print) submodule for a list of print events.
+    /// Like [`PluginHandle::print`], text before the first tab character is shown in the nick column.
+    /// Unlike [`PluginHandle::print`], the message is shown with the time from `attrs` rather than the current time,
+    /// which is useful for replaying logs.
     ///
-    /// Note that this triggers any print hooks registered for the event, so be careful to avoid infinite recursion
-    /// when calling this function from hook callbacks such as [`PluginHandle::hook_print_attrs`].
+    /// This emits the [`GenericMessage`] print event, so it triggers any print hooks registered for that event.
     ///
-    /// Analogous to [`hexchat_emit_print_attrs`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_emit_print_attrs).
+    /// Returns `Err` if HexChat fails to emit the event.
+    ///
+    /// Analogous to [`hexchat_emit_print_attrs`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_emit_print_attrs)
+    /// with `"Generic Message"`.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use hexavalent::PluginHandle;
     /// use hexavalent::event::EventAttrs;
-    /// use hexavalent::event::print::ChannelMessage;
     /// use time::OffsetDateTime;
     ///
     /// # #[cfg(not(feature = "__unstable_ircv3_line_in_event_attrs"))]
-    /// fn print_fake_message_like_its_1979<P>(ph: PluginHandle<'_, P>, user: &str, text: &str) -> Result<(), ()> {
-    ///     let attrs = EventAttrs::new(OffsetDateTime::from_unix_timestamp(86400 * 365 * 10).unwrap());
-    ///     ph.emit_print_attrs(ChannelMessage, attrs, (user, text, c"@", c"$"))
+    /// fn replay_log_line<P>(ph: PluginHandle<'_, P>, timestamp: i64, nick: &str, text: &str) -> Result<(), ()> {
+    ///     let time = OffsetDateTime::from_unix_timestamp(timestamp).map_err(|_| ())?;
+    ///     ph.print_attrs(&format!("<{}>\t{}", nick, text), EventAttrs::new(time))
     /// }
     /// ```
-    pub fn emit_print_attrs<E: PrintEvent<N>, const N: usize>(
-        self,
-        event: E,
-        attrs: EventAttrs<'_>,
-        args: impl IntoCStrArray<N>,
-    ) -> Result<(), ()> {
-        let _ = event;
-
-        let args = args.into_cstrs();
-        let args = args.as_cstr_array();
-
-        assert!(
-            args.len() <= 4,
-            "bug in hexavalent - more than 4 args from PrintEvent"
-        );
-
-        let args: [*const c_char; 4] = [
-            args.get(0).map_or_else(ptr::null, |a| a.as_ptr()),
-            args.get(1).map_or_else(ptr::null, |a| a.as_ptr()),
-            args.get(2).map_or_else(ptr::null, |a| a.as_ptr()),
-            args.get(3).map_or_else(ptr::null, |a| a.as_ptr()),
-        ];
-
-        int_to_result(unsafe {
-            // Safety: no preconditions
-            let event_attrs = self.raw.hexchat_event_attrs_create();
-            // Safety: `event_attrs` does not escape`
-            defer! { self.raw.hexchat_event_attrs_free(event_attrs) };
-
-            ptr::write(
-                &mut (*event_attrs).server_time_utc as *mut _,
-                attrs.time().unix_timestamp(),
-            );
-
-            #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
-            let ircv3_line = crate::str::private::IntoCStrImpl::into_cstr(attrs.ircv3_line());
-            #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
-            ptr::write(
-                &mut (*event_attrs).ircv3_line as *mut _,
-                ircv3_line.as_ptr(),
-            );
-
-            // Safety: `event_attrs` is fully initialized; `NAME` and `args` are null-terminated C strings, varags list is null-terminated
-            self.raw.hexchat_emit_print_attrs(
-                event_attrs,
-                E::NAME.as_ptr(),
-                args[0],
-                args[1],
-                args[2],
-                args[3],
-                ptr::null::<c_char>(),
-            )
-        })
+    pub fn print_attrs(self, text: &str, attrs: EventAttrs<'_>) -> Result<(), ()> {
+        let (left, right) = text.split_once('\t').unwrap_or(("", text));
+        self.emit_print_attrs(GenericMessage, attrs, (left, right))
     }
 
-    /// Sends channel mode changes to targets in the current [context](crate::PluginHandle::find_context).
+    /// Executes a command in the current [context](crate::PluginHandle::find_context) as if it were typed into HexChat's input box after a `/`.
     ///
-    /// Analogous to [`hexchat_send_modes`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_send_modes).
+    /// Analogous to [`hexchat_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_command).
     ///
     /// # Examples
     ///
     /// ```rust
     /// use hexavalent::PluginHandle;
-    /// use hexavalent::mode::Sign;
+    /// use hexavalent::str::HexStr;
     ///
-    /// fn op_users<P>(ph: PluginHandle<'_, P>, users: &[&str]) {
-    ///     // sends `MODE <users> +o`
-    ///     ph.send_modes(users.into_iter().copied(), Sign::Add, b'o');
+    /// fn op_user<P>(ph: PluginHandle<'_, P>, username: &HexStr) {
+    ///     // do not include the leading slash
+    ///     ph.command(format!("OP {}", username));
     /// }
     /// ```
-    pub fn send_modes(
-        self,
-        targets: impl IntoIterator<Item = impl IntoCStr>,
-        sign: Sign,
-        mode_char: u8,
-    ) {
-        let targets: Vec<_> = targets.into_iter().map(|t| t.into_cstr()).collect();
-        let mut targets: Vec<*const c_char> = targets.iter().map(|t| t.as_ptr()).collect();
-        let ntargets = targets
-            .len()
-            .try_into()
-            .unwrap_or_else(|e| panic!("Too many send_modes targets: {}", e));
-
-        let sign = match sign {
-            Sign::Add => b'+',
-            Sign::Remove => b'-',
-        } as c_char;
-
-        let mode = mode_char as c_char;
-
-        // Safety: `targets` is an array of valid null-terminated C strings with `ntargets` length
+    pub fn command(self, cmd: impl IntoCStr) {
+        let cmd = cmd.into_cstr();
+        // Safety: `cmd` is a null-terminated C string
         unsafe {
-            self.raw
-                .hexchat_send_modes(targets.as_mut_ptr(), ntargets, 0, sign, mode)
+            self.raw.hexchat_command(cmd.as_ptr());
         }
     }
 
-    /// Sends channel mode changes to a target in the current [context](crate::PluginHandle::find_context).
+    /// Executes a command in the current [context](crate::PluginHandle::find_context), returning an error if it appears to fail.
     ///
-    /// Behaves the same as [`PluginHandle::send_modes`],
-    /// but is more efficient when you only need to send mode changes to one target.
+    /// `hexchat_command` does not report failure, so this watches for [`ServerError`](crate::event::print::ServerError)
+    /// and [`GenericMessage`](crate::event::print::GenericMessage) print events emitted while the command runs,
+    /// and returns the first one seen as an error.
     ///
-    /// Analogous to [`hexchat_send_modes`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_send_modes).
+    /// This detection is best-effort:
+    /// - some failures (such as unknown commands) are printed directly, without emitting any print event, so they are not detected
+    /// - commands which send a message to the server only fail once the server replies, after this function has returned
+    /// - commands which legitimately print a `Generic Message`, such as `/echo`-like commands from other plugins, are reported as errors
+    ///
+    /// Analogous to [`hexchat_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_command).
     ///
     /// # Examples
     ///
     /// ```rust
     /// use hexavalent::PluginHandle;
-    /// use hexavalent::mode::Sign;
     ///
-    /// fn unban_user<P>(ph: PluginHandle<'_, P>, user: &str) {
-    ///     // sends `MODE <user> -b`
-    ///     ph.send_mode(user, Sign::Remove, b'b');
+    /// fn load_script<P>(ph: PluginHandle<'_, P>, path: &str) {
+    ///     if let Err(e) = ph.command_checked(format!("LOAD {}", path)) {
+    ///         ph.print(format!("Failed to load {}: {}", path, e));
+    ///     }
     /// }
     /// ```
-    pub fn send_mode(self, target: impl IntoCStr, sign: Sign, mode_char: u8) {
-        let target = target.into_cstr();
-        let mut targets: [*const c_char; 1] = [target.as_ptr()];
-        let ntargets = 1;
+    pub fn command_checked(self, cmd: impl IntoCStr) -> Result<(), CommandError> {
+        let error = Rc::new(RefCell::new(None));
+
+        let server_error = self.hook_print_boxed(ServerError, Priority::Highest, {
+            let error = Rc::clone(&error);
+            move |_, _, [text]| {
+                error
+                    .borrow_mut()
+                    .get_or_insert_with(|| CommandError::ServerError(text.to_owned()));
+                Eat::None
+            }
+        });
+        let generic_message = self.hook_print_boxed(GenericMessage, Priority::Highest, {
+            let error = Rc::clone(&error);
+            move |_, _, [left, right]| {
+                error.borrow_mut().get_or_insert_with(|| {
+                    CommandError::GenericMessage(left.to_owned(), right.to_owned())
+                });
+                Eat::None
+            }
+        });
 
-        let sign = match sign {
-            Sign::Add => b'+',
-            Sign::Remove => b'-',
-        } as c_char;
+        self.command(cmd);
 
-        let mode = mode_char as c_char;
+        self.unhook(server_error);
+        self.unhook(generic_message);
 
-        // Safety: `targets` is an array of valid null-terminated C strings with `ntargets` length
-        unsafe {
+        let error = error.borrow_mut().take();
+        match error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Executes each command in sequence, as if they were typed into HexChat's input box after a `/`.
+    ///
+    /// Behaves the same as calling [`PluginHandle::command`] for each command,
+    /// but borrowed strings are converted into a single reused buffer, so they do not allocate individually.
+    ///
+    /// Analogous to [`hexchat_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_command), called once per command.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn leave_channels<P>(ph: PluginHandle<'_, P>) {
+    ///     ph.commands(["PART #rust", "PART #hexchat", "AWAY gone"]);
+    /// }
+    /// ```
+    pub fn commands(self, cmds: impl IntoIterator<Item = impl IntoCStr>) {
+        let mut scratch = Vec::new();
+        for cmd in cmds {
+            cmd.with_cstr(&mut scratch, |cmd| {
+                // Safety: `cmd` is a null-terminated C string
+                unsafe {
+                    self.raw.hexchat_command(cmd.as_ptr());
+                }
+            });
+        }
+    }
+
+    /// Executes a command formatted from `args`, as if it were typed in HexChat.
+    ///
+    /// Behaves the same as [`PluginHandle::command`], but short commands are formatted into a buffer on the stack,
+    /// so they do not allocate.
+    ///
+    /// Analogous to [`hexchat_commandf`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_commandf).
+    ///
+    /// # Panics
+    ///
+    /// If the formatted command contains an interior null byte.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::str::HexStr;
+    ///
+    /// fn op_user<P>(ph: PluginHandle<'_, P>, username: &HexStr) {
+    ///     ph.commandf(format_args!("OP {}", username));
+    /// }
+    /// ```
+    pub fn commandf(self, args: fmt::Arguments<'_>) {
+        with_formatted_cstr(args, |cmd| {
+            // Safety: `cmd` is a null-terminated C string
+            unsafe {
+                self.raw.hexchat_command(cmd.as_ptr());
+            }
+        })
+    }
+
+    /// Injects a line in the current server [context](crate::PluginHandle::find_context) as if it were received from the server.
+    ///
+    /// This triggers any server hooks registered for the line's command, e.g. via [`PluginHandle::hook_server`],
+    /// so it is useful for testing hook callbacks with synthetic IRC lines.
+    ///
+    /// This runs HexChat's `/recv` command, so it requires an active server context; otherwise nothing happens.
+    ///
+    /// Returns `Err` if `line` contains a line break, which would cause HexChat to treat it as multiple lines.
+    ///
+    /// Analogous to [`hexchat_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_command) with `RECV <line>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn fake_privmsg<P>(ph: PluginHandle<'_, P>) -> Result<(), ()> {
+    ///     ph.fake_server_line(":alice!alice@example.com PRIVMSG #rust :hello world")
+    /// }
+    /// ```
+    pub fn fake_server_line(self, line: &str) -> Result<(), ()> {
+        if line.contains(['\r', '\n']) {
+            return Err(());
+        }
+        self.command(format!("RECV {}", line));
+        Ok(())
+    }
+
+    /// Joins a channel on the current server, optionally with a key.
+    ///
+    /// Returns `Err` without sending anything if `channel` or `key` is empty or contains whitespace or control characters.
+    ///
+    /// Analogous to [`hexchat_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_command) with `JOIN <channel> [key]`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn join_secret_channel<P>(ph: PluginHandle<'_, P>) -> Result<(), ()> {
+    ///     ph.join("#secret", Some("hunter2"))
+    /// }
+    /// ```
+    pub fn join(self, channel: &str, key: Option<&str>) -> Result<(), ()> {
+        let channel = command_word(channel)?;
+        match key {
+            Some(key) => self.commandf(format_args!("JOIN {} {}", channel, command_word(key)?)),
+            None => self.commandf(format_args!("JOIN {}", channel)),
+        }
+        Ok(())
+    }
+
+    /// Leaves a channel on the current server, optionally with a reason.
+    ///
+    /// Returns `Err` without sending anything if `channel` is empty or contains whitespace or control characters,
+    /// or if `reason` contains a line break or null byte.
+    ///
+    /// Analogous to [`hexchat_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_command) with `PART <channel> [reason]`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn leave<P>(ph: PluginHandle<'_, P>, channel: &str) -> Result<(), ()> {
+    ///     ph.part(channel, Some("bye!"))
+    /// }
+    /// ```
+    pub fn part(self, channel: &str, reason: Option<&str>) -> Result<(), ()> {
+        let channel = command_word(channel)?;
+        match reason {
+            Some(reason) => {
+                self.commandf(format_args!("PART {} {}", channel, command_text(reason)?))
+            }
+            None => self.commandf(format_args!("PART {}", channel)),
+        }
+        Ok(())
+    }
+
+    /// Changes your nickname on the current server.
+    ///
+    /// Returns `Err` without sending anything if `new_nick` is empty or contains whitespace or control characters.
+    ///
+    /// Analogous to [`hexchat_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_command) with `NICK <new_nick>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn go_away<P>(ph: PluginHandle<'_, P>) -> Result<(), ()> {
+    ///     ph.nick("alice|away")
+    /// }
+    /// ```
+    pub fn nick(self, new_nick: &str) -> Result<(), ()> {
+        self.commandf(format_args!("NICK {}", command_word(new_nick)?));
+        Ok(())
+    }
+
+    /// Sends a message to a user or channel on the current server.
+    ///
+    /// Returns `Err` without sending anything if `target` is empty or contains whitespace or control characters,
+    /// or if `text` is empty or contains a line break or null byte.
+    ///
+    /// Analogous to [`hexchat_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_command) with `MSG <target> <text>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn greet<P>(ph: PluginHandle<'_, P>, nick: &str) -> Result<(), ()> {
+    ///     ph.msg(nick, "hello!")
+    /// }
+    /// ```
+    pub fn msg(self, target: &str, text: &str) -> Result<(), ()> {
+        let target = command_word(target)?;
+        let text = command_text(text).and_then(non_empty)?;
+        self.commandf(format_args!("MSG {} {}", target, text));
+        Ok(())
+    }
+
+    /// Sends a notice to a user or channel on the current server.
+    ///
+    /// Returns `Err` without sending anything if `target` is empty or contains whitespace or control characters,
+    /// or if `text` is empty or contains a line break or null byte.
+    ///
+    /// Analogous to [`hexchat_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_command) with `NOTICE <target> <text>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn warn<P>(ph: PluginHandle<'_, P>, nick: &str) -> Result<(), ()> {
+    ///     ph.notice(nick, "please stop flooding")
+    /// }
+    /// ```
+    pub fn notice(self, target: &str, text: &str) -> Result<(), ()> {
+        let target = command_word(target)?;
+        let text = command_text(text).and_then(non_empty)?;
+        self.commandf(format_args!("NOTICE {} {}", target, text));
+        Ok(())
+    }
+
+    /// Kicks a user from a channel on the current server, optionally with a reason.
+    ///
+    /// Unlike HexChat's `/KICK` command, which always acts on the current channel, this sends the `KICK` message directly,
+    /// so `channel` does not need to be the current channel.
+    ///
+    /// Returns `Err` without sending anything if `channel` or `nick` is empty or contains whitespace or control characters,
+    /// or if `reason` contains a line break or null byte.
+    ///
+    /// Analogous to [`hexchat_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_command)
+    /// with `QUOTE KICK <channel> <nick> :[reason]`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn kick_spammer<P>(ph: PluginHandle<'_, P>, nick: &str) -> Result<(), ()> {
+    ///     ph.kick("#rust", nick, Some("spam"))
+    /// }
+    /// ```
+    pub fn kick(self, channel: &str, nick: &str, reason: Option<&str>) -> Result<(), ()> {
+        let channel = command_word(channel)?;
+        let nick = command_word(nick)?;
+        match reason {
+            Some(reason) => self.commandf(format_args!(
+                "QUOTE KICK {} {} :{}",
+                channel,
+                nick,
+                command_text(reason)?
+            )),
+            None => self.commandf(format_args!("QUOTE KICK {} {}", channel, nick)),
+        }
+        Ok(())
+    }
+
+    /// Emits a print event in the current [context](crate::PluginHandle::find_context).
+    ///
+    /// See the [`event::print`](crate::event::print) submodule for a list of print events.
+    ///
+    /// Note that this triggers any print hooks registered for the event, so be careful to avoid infinite recursion
+    /// when calling this function from hook callbacks such as [`PluginHandle::hook_print`].
+    ///
+    /// `args` may be a tuple, whose elements can each be a different [`IntoCStr`] type,
+    /// or an array of a single [`IntoCStr`] type; see [`IntoCStrArray`].
+    ///
+    /// Returns `Err` if HexChat fails to emit the event.
+    ///
+    /// Analogous to [`hexchat_emit_print`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_emit_print).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::event::print::ChannelMessage;
+    /// use hexavalent::str::HexStr;
+    ///
+    /// fn print_fake_message<P>(ph: PluginHandle<'_, P>, user: &HexStr, text: String) -> Result<(), ()> {
+    ///     ph.emit_print(ChannelMessage, (user, text, c"@", c"$"))
+    /// }
+    ///
+    /// fn print_fake_message_from_strs<P>(ph: PluginHandle<'_, P>, user: &str, text: &str) -> Result<(), ()> {
+    ///     ph.emit_print(ChannelMessage, [user, text, "@", "$"])
+    /// }
+    /// ```
+    pub fn emit_print<E: PrintEvent<N>, const N: usize>(
+        self,
+        event: E,
+        // todo this should just pass in E::ARGS directly, but you can't use type params in consts
+        args: impl IntoCStrArray<N>,
+    ) -> Result<(), ()> {
+        let _ = event;
+
+        let args = args.into_cstrs();
+        let args = args.as_cstr_array();
+
+        assert!(
+            args.len() <= 4,
+            "bug in hexavalent - more than 4 args from PrintEvent"
+        );
+
+        let args: [*const c_char; 4] = [
+            args.get(0).map_or_else(ptr::null, |a| a.as_ptr()),
+            args.get(1).map_or_else(ptr::null, |a| a.as_ptr()),
+            args.get(2).map_or_else(ptr::null, |a| a.as_ptr()),
+            args.get(3).map_or_else(ptr::null, |a| a.as_ptr()),
+        ];
+
+        // Safety: `NAME` and `args` are null-terminated C strings; vararg list is null-terminated
+        int_to_result(unsafe {
+            self.raw.hexchat_emit_print(
+                E::NAME.as_ptr(),
+                args[0],
+                args[1],
+                args[2],
+                args[3],
+                ptr::null::<c_char>(),
+            )
+        })
+    }
+
+    /// Emits a print event in the current [context](crate::PluginHandle::find_context), specifying its attributes.
+    ///
+    /// See the [`event::print`](crate::event::print) submodule for a list of print events.
+    ///
+    /// Note that this triggers any print hooks registered for the event, so be careful to avoid infinite recursion
+    /// when calling this function from hook callbacks such as [`PluginHandle::hook_print_attrs`].
+    ///
+    /// Returns `Err` if HexChat fails to emit the event.
+    ///
+    /// Analogous to [`hexchat_emit_print_attrs`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_emit_print_attrs).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::event::EventAttrs;
+    /// use hexavalent::event::print::ChannelMessage;
+    /// use time::OffsetDateTime;
+    ///
+    /// # #[cfg(not(feature = "__unstable_ircv3_line_in_event_attrs"))]
+    /// fn print_fake_message_like_its_1979<P>(ph: PluginHandle<'_, P>, user: &str, text: &str) -> Result<(), ()> {
+    ///     let attrs = EventAttrs::new(OffsetDateTime::from_unix_timestamp(86400 * 365 * 10).unwrap());
+    ///     ph.emit_print_attrs(ChannelMessage, attrs, (user, text, c"@", c"$"))
+    /// }
+    /// ```
+    pub fn emit_print_attrs<E: PrintEvent<N>, const N: usize>(
+        self,
+        event: E,
+        attrs: EventAttrs<'_>,
+        args: impl IntoCStrArray<N>,
+    ) -> Result<(), ()> {
+        let _ = event;
+
+        // Safety: no preconditions
+        let event_attrs = unsafe { self.raw.hexchat_event_attrs_create() };
+        // Safety: `event_attrs` does not escape
+        defer! { unsafe { self.raw.hexchat_event_attrs_free(event_attrs) } };
+
+        // Safety: `event_attrs` was returned from `hexchat_event_attrs_create` and has not been freed
+        unsafe { self.emit_print_with_event_attrs(event_attrs, E::NAME, attrs, args) }
+    }
+
+    /// Emits a sequence of print events in the current [context](crate::PluginHandle::find_context), specifying their attributes.
+    ///
+    /// Equivalent to calling [`PluginHandle::emit_print_attrs`] for each item,
+    /// but allocates the underlying `hexchat_event_attrs` only once, which is useful when replaying many lines at once.
+    ///
+    /// All items must be the same event type `E`.
+    /// To emit a sequence of different events, call [`PluginHandle::emit_print_attrs`] for each one.
+    ///
+    /// Stops and returns `Err` at the first event which HexChat fails to emit.
+    ///
+    /// Analogous to [`hexchat_emit_print_attrs`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_emit_print_attrs),
+    /// called repeatedly with the same attributes object.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::event::EventAttrs;
+    /// use hexavalent::event::print::ChannelMessage;
+    /// use time::OffsetDateTime;
+    ///
+    /// # #[cfg(not(feature = "__unstable_ircv3_line_in_event_attrs"))]
+    /// fn replay_log<P>(ph: PluginHandle<'_, P>, log: &[(OffsetDateTime, String, String)]) -> Result<(), ()> {
+    ///     ph.emit_prints_attrs(log.iter().map(|(time, user, text)| {
+    ///         (ChannelMessage, EventAttrs::new(*time), [user.as_str(), text.as_str(), "", ""])
+    ///     }))
+    /// }
+    /// ```
+    pub fn emit_prints_attrs<'a, E: PrintEvent<N>, A: IntoCStrArray<N>, const N: usize>(
+        self,
+        items: impl IntoIterator<Item = (E, EventAttrs<'a>, A)>,
+    ) -> Result<(), ()> {
+        // Safety: no preconditions
+        let event_attrs = unsafe { self.raw.hexchat_event_attrs_create() };
+        // Safety: `event_attrs` does not escape
+        defer! { unsafe { self.raw.hexchat_event_attrs_free(event_attrs) } };
+
+        for (event, attrs, args) in items {
+            let _ = event;
+
+            // Safety: `event_attrs` was returned from `hexchat_event_attrs_create` and has not been freed
+            unsafe { self.emit_print_with_event_attrs(event_attrs, E::NAME, attrs, args) }?;
+        }
+
+        Ok(())
+    }
+
+    /// Fills in `event_attrs` from `attrs`, then emits the print event `name`.
+    ///
+    /// # Safety
+    ///
+    /// `event_attrs` must be a valid pointer returned by `hexchat_event_attrs_create`.
+    unsafe fn emit_print_with_event_attrs<const N: usize>(
+        self,
+        event_attrs: *mut hexchat_event_attrs,
+        name: &CStr,
+        attrs: EventAttrs<'_>,
+        args: impl IntoCStrArray<N>,
+    ) -> Result<(), ()> {
+        let args = args.into_cstrs();
+        let args = args.as_cstr_array();
+
+        assert!(
+            args.len() <= 4,
+            "bug in hexavalent - more than 4 args from PrintEvent"
+        );
+
+        let args: [*const c_char; 4] = [
+            args.get(0).map_or_else(ptr::null, |a| a.as_ptr()),
+            args.get(1).map_or_else(ptr::null, |a| a.as_ptr()),
+            args.get(2).map_or_else(ptr::null, |a| a.as_ptr()),
+            args.get(3).map_or_else(ptr::null, |a| a.as_ptr()),
+        ];
+
+        #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
+        let ircv3_line = IntoCStrImpl::into_cstr(attrs.ircv3_line());
+
+        // Safety: `event_attrs` is valid, per precondition; fields are plain data, so overwriting them does not leak
+        unsafe {
+            ptr::write(
+                &mut (*event_attrs).server_time_utc as *mut _,
+                attrs.time().unix_timestamp(),
+            );
+
+            #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
+            ptr::write(
+                &mut (*event_attrs).ircv3_line as *mut _,
+                ircv3_line.as_ptr(),
+            );
+        }
+
+        // Safety: `event_attrs` is fully initialized; `name` and `args` are null-terminated C strings, varags list is null-terminated
+        int_to_result(unsafe {
+            self.raw.hexchat_emit_print_attrs(
+                event_attrs,
+                name.as_ptr(),
+                args[0],
+                args[1],
+                args[2],
+                args[3],
+                ptr::null::<c_char>(),
+            )
+        })
+    }
+
+    /// Emits a print event which has no fields, such as [`Beep`](crate::event::print::Beep), in the current [context](crate::PluginHandle::find_context).
+    ///
+    /// These events print nothing; emitting them only triggers their side effects and any print hooks registered for them.
+    ///
+    /// Returns `Err` if HexChat fails to emit the event.
+    ///
+    /// Analogous to [`hexchat_emit_print`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_emit_print) with no arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::event::print::OpenDialog;
+    ///
+    /// fn signal_dialog<P>(ph: PluginHandle<'_, P>) -> Result<(), ()> {
+    ///     ph.emit_signal(OpenDialog)
+    /// }
+    /// ```
+    pub fn emit_signal<E: SignalPrintEvent>(self, event: E) -> Result<(), ()> {
+        self.emit_print(event, ())
+    }
+
+    /// Emits the [`Beep`](crate::event::print::Beep) print event in the current [context](crate::PluginHandle::find_context).
+    ///
+    /// Returns `Err` if HexChat fails to emit the event.
+    ///
+    /// Analogous to [`hexchat_emit_print`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_emit_print) with `"Beep"`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn alert<P>(ph: PluginHandle<'_, P>) {
+    ///     if ph.beep().is_err() {
+    ///         ph.print("Failed to beep.");
+    ///     }
+    /// }
+    /// ```
+    pub fn beep(self) -> Result<(), ()> {
+        self.emit_signal(Beep)
+    }
+
+    /// Sends channel mode changes to targets in the current [context](crate::PluginHandle::find_context).
+    ///
+    /// Analogous to [`hexchat_send_modes`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_send_modes).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::mode::Sign;
+    ///
+    /// fn op_users<P>(ph: PluginHandle<'_, P>, users: &[&str]) {
+    ///     // sends `MODE <users> +o`
+    ///     ph.send_modes(users.into_iter().copied(), Sign::Add, b'o');
+    /// }
+    /// ```
+    pub fn send_modes(
+        self,
+        targets: impl IntoIterator<Item = impl IntoCStr>,
+        sign: Sign,
+        mode_char: u8,
+    ) {
+        let targets: Vec<_> = targets.into_iter().map(|t| t.into_cstr()).collect();
+        let mut targets: Vec<*const c_char> = targets.iter().map(|t| t.as_ptr()).collect();
+        let ntargets = targets
+            .len()
+            .try_into()
+            .unwrap_or_else(|e| panic!("Too many send_modes targets: {}", e));
+
+        let sign = match sign {
+            Sign::Add => b'+',
+            Sign::Remove => b'-',
+        } as c_char;
+
+        let mode = mode_char as c_char;
+
+        // Safety: `targets` is an array of valid null-terminated C strings with `ntargets` length
+        unsafe {
+            self.raw
+                .hexchat_send_modes(targets.as_mut_ptr(), ntargets, 0, sign, mode)
+        }
+    }
+
+    /// Sends channel mode changes to a target in the current [context](crate::PluginHandle::find_context).
+    ///
+    /// Behaves the same as [`PluginHandle::send_modes`],
+    /// but is more efficient when you only need to send mode changes to one target.
+    ///
+    /// Analogous to [`hexchat_send_modes`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_send_modes).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::mode::Sign;
+    ///
+    /// fn unban_user<P>(ph: PluginHandle<'_, P>, user: &str) {
+    ///     // sends `MODE <user> -b`
+    ///     ph.send_mode(user, Sign::Remove, b'b');
+    /// }
+    /// ```
+    pub fn send_mode(self, target: impl IntoCStr, sign: Sign, mode_char: u8) {
+        let target = target.into_cstr();
+        let mut targets: [*const c_char; 1] = [target.as_ptr()];
+        let ntargets = 1;
+
+        let sign = match sign {
+            Sign::Add => b'+',
+            Sign::Remove => b'-',
+        } as c_char;
+
+        let mode = mode_char as c_char;
+
+        // Safety: `targets` is an array of valid null-terminated C strings with `ntargets` length
+        unsafe {
             self.raw
                 .hexchat_send_modes(targets.as_mut_ptr(), ntargets, 0, sign, mode)
         }
     }
 
+    /// Sends a batch of heterogeneous channel mode changes in the current [context](crate::PluginHandle::find_context).
+    ///
+    /// Consecutive changes with the same sign and mode character are grouped together,
+    /// and each group is sent with [`PluginHandle::send_modes`],
+    /// so HexChat packs as many targets into each `MODE` line as the server allows.
+    /// Changes are never reordered, so e.g. `+o`, `-o`, `+o` on the same user leaves them opped.
+    ///
+    /// Analogous to [`hexchat_send_modes`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_send_modes).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::mode::Sign;
+    ///
+    /// fn promote_and_demote<P>(ph: PluginHandle<'_, P>) {
+    ///     // sends `MODE +oo alice carol` and `MODE -v bob`
+    ///     ph.send_mode_changes([
+    ///         (Sign::Add, b'o', "alice"),
+    ///         (Sign::Add, b'o', "carol"),
+    ///         (Sign::Remove, b'v', "bob"),
+    ///     ]);
+    /// }
+    /// ```
+    pub fn send_mode_changes(self, changes: impl IntoIterator<Item = (Sign, u8, impl IntoCStr)>) {
+        let groups = group_consecutive(
+            changes
+                .into_iter()
+                .map(|(sign, mode_char, target)| ((sign, mode_char), target.into_cstr())),
+        );
+
+        for ((sign, mode_char), targets) in groups {
+            self.send_modes(targets.iter().map(|t| &**t), sign, mode_char);
+        }
+    }
+
     /// Performs a comparison of nicknames or channel names, compliant with RFC1459.
     ///
     /// [RFC1459 says](https://tools.ietf.org/html/rfc1459#section-2.2):
@@ -457,6 +1149,11 @@ impl<'ph, P> PluginHandle<'ph, P> {
     /// > respectively. This is a critical issue when determining the
     /// > equivalence of two nicknames.
     ///
+    /// Passing [`&HexStr`](crate::str::HexStr) (e.g. nicknames from hook arguments or lists) or `&CStr` does not allocate;
+    /// `&str` and `String` must be copied to add a null terminator. See [`IntoCStr`].
+    ///
+    /// See also [`rfc1459`](crate::rfc1459), which performs the same comparison without calling into HexChat.
+    ///
     /// Analogous to [`hexchat_nickcmp`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_nickcmp).
     ///
     /// # Examples
@@ -505,8 +1202,90 @@ impl<'ph, P> PluginHandle<'ph, P> {
         mirc: MircColors,
         attrs: TextAttrs,
     ) -> Result<StrippedStr<'ph>, ()> {
-        let str = str.into_cstr();
+        self.strip_cstr(&str.into_cstr(), mirc, attrs)
+    }
+
+    /// Strips formatting from a string, as selected by a single [`StripMode`].
+    ///
+    /// Behaves the same as [`PluginHandle::strip`], which takes [`MircColors`] and [`TextAttrs`] separately.
+    ///
+    /// Analogous to [`hexchat_strip`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_strip).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::strip::StripMode;
+    ///
+    /// fn strip_mode_example<P>(ph: PluginHandle<'_, P>) {
+    ///     let orig = "\x0312Blue\x03 \x02Bold!\x02";
+    ///
+    ///     let strip_all = ph.strip_mode(orig, StripMode::All);
+    ///     assert_eq!(strip_all.unwrap().as_str(), "Blue Bold!");
+    ///
+    ///     let strip_attrs = ph.strip_mode(orig, StripMode::AttrsOnly);
+    ///     assert_eq!(strip_attrs.unwrap().as_str(), "\x0312Blue\x03 Bold!");
+    /// }
+    /// ```
+    pub fn strip_mode(self, str: impl IntoCStr, mode: StripMode) -> Result<StrippedStr<'ph>, ()> {
+        let (mirc, attrs) = mode.into_parts();
+        self.strip(str, mirc, attrs)
+    }
+
+    /// Strips formatting from each string in a batch, returning an owned result for each one.
+    ///
+    /// Behaves the same as calling [`PluginHandle::strip`] on each string, but reuses a single buffer
+    /// to convert the strings to C strings, and does not call into HexChat for strings with no formatting codes.
+    ///
+    /// A failure to strip one string (including a string which contains a null byte) does not affect the others.
+    ///
+    /// Analogous to calling [`hexchat_strip`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_strip) for each string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::strip::{MircColors, TextAttrs};
+    ///
+    /// fn strip_all_example<P>(ph: PluginHandle<'_, P>) {
+    ///     let stripped = ph.strip_all(&["\x0312Blue", "plain", "nul\0"], MircColors::Remove, TextAttrs::Remove);
+    ///     assert_eq!(stripped[0].as_ref().unwrap().as_str(), "Blue");
+    ///     assert_eq!(stripped[1].as_ref().unwrap().as_str(), "plain");
+    ///     assert!(stripped[2].is_err());
+    /// }
+    /// ```
+    pub fn strip_all(
+        self,
+        strs: &[&str],
+        mirc: MircColors,
+        attrs: TextAttrs,
+    ) -> Vec<Result<HexString, ()>> {
+        let mut scratch = Vec::new();
+
+        strs.iter()
+            .map(|&str| {
+                if str.contains('\0') {
+                    return Err(());
+                }
+                IntoCStrImpl::with_cstr(str, &mut scratch, |cstr| {
+                    if may_need_stripping(str, mirc, attrs) {
+                        Ok((*self.strip_cstr(cstr, mirc, attrs)?).to_owned())
+                    } else {
+                        let hex = HexStr::from_cstr(cstr)
+                            .unwrap_or_else(|e| panic!("Invalid UTF8 from `&str`: {}", e));
+                        Ok(hex.to_owned())
+                    }
+                })
+            })
+            .collect()
+    }
 
+    fn strip_cstr(
+        self,
+        str: &CStr,
+        mirc: MircColors,
+        attrs: TextAttrs,
+    ) -> Result<StrippedStr<'ph>, ()> {
         let mirc_flag = match mirc {
             MircColors::Keep => 0,
             MircColors::Remove => 1,
@@ -525,15 +1304,111 @@ impl<'ph, P> PluginHandle<'ph, P> {
             None => return Err(()),
         };
 
-        // Safety: hexchat_strip returns a valid pointer or null
-        let raw_str = unsafe { CStr::from_ptr(stripped_ptr.as_ptr()) };
-
-        // Safety: `stripped_ptr` points to a valid utf8 bytes; is not used after this
-        let stripped = unsafe { StrippedStr::new(self.raw, raw_str) }
-            .unwrap_or_else(|e| panic!("Invalid UTF8 from `hexchat_strip`: {}", e));
+        // Safety: hexchat_strip returns a valid pointer or null; `stripped_ptr` is not used after this
+        let stripped = unsafe { StrippedStr::new(self.raw, stripped_ptr) };
 
         Ok(stripped)
     }
+
+    /// Strips mIRC colors and/or text attributes (bold, underline, etc.) from a string,
+    /// without calling into HexChat if there is nothing to strip.
+    ///
+    /// Behaves the same as [`PluginHandle::strip`], but returns `Cow::Borrowed` if `str` contains no formatting codes,
+    /// so stripping plain text does not allocate.
+    ///
+    /// Analogous to [`hexchat_strip`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_strip).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::borrow::Cow;
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::strip::{MircColors, TextAttrs};
+    ///
+    /// fn strip_cow_example<P>(ph: PluginHandle<'_, P>) {
+    ///     let plain = ph.strip_cow("no formatting", MircColors::Remove, TextAttrs::Remove);
+    ///     assert!(matches!(plain, Ok(Cow::Borrowed("no formatting"))));
+    ///
+    ///     let bold = ph.strip_cow("\x02Bold!\x02", MircColors::Remove, TextAttrs::Remove);
+    ///     assert_eq!(bold.unwrap(), "Bold!");
+    /// }
+    /// ```
+    pub fn strip_cow<'a>(
+        self,
+        str: &'a str,
+        mirc: MircColors,
+        attrs: TextAttrs,
+    ) -> Result<Cow<'a, str>, ()> {
+        if !may_need_stripping(str, mirc, attrs) {
+            return Ok(Cow::Borrowed(str));
+        }
+
+        let stripped = self.strip(str, mirc, attrs)?;
+        Ok(Cow::Owned(stripped.as_str().to_owned()))
+    }
+
+    /// Sets whether invalid UTF-8 in strings from HexChat is replaced with `U+FFFD REPLACEMENT CHARACTER`, instead of panicking.
+    ///
+    /// HexChat usually converts text from IRC to UTF-8, but some strings (e.g. from networks using other encodings,
+    /// or from misconfigured servers) may still be invalid. By default, this causes a panic,
+    /// which is caught and logged, but aborts the hook callback that received the string.
+    ///
+    /// When enabled, invalid strings are decoded lossily (like [`String::from_utf8_lossy`])
+    /// in hook arguments and [event attributes](crate::event::EventAttrs), [lists](crate::PluginHandle::get_list),
+    /// [info](crate::PluginHandle::get_info), [preferences](crate::PluginHandle::get_pref),
+    /// [stripped strings](crate::PluginHandle::strip), and [plugin preferences](crate::PluginHandle::pluginpref_get_str)
+    /// (including their [names](crate::PluginHandle::pluginpref_list)).
+    /// Strings which are already valid UTF-8 are not copied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::{Plugin, PluginHandle};
+    ///
+    /// #[derive(Default)]
+    /// struct MyPlugin;
+    ///
+    /// impl Plugin for MyPlugin {
+    ///     fn init(&self, ph: PluginHandle<'_, Self>) {
+    ///         ph.set_lossy_utf8(true);
+    ///     }
+    /// }
+    /// ```
+    pub fn set_lossy_utf8(self, lossy: bool) {
+        set_lossy_utf8(lossy);
+    }
+
+    /// Sets whether [`PluginHandle::print`] writes to stderr instead of the current tab while [`Plugin::deinit`] is running.
+    ///
+    /// HexChat does not tell plugins whether they are being unloaded because HexChat is quitting or because they are being reloaded,
+    /// so messages printed during `deinit` (e.g. "Unloading...") appear every time the plugin is reloaded.
+    /// Enabling this keeps those messages out of HexChat while still making them available when running HexChat from a terminal.
+    ///
+    /// This only affects `print` (and functions which call it, like [`PluginHandle::print_lines`]), not print events.
+    /// It is reset to `false` after the plugin is unloaded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::{Plugin, PluginHandle};
+    ///
+    /// #[derive(Default)]
+    /// struct MyPlugin;
+    ///
+    /// impl Plugin for MyPlugin {
+    ///     fn init(&self, ph: PluginHandle<'_, Self>) {
+    ///         ph.set_deinit_prints_to_stderr(true);
+    ///     }
+    ///
+    ///     fn deinit(&self, ph: PluginHandle<'_, Self>) {
+    ///         // only shown on stderr
+    ///         ph.print("Unloading...");
+    ///     }
+    /// }
+    /// ```
+    pub fn set_deinit_prints_to_stderr(self, to_stderr: bool) {
+        set_deinit_prints_to_stderr(to_stderr);
+    }
 }
 
 /// [Getting Information](https://hexchat.readthedocs.io/en/latest/plugins.html#getting-information)
@@ -550,7 +1425,7 @@ impl<'ph, P> PluginHandle<'ph, P> {
     ///
     /// ```rust
     /// use hexavalent::PluginHandle;
-    /// use hexavalent::info::{AwayReason, Channel};
+    /// use hexavalent::info::{AwayReason, Channel, IsAway};
     /// use hexavalent::str::HexString;
     ///
     /// fn current_channel<P>(ph: PluginHandle<'_, P>) -> HexString {
@@ -560,12 +1435,36 @@ impl<'ph, P> PluginHandle<'ph, P> {
     /// fn current_away_reason<P>(ph: PluginHandle<'_, P>) -> Option<HexString> {
     ///     ph.get_info(AwayReason)
     /// }
+    ///
+    /// fn is_away<P>(ph: PluginHandle<'_, P>) -> bool {
+    ///     ph.get_info(IsAway)
+    /// }
     /// ```
     pub fn get_info<I: Info>(self, info: I) -> <I as Info>::Type {
         self.get_info_with(info, FromInfoValue::from_info_value)
     }
 
-    fn get_info_with<I: Info, R>(
+    /// Gets information based on the current [context](crate::PluginHandle::find_context), passing the raw result to a function.
+    ///
+    /// Behaves the same as [`PluginHandle::get_info`], but avoids allocating a `String` to hold the value.
+    /// `f` receives `None` if HexChat returned no value.
+    ///
+    /// Note that `f` is a function pointer, so it cannot capture any variables.
+    /// This is required because the string is owned by HexChat, and could be invalidated by interacting with HexChat while it is borrowed.
+    ///
+    /// Analogous to [`hexchat_get_info`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_get_info).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::info::Channel;
+    ///
+    /// fn in_channel_tab<P>(ph: PluginHandle<'_, P>) -> bool {
+    ///     ph.get_info_with(Channel, |channel| channel.map_or(false, |c| c.starts_with('#')))
+    /// }
+    /// ```
+    pub fn get_info_with<I: Info, R>(
         self,
         info: I,
         // Note: this must be a fn pointer as this api returns a pointer to memory owned by hexchat,
@@ -584,10 +1483,144 @@ impl<'ph, P> PluginHandle<'ph, P> {
         // Safety: pointer returned from hexchat_get_info is null or valid; str does not outlive this function
         let str = unsafe { CStr::from_ptr(ptr) };
 
-        let str = HexStr::from_cstr(str)
-            .unwrap_or_else(|e| panic!("Invalid UTF8 from `hexchat_get_info`: {}", e));
+        let arena = LossyArena::default();
+        f(Some(arena.hexstr(str, "hexchat_get_info")))
+    }
+
+    /// Gets information based on the current [context](crate::PluginHandle::find_context), by its raw key.
+    ///
+    /// Behaves the same as [`PluginHandle::get_info`], but allows reading keys which don't have a corresponding type
+    /// in the [`info`](crate::info) submodule, such as keys added in newer versions of HexChat.
+    ///
+    /// Returns `None` if HexChat returned no value.
+    /// Also returns `None` for `"win_ptr"` and `"gtkwin_ptr"`, since they are pointers rather than strings.
+    ///
+    /// Analogous to [`hexchat_get_info`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_get_info).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::str::HexString;
+    ///
+    /// fn current_network<P>(ph: PluginHandle<'_, P>) -> Option<HexString> {
+    ///     ph.get_info_dynamic("network")
+    /// }
+    /// ```
+    pub fn get_info_dynamic(self, key: impl IntoCStr) -> Option<HexString> {
+        let key = key.into_cstr();
+
+        if matches!(key.to_bytes(), b"win_ptr" | b"gtkwin_ptr") {
+            return None;
+        }
+
+        // Safety: `key` is a null-terminated C string
+        let ptr = unsafe { self.raw.hexchat_get_info(key.as_ptr()) };
+
+        if ptr.is_null() {
+            return None;
+        }
+
+        // Safety: pointer returned from hexchat_get_info is null or valid, and points to a string for all keys but those excluded above;
+        // str does not outlive this function
+        let str = unsafe { CStr::from_ptr(ptr) };
+
+        let arena = LossyArena::default();
+        Some(arena.hexstr(str, "hexchat_get_info").to_owned())
+    }
+
+    /// Gets the version of HexChat that the plugin is running in.
+    ///
+    /// Returns `None` if the version string could not be parsed.
+    ///
+    /// Analogous to [`hexchat_get_info`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_get_info) with `"version"`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::info::HexchatVersion;
+    ///
+    /// fn supports_channel_keys<P>(ph: PluginHandle<'_, P>) -> bool {
+    ///     ph.hexchat_version()
+    ///         .map_or(false, |version| version >= HexchatVersion::new(2, 9, 6))
+    /// }
+    /// ```
+    pub fn hexchat_version(self) -> Option<HexchatVersion> {
+        self.get_info_with(Version, |version| {
+            version.and_then(|version| HexchatVersion::parse(version))
+        })
+    }
+
+    /// Gets connection-related info about the current [context](crate::PluginHandle::find_context) in one call.
+    ///
+    /// Unlike [`PluginHandle::get_info`], this does not panic if HexChat does not provide a value for
+    /// a non-optional info, e.g. [`Nick`](crate::info::Nick); the corresponding field is `None` instead.
+    ///
+    /// Analogous to [`hexchat_get_info`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_get_info)
+    /// with `"nick"`, `"network"`, `"server"`, `"host"`, and `"away"`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn print_status<P>(ph: PluginHandle<'_, P>) {
+    ///     let info = ph.connection_info();
+    ///     let nick = info.nick.as_ref().map_or("<none>", |nick| nick.as_str());
+    ///     let network = info.network.as_ref().map_or("<unknown>", |network| network.as_str());
+    ///     match info.away_reason {
+    ///         Some(reason) => ph.print(format!("{} on {} (away: {})", nick, network, reason)),
+    ///         None => ph.print(format!("{} on {}", nick, network)),
+    ///     }
+    /// }
+    /// ```
+    pub fn connection_info(self) -> ConnectionInfo {
+        fn to_owned(value: Option<&HexStr>) -> Option<HexString> {
+            value.map(ToOwned::to_owned)
+        }
+
+        ConnectionInfo {
+            nick: self.get_info_with(Nick, to_owned),
+            network: self.get_info_with(Network, to_owned),
+            server: self.get_info_with(Server, to_owned),
+            hostname: self.get_info_with(Hostname, to_owned),
+            away_reason: self.get_info_with(AwayReason, to_owned),
+        }
+    }
 
-        f(Some(str))
+    /// Gets the active modes of the current channel.
+    ///
+    /// Which modes take parameters is determined by the server's `CHANMODES`, as in [`Channel::modes`],
+    /// falling back to [`DEFAULT_CHANMODES`](crate::mode::DEFAULT_CHANMODES) if the current server is not found.
+    ///
+    /// Returns `None` if the modes are not known, e.g. if the current context is not a channel.
+    ///
+    /// Analogous to [`hexchat_get_info`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_get_info) with `"modes"`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn is_moderated<P>(ph: PluginHandle<'_, P>) -> bool {
+    ///     ph.channel_modes().map_or(false, |modes| modes.contains('m'))
+    /// }
+    /// ```
+    pub fn channel_modes(self) -> Option<ChannelModes> {
+        let modes = self.get_info(Modes)?;
+        let server_chanmodes = self.current_server_id().and_then(|id| {
+            self.get_list(Channels)
+                .ok()?
+                .find(|channel| channel.server_id() == id)
+                .map(|channel| channel.modes().to_owned())
+        });
+        Some(match server_chanmodes {
+            Some(chanmodes) if !chanmodes.is_empty() => {
+                ChannelModes::parse_with_chanmodes(&modes, &chanmodes)
+            }
+            _ => ChannelModes::parse(&modes),
+        })
     }
 
     /// Gets settings information from HexChat, as available with `/set`.
@@ -614,6 +1647,31 @@ impl<'ph, P> PluginHandle<'ph, P> {
         self.get_pref_value_with(pref, |value| value.and_then(FromPrefValue::from_pref_value))
     }
 
+    /// Gets the value of a preference whose type is only known at runtime, e.g. from [`pref::ALL`](crate::pref::ALL).
+    ///
+    /// Analogous to [`hexchat_get_prefs`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_get_prefs).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::pref;
+    ///
+    /// fn print_all_settings<P>(ph: PluginHandle<'_, P>) {
+    ///     for info in pref::ALL {
+    ///         match ph.get_pref_data(*info) {
+    ///             Ok(value) => ph.print(format!("{} = {}", info.name(), value)),
+    ///             Err(()) => ph.print(format!("{} is unavailable", info.name())),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn get_pref_data(self, pref: PrefInfo) -> Result<PrefData, ()> {
+        self.get_pref_value_by_name_with(pref.name_cstr(), |value| {
+            value.map(PrefData::from_pref_value)
+        })
+    }
+
     fn get_pref_value_with<Pr: Pref, R>(
         self,
         pref: Pr,
@@ -623,15 +1681,26 @@ impl<'ph, P> PluginHandle<'ph, P> {
     ) -> R {
         let _ = pref;
 
+        self.get_pref_value_by_name_with(Pr::NAME, f)
+    }
+
+    fn get_pref_value_by_name_with<R>(
+        self,
+        name: &CStr,
+        // Note: this must be a fn pointer, see `get_pref_value_with`.
+        f: fn(Result<PrefValue<'_>, ()>) -> R,
+    ) -> R {
         let mut string = ptr::null();
         let mut int = 0;
 
-        // Safety: NAME is a null-terminated C string
+        // Safety: name is a null-terminated C string
         let result = unsafe {
             self.raw
-                .hexchat_get_prefs(Pr::NAME.as_ptr(), &mut string, &mut int)
+                .hexchat_get_prefs(name.as_ptr(), &mut string, &mut int)
         };
 
+        let arena = LossyArena::default();
+
         // https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_get_prefs
         let value = match result {
             1 => {
@@ -640,10 +1709,7 @@ impl<'ph, P> PluginHandle<'ph, P> {
                 // Safety: hexchat_get_prefs sets a valid string or null, temporary does not outlive this function
                 let str = unsafe { CStr::from_ptr(string) };
 
-                let str = HexStr::from_cstr(str)
-                    .unwrap_or_else(|e| panic!("Invalid UTF8 from `hexchat_get_prefs`: {}", e));
-
-                PrefValue::Str(str)
+                PrefValue::Str(arena.hexstr(str, "hexchat_get_prefs"))
             }
             2 => PrefValue::Int(int),
             3 => PrefValue::Bool(int != 0),
@@ -653,10 +1719,53 @@ impl<'ph, P> PluginHandle<'ph, P> {
         f(Ok(value))
     }
 
+    /// Gets the unique ID of the server for the current [context](crate::PluginHandle::find_context).
+    ///
+    /// This is the same ID as [`Channel::server_id`](crate::list::Channel::server_id),
+    /// so it can be used to key per-connection state, e.g. from a [`PluginHandle::hook_server`] callback,
+    /// which runs in the context of the server where the event was received.
+    ///
+    /// Returns `None` if the ID is not available.
+    ///
+    /// Analogous to [`hexchat_get_prefs`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_get_prefs) with `"id"`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use std::collections::HashMap;
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::event::server::Privmsg;
+    /// use hexavalent::hook::{Eat, Priority};
+    ///
+    /// #[derive(Default)]
+    /// struct MyPlugin {
+    ///     messages_per_server: RefCell<HashMap<i32, u32>>,
+    /// }
+    ///
+    /// fn count_messages(ph: PluginHandle<'_, MyPlugin>) {
+    ///     ph.hook_server(Privmsg, Priority::Normal, |plugin, ph, args| {
+    ///         if let Some(id) = ph.current_server_id() {
+    ///             *plugin.messages_per_server.borrow_mut().entry(id).or_default() += 1;
+    ///         }
+    ///         Eat::None
+    ///     });
+    /// }
+    /// ```
+    pub fn current_server_id(self) -> Option<i32> {
+        self.get_pref(ServerId).ok()
+    }
+
     /// Gets a list of information, possibly specific to the current [context](crate::PluginHandle::find_context).
     ///
     /// See the [`list`](crate::list) submodule for a list of lists.
     ///
+    /// Returns an empty iterator if the list is empty or not available in the current context,
+    /// and [`ListError::Unknown`] if HexChat does not recognize the list (e.g. on an old version of HexChat).
+    ///
+    /// The iterator is lazy: each element is read from HexChat when `next` is called,
+    /// and HexChat's list is freed when the iterator is dropped.
+    ///
     /// Analogous to [`hexchat_list_get`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_list_get) and related functions.
     ///
     /// # Examples
@@ -669,7 +1778,7 @@ impl<'ph, P> PluginHandle<'ph, P> {
     /// fn print_all_users_in_all_channels<P>(ph: PluginHandle<'_, P>) {
     ///     let channels = match ph.get_list(Channels) {
     ///         Ok(channels) => channels,
-    ///         Err(()) => return ph.print(c"Failed to get channels!"),
+    ///         Err(e) => return ph.print(format!("Failed to get channels: {}", e)),
     ///     };
     ///     for channel in channels {
     ///         let ctxt = match ph.find_context(Context::fully_qualified(channel.servname(), channel.name())) {
@@ -681,7 +1790,7 @@ impl<'ph, P> PluginHandle<'ph, P> {
     ///         };
     ///         let users = match ph.with_context(ctxt, || ph.get_list(Users)) {
     ///             Ok(users) => users,
-    ///             Err(()) => {
+    ///             Err(_) => {
     ///                 ph.print(format!("Failed to find users in {} on server {}, skipping.", channel.name(), channel.servname()));
     ///                 continue;
     ///             }
@@ -696,16 +1805,240 @@ impl<'ph, P> PluginHandle<'ph, P> {
     pub fn get_list<L: List>(
         self,
         list: L,
-    ) -> Result<impl Iterator<Item = <L as List>::Elem> + 'ph, ()> {
+    ) -> Result<impl Iterator<Item = <L as List>::Elem> + 'ph, ListError> {
+        // Safety: `ListElem`s are immediately consumed by `from_list_elem`, so they can't be invalidated
+        let mut iter = unsafe { self.get_list_iter(list) }?;
+
+        let mut interner = Interner::disabled();
+
+        Ok(iter::from_fn(move || {
+            iter.next()
+                .map(|elem| FromListElem::from_list_elem(elem, &mut interner))
+        }))
+    }
+
+    /// Gets a list of information, like [`PluginHandle::get_list`], but shares the allocation of repeated strings.
+    ///
+    /// Within a single read of the list, string fields with identical values (e.g. the server or network
+    /// name of every channel on the same network) point to the same allocation.
+    /// This reduces allocation when scanning large lists, at the cost of hashing each string field.
+    ///
+    /// Analogous to [`hexchat_list_get`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_list_get) and related functions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::list::Channels;
+    ///
+    /// fn count_channels_per_network<P>(ph: PluginHandle<'_, P>) {
+    ///     let channels = match ph.get_list_interned(Channels) {
+    ///         Ok(channels) => channels,
+    ///         Err(e) => return ph.print(format!("Failed to get channels: {}", e)),
+    ///     };
+    ///     let mut counts = HashMap::new();
+    ///     for channel in channels {
+    ///         *counts.entry(channel.network().to_owned()).or_insert(0) += 1;
+    ///     }
+    ///     for (network, count) in counts {
+    ///         ph.print(format!("{}: {} channels", network, count));
+    ///     }
+    /// }
+    /// ```
+    pub fn get_list_interned<L: List>(
+        self,
+        list: L,
+    ) -> Result<impl Iterator<Item = <L as List>::Elem> + 'ph, ListError> {
         // Safety: `ListElem`s are immediately consumed by `from_list_elem`, so they can't be invalidated
         let mut iter = unsafe { self.get_list_iter(list) }?;
+        let mut interner = Interner::new();
+
+        Ok(iter::from_fn(move || {
+            iter.next()
+                .map(|elem| FromListElem::from_list_elem(elem, &mut interner))
+        }))
+    }
+
+    /// Gets a list of information, like [`PluginHandle::get_list`], and collects it into a snapshot which can be queried repeatedly.
+    ///
+    /// The snapshot cannot outlive the current callback, since the list may change afterwards.
+    /// This is useful for passing the same list to several helper functions, without reading it from HexChat each time.
+    ///
+    /// Analogous to [`hexchat_list_get`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_list_get) and related functions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::list::{ListSnapshot, Users};
+    ///
+    /// fn count_away(users: &ListSnapshot<'_, Users>) -> usize {
+    ///     users.iter().filter(|user| user.is_away()).count()
+    /// }
+    ///
+    /// fn print_user_stats<P>(ph: PluginHandle<'_, P>) {
+    ///     let users = match ph.get_list_snapshot(Users) {
+    ///         Ok(users) => users,
+    ///         Err(e) => return ph.print(format!("Failed to get users: {}", e)),
+    ///     };
+    ///     ph.print(format!("{} users, {} away", users.len(), count_away(&users)));
+    /// }
+    /// ```
+    pub fn get_list_snapshot<L: List>(self, list: L) -> Result<ListSnapshot<'ph, L>, ListError> {
+        Ok(ListSnapshot::new(self.get_list(list)?.collect()))
+    }
+
+    /// Calls a function on each element of a list, stopping as soon as it returns [`ControlFlow::Break`].
+    ///
+    /// HexChat's list is freed before this function returns, so no further elements are read after `f` breaks.
+    ///
+    /// Returns the value passed to `Break`, or `Continue` if `f` never broke.
+    /// Returns [`ListError::Unknown`] if HexChat does not recognize the list.
+    ///
+    /// Behaves the same as [`PluginHandle::get_list`] followed by [`Iterator::try_for_each`].
+    ///
+    /// Analogous to [`hexchat_list_get`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_list_get) and related functions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::ops::ControlFlow;
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::list::Users;
+    ///
+    /// fn print_first_ops<P>(ph: PluginHandle<'_, P>, limit: usize) {
+    ///     let mut printed = 0;
+    ///     let result = ph.for_each_in_list(Users, |user| {
+    ///         if printed == limit {
+    ///             return ControlFlow::Break(());
+    ///         }
+    ///         if user.prefix() == Some('@') {
+    ///             ph.print(format!("Op: {}", user.nick()));
+    ///             printed += 1;
+    ///         }
+    ///         ControlFlow::Continue(())
+    ///     });
+    ///     if let Err(e) = result {
+    ///         ph.print(format!("Failed to get users: {}", e));
+    ///     }
+    /// }
+    /// ```
+    pub fn for_each_in_list<L: List, B>(
+        self,
+        list: L,
+        f: impl FnMut(<L as List>::Elem) -> ControlFlow<B>,
+    ) -> Result<ControlFlow<B>, ListError> {
+        let mut iter = self.get_list(list)?;
+        let result = iter.try_for_each(f);
+        // free the list now, even if it wasn't fully consumed
+        drop(iter);
+        Ok(result)
+    }
+
+    /// Gets a list of information, like [`PluginHandle::get_list`], and also reads additional fields from each element by name.
+    ///
+    /// `f` is called with each element of the list, and its result is returned alongside the element.
+    /// This allows reading fields which are not exposed by the list's element type, e.g. those added in newer versions of HexChat.
+    ///
+    /// Note that `f` is a function pointer, so it cannot capture any variables or interact with HexChat,
+    /// which could invalidate the list element.
+    ///
+    /// Analogous to [`hexchat_list_get`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_list_get) and related functions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::list::Users;
+    ///
+    /// fn print_user_flags<P>(ph: PluginHandle<'_, P>) {
+    ///     let users = match ph.get_list_with_fields(Users, |elem| elem.int("selected")) {
+    ///         Ok(users) => users,
+    ///         Err(e) => return ph.print(format!("Failed to get users: {}", e)),
+    ///     };
+    ///     for (user, selected) in users {
+    ///         ph.print(format!("{}: selected={}", user.nick(), selected));
+    ///     }
+    /// }
+    /// ```
+    pub fn get_list_with_fields<L: List, R: 'static>(
+        self,
+        list: L,
+        f: fn(&RawListElem<'_>) -> R,
+    ) -> Result<impl Iterator<Item = (<L as List>::Elem, R)> + 'ph, ListError> {
+        // Safety: `ListElem`s are immediately consumed by `f` and `from_list_elem`, so they can't be invalidated
+        let mut iter = unsafe { self.get_list_iter(list) }?;
+        let mut interner = Interner::disabled();
 
         Ok(iter::from_fn(move || {
-            iter.next().map(FromListElem::from_list_elem)
+            iter.next().map(|elem| {
+                let elem = RawListElem::new(elem);
+                let fields = f(&elem);
+                let elem = FromListElem::from_list_elem(elem.into_inner(), &mut interner);
+                (elem, fields)
+            })
         }))
     }
 
-    #[allow(dead_code)] // doesn't really make sense to export until we have GATs + LendingIterator in std
+    /// Finds the first channel, query, or server tab with the specified name.
+    ///
+    /// Names are compared using [RFC1459 casemapping](crate::rfc1459).
+    /// The [`Channels`] list is scanned only until a match is found.
+    ///
+    /// If the same channel is open on multiple servers, the first one in the list is returned;
+    /// use [`PluginHandle::find_contexts`] to find all of them.
+    ///
+    /// Returns [`ListError::Unknown`] if HexChat does not recognize the list.
+    ///
+    /// Analogous to [`hexchat_list_get`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_list_get) with `"channels"`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn print_user_count<P>(ph: PluginHandle<'_, P>, name: &str) {
+    ///     match ph.find_channel(name) {
+    ///         Ok(Some(channel)) => ph.print(format!("{} has {} users", name, channel.num_users())),
+    ///         Ok(None) => ph.print(format!("Not in {}", name)),
+    ///         Err(e) => ph.print(format!("Failed to get channels: {}", e)),
+    ///     }
+    /// }
+    /// ```
+    pub fn find_channel(self, name: &str) -> Result<Option<Channel>, ListError> {
+        let mut channels = self.get_list(Channels)?;
+        Ok(channels.find(|channel| rfc1459::eq(channel.name(), name)))
+    }
+
+    /// Finds the entry for the specified nick in the notify list.
+    ///
+    /// Nicks are compared using [`PluginHandle::nickcmp`], so they follow the current server's casemapping.
+    /// The [`Notifies`] list is scanned only until a match is found.
+    ///
+    /// Returns [`ListError::Unknown`] if HexChat does not recognize the list.
+    ///
+    /// Analogous to [`hexchat_list_get`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_list_get) with `"notify"`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::list::NotifyFlags;
+    ///
+    /// fn is_online<P>(ph: PluginHandle<'_, P>, nick: &str) -> bool {
+    ///     match ph.find_notify(nick) {
+    ///         Ok(Some(notify)) => notify.flags().contains(NotifyFlags::IS_ONLINE),
+    ///         _ => false,
+    ///     }
+    /// }
+    /// ```
+    pub fn find_notify(self, nick: &str) -> Result<Option<Notify>, ListError> {
+        let mut notifies = self.get_list(Notifies)?;
+        Ok(notifies.find(|notify| self.nickcmp(notify.nick(), nick) == Ordering::Equal))
+    }
+
+    #[allow(dead_code)]
     fn get_list_with<L: List, R>(
         self,
         list: L,
@@ -713,7 +2046,7 @@ impl<'ph, P> PluginHandle<'ph, P> {
         f: fn(
             Result<
                 &mut dyn LendingIterator<Item = dyn for<'a> CurriedItem<'a, Item = ListElem<'a>>>,
-                (),
+                ListError,
             >,
         ) -> R,
     ) -> R {
@@ -740,28 +2073,38 @@ impl<'ph, P> PluginHandle<'ph, P> {
         list: L,
     ) -> Result<
         impl LendingIterator<Item = dyn for<'a> CurriedItem<'a, Item = ListElem<'a>>> + 'ph,
-        (),
+        ListError,
     > {
         let _ = list;
 
         // Safety: NAME is a null-terminated C string
         let list_ptr = unsafe { self.raw.hexchat_list_get(L::NAME.as_ptr()) };
 
-        let list_ptr = match NonNull::new(list_ptr) {
-            Some(list_ptr) => list_ptr,
-            None => return Err(()),
-        };
+        let list_ptr = NonNull::new(list_ptr);
+
+        if list_ptr.is_none() {
+            // hexchat_list_get also returns null when the list is unavailable in the current context,
+            // so check whether HexChat knows about the list at all
+            // Safety: NAME is a null-terminated C string
+            let fields = unsafe { self.raw.hexchat_list_fields(L::NAME.as_ptr()) };
+            if fields.is_null() {
+                return Err(ListError::Unknown);
+            }
+        }
 
         struct ListElemIter<'ph> {
             raw: RawPluginHandle<'ph>,
-            list_ptr: NonNull<hexchat_list>,
+            /// `None` if the list is unavailable, in which case it is treated as empty.
+            list_ptr: Option<NonNull<hexchat_list>>,
         }
 
         impl<'ph> Drop for ListElemIter<'ph> {
             fn drop(&mut self) {
-                // Safety: list_ptr was returned from hexchat_list_get
-                // Safety: `ListElem`s don't outlive this struct, so there are no dangling pointers
-                unsafe { self.raw.hexchat_list_free(self.list_ptr.as_ptr()) };
+                if let Some(list_ptr) = self.list_ptr {
+                    // Safety: list_ptr was returned from hexchat_list_get
+                    // Safety: `ListElem`s don't outlive this struct, so there are no dangling pointers
+                    unsafe { self.raw.hexchat_list_free(list_ptr.as_ptr()) };
+                }
             }
         }
 
@@ -769,16 +2112,18 @@ impl<'ph, P> PluginHandle<'ph, P> {
             type Item = dyn for<'a> CurriedItem<'a, Item = ListElem<'a>>;
 
             fn next<'a>(&'a mut self) -> Option<ListElem<'a>> {
+                let list_ptr = self.list_ptr?;
+
                 // Safety: list is valid for the entire lifetime 'a
                 // Safety: hexchat_list_next can safely be called multiple times at the end of a list
-                if unsafe { self.raw.hexchat_list_next(self.list_ptr.as_ptr()) } == 0 {
+                if unsafe { self.raw.hexchat_list_next(list_ptr.as_ptr()) } == 0 {
                     return None;
                 }
 
                 // Safety: list is valid for the entire lifetime 'a, and hexchat_list_next returned true
                 // Safety: hexchat_list_next cannot be called while this ListElem exists, because this is a LendingIterator,
                 //         and the safety property of the parent get_list_elems ensures the lack of other invalidation.
-                let elem = unsafe { ListElem::<'a>::new(self.raw, self.list_ptr) };
+                let elem = unsafe { ListElem::<'a>::new(self.raw, list_ptr) };
 
                 Some(elem)
             }
@@ -800,8 +2145,11 @@ impl<'ph, P> PluginHandle<'ph, P> {
 ///
 /// The `callback` passed into each hook function is a function pointer (`fn(X) -> Y`)
 /// and not a type implementing a function trait (`impl Fn(X) -> Y`), unlike most higher-order functions in Rust.
-/// This means that no allocation is required to register a hook, so the plugin cannot leak memory on unload.
+/// Registering a hook still allocates a small entry in the plugin's hook registry, which tracks the callback and its panics,
+/// but that entry is freed when the hook is unregistered or the plugin is unloaded, so the plugin cannot leak memory on unload.
 /// However, it also means that you cannot capture local variables in hook callbacks.
+/// (If you do need to capture variables, [`PluginHandle::hook_command_boxed`] and [`PluginHandle::hook_timer_boxed`] accept a closure,
+/// which is freed when the hook is unregistered or the plugin is unloaded.)
 ///
 /// For example, the following does not compile, because `count` is captured by the closure.
 ///
@@ -895,14 +2243,26 @@ impl<'ph, P> PluginHandle<'ph, P> {
     ///
     /// The command is usable by typing `/command <words...>`.
     /// Command names starting with `.` are hidden in `/help`.
-    /// Hooking the special command `""` (empty string) captures non-commands, i.e. input without a `/` at the beginning.
+    /// Hooking the special command `""` (empty string) captures non-commands, i.e. input without a `/` at the beginning;
+    /// prefer [`PluginHandle::hook_input`] for this, which provides the input as a single string.
     ///
     /// Each element of `words` is an argument to the command.
     /// `words[0]`  is the name of the command, so `words[1]` is the first user-provided argument.
-    /// `words` is limited to 32 elements, and HexChat may provide excess elements, so the length of `words` is not meaningful.
+    /// `words` is padded with empty strings to at least 32 elements, so the length of `words` is not meaningful,
+    /// but missing arguments can be indexed without panicking.
+    /// If the user provides more arguments than that, all of them are included.
+    ///
+    /// To keep `help_text` in sync with the arguments the callback reads, describe the command with a
+    /// [`CommandBuilder`](crate::command::CommandBuilder), and use its [`usage`](crate::command::CommandBuilder::usage)
+    /// and [`parse_words`](crate::command::CommandBuilder::parse_words) methods.
+    ///
+    /// In debug builds, a warning is printed if this plugin hooks the same command more than once,
+    /// since HexChat calls every hook for the command, which is usually a mistake.
     ///
     /// Note that `callback` is a function pointer, so it cannot capture any variables.
     ///
+    /// Panics if HexChat does not return a hook handle; use [`PluginHandle::try_hook_command`] to handle this case instead.
+    ///
     /// Analogous to [`hexchat_hook_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_command).
     ///
     /// # Example
@@ -932,47 +2292,272 @@ impl<'ph, P> PluginHandle<'ph, P> {
         priority: Priority,
         callback: fn(plugin: &P, ph: PluginHandle<'_, P>, words: &[&HexStr]) -> Eat,
     ) -> HookHandle {
+        self.try_hook_command(name, help_text, priority, callback)
+            .unwrap_or_else(|_| panic!("Hook handle was null, should be infallible"))
+    }
+
+    /// Registers a command hook with HexChat, returning an error instead of panicking if registration fails.
+    ///
+    /// Behaves the same as [`PluginHandle::hook_command`], which panics if HexChat does not return a hook handle.
+    /// HexChat does not fail to register hooks in practice, but a broken host could.
+    ///
+    /// Analogous to [`hexchat_hook_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_command).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::hook::{Eat, Priority};
+    ///
+    /// fn add_greeting_command<P>(ph: PluginHandle<'_, P>) {
+    ///     let hooked = ph.try_hook_command(
+    ///         c"greet",
+    ///         c"Usage: GREET <name>, prints a greeting locally",
+    ///         Priority::Normal,
+    ///         |plugin, ph, words| {
+    ///             ph.print(format!("Hello {}!", words[1]));
+    ///             Eat::All
+    ///         }
+    ///     );
+    ///     if let Err(e) = hooked {
+    ///         eprintln!("Failed to hook GREET: {}", e);
+    ///     }
+    /// }
+    /// ```
+    pub fn try_hook_command(
+        self,
+        name: impl IntoCStr,
+        help_text: impl IntoCStr,
+        priority: Priority,
+        callback: fn(plugin: &P, ph: PluginHandle<'_, P>, words: &[&HexStr]) -> Eat,
+    ) -> Result<HookHandle, HookError> {
         extern "C" fn hook_command_callback<P: 'static>(
             word: *mut *mut c_char,
             _word_eol: *mut *mut c_char,
             user_data: *mut c_void,
         ) -> c_int {
             catch_and_log_unwind("hook_command_callback", || {
-                // Safety: this is exactly the type we pass into user_data below
+                // Safety: this is exactly the type we pass into user_data below, and the hook is still registered
                 let callback: fn(plugin: &P, ph: PluginHandle<'_, P>, words: &[&HexStr]) -> Eat =
-                    unsafe { mem::transmute(user_data) };
+                    *unsafe { hook_data(user_data) };
+
+                let arena = LossyArena::default();
 
                 // Safety: `word` is a valid word pointer for this entire callback
-                let word = unsafe { word_to_iter(&word) };
+                let word = unsafe { word_to_iter(&word, &arena) };
 
-                let mut words = [HexStr::EMPTY; 32];
+                with_padded_words(word, |words| {
+                    with_plugin_state(|plugin, ph| callback(plugin, ph, words))
+                })
+            })
+            .unwrap_or_else(|()| {
+                handle_hook_panic::<P>(user_data);
+                Eat::None
+            }) as c_int
+        }
 
-                for (ws, w) in words.iter_mut().zip(word) {
-                    *ws = w;
-                }
+        let name = name.into_cstr();
+        let help_text = help_text.into_cstr();
+
+        let user_data = register_hook_data(callback);
+
+        // Safety: `name` and `help_text` are null-terminated C strings
+        let hook = unsafe {
+            self.raw.hexchat_hook_command(
+                name.as_ptr(),
+                c_int::from(priority.as_raw()),
+                hook_command_callback::<P>,
+                help_text.as_ptr(),
+                user_data,
+            )
+        };
+
+        let hook = match NonNull::new(hook) {
+            Some(hook) => hook,
+            None => {
+                release_hook_data(user_data);
+                return Err(HookError::Null);
+            }
+        };
+
+        self.check_command_collision(&name, hook);
+
+        set_hook_data_owner(user_data, hook);
+
+        // Safety: hook was returned by HexChat; hook is not used after this
+        Ok(unsafe { HookHandle::new(hook) })
+    }
+
+    /// Registers a command hook with HexChat, using a closure which may capture variables.
+    ///
+    /// Behaves the same as [`PluginHandle::hook_command`], but `callback` is stored on the heap.
+    /// It is dropped when the hook is passed to [`PluginHandle::unhook`], or when the plugin is unloaded.
+    ///
+    /// Analogous to [`hexchat_hook_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_command).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::hook::{Eat, Priority};
+    ///
+    /// fn add_alias_commands<P>(ph: PluginHandle<'_, P>, aliases: &[(&str, &str)]) {
+    ///     for &(alias, target) in aliases {
+    ///         let target = target.to_owned();
+    ///         ph.hook_command_boxed(
+    ///             alias,
+    ///             format!("Usage: {} <arg>, alias for {}", alias, target),
+    ///             Priority::Normal,
+    ///             move |plugin, ph, words| {
+    ///                 ph.command(format!("{} {}", target, words[1]));
+    ///                 Eat::All
+    ///             },
+    ///         );
+    ///     }
+    /// }
+    /// ```
+    pub fn hook_command_boxed<F>(
+        self,
+        name: impl IntoCStr,
+        help_text: impl IntoCStr,
+        priority: Priority,
+        callback: F,
+    ) -> HookHandle
+    where
+        F: Fn(&P, PluginHandle<'_, P>, &[&HexStr]) -> Eat + 'static,
+    {
+        extern "C" fn hook_command_boxed_callback<P: 'static, F>(
+            word: *mut *mut c_char,
+            _word_eol: *mut *mut c_char,
+            user_data: *mut c_void,
+        ) -> c_int
+        where
+            F: Fn(&P, PluginHandle<'_, P>, &[&HexStr]) -> Eat + 'static,
+        {
+            catch_and_log_unwind("hook_command_boxed_callback", || {
+                // Safety: this is exactly the type we pass into user_data below, and the hook is still registered
+                let callback = unsafe { hook_data::<F>(user_data) };
+
+                let arena = LossyArena::default();
+
+                // Safety: `word` is a valid word pointer for this entire callback
+                let word = unsafe { word_to_iter(&word, &arena) };
 
-                with_plugin_state(|plugin, ph| callback(plugin, ph, &words))
+                with_padded_words(word, |words| {
+                    with_plugin_state(|plugin, ph| callback(plugin, ph, words))
+                })
             })
-            .unwrap_or(Eat::None) as c_int
+            .unwrap_or_else(|()| {
+                handle_hook_panic::<P>(user_data);
+                Eat::None
+            }) as c_int
         }
 
         let name = name.into_cstr();
         let help_text = help_text.into_cstr();
 
+        let user_data = register_hook_data(callback);
+
         // Safety: `name` and `help_text` are null-terminated C strings
         let hook = unsafe {
             self.raw.hexchat_hook_command(
                 name.as_ptr(),
-                priority as c_int,
-                hook_command_callback::<P>,
+                c_int::from(priority.as_raw()),
+                hook_command_boxed_callback::<P, F>,
                 help_text.as_ptr(),
-                callback as *mut c_void,
+                user_data,
+            )
+        };
+
+        let hook = NonNull::new(hook)
+            .unwrap_or_else(|| panic!("Hook handle was null, should be infallible"));
+
+        self.check_command_collision(&name, hook);
+
+        set_hook_data_owner(user_data, hook);
+
+        // Safety: hook was returned by HexChat; hook is not used after this
+        unsafe { HookHandle::new(hook) }
+    }
+
+    /// Registers a hook for user input which is not a command, i.e. text typed without a `/` at the beginning.
+    ///
+    /// `callback` receives the entire input line as a single string, exactly as typed.
+    /// Return [`Eat::All`] to prevent the text from being sent.
+    ///
+    /// This hooks the special command `""` (empty string), like [`PluginHandle::hook_command_boxed`] would,
+    /// but avoids having to reassemble the line from individual words.
+    ///
+    /// Analogous to [`hexchat_hook_command`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_command)
+    /// with an empty command name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::hook::{Eat, Priority};
+    ///
+    /// fn block_passwords<P>(ph: PluginHandle<'_, P>) {
+    ///     ph.hook_input(Priority::High, |plugin, ph, line| {
+    ///         if line.contains("hunter2") {
+    ///             ph.print("Not sending that.");
+    ///             Eat::All
+    ///         } else {
+    ///             Eat::None
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    pub fn hook_input<F>(self, priority: Priority, callback: F) -> HookHandle
+    where
+        F: Fn(&P, PluginHandle<'_, P>, &str) -> Eat + 'static,
+    {
+        extern "C" fn hook_input_callback<P: 'static, F>(
+            _word: *mut *mut c_char,
+            word_eol: *mut *mut c_char,
+            user_data: *mut c_void,
+        ) -> c_int
+        where
+            F: Fn(&P, PluginHandle<'_, P>, &str) -> Eat + 'static,
+        {
+            catch_and_log_unwind("hook_input_callback", || {
+                // Safety: this is exactly the type we pass into user_data below, and the hook is still registered
+                let callback = unsafe { hook_data::<F>(user_data) };
+
+                let arena = LossyArena::default();
+
+                // Safety: `word_eol` is a valid word pointer for this entire callback
+                let mut word_eol = unsafe { word_to_iter(&word_eol, &arena) };
+
+                // the first element of `word_eol` is the entire line
+                let line = word_eol.next().unwrap_or(HexStr::EMPTY);
+
+                with_plugin_state(|plugin, ph| callback(plugin, ph, line))
+            })
+            .unwrap_or_else(|()| {
+                handle_hook_panic::<P>(user_data);
+                Eat::None
+            }) as c_int
+        }
+
+        let user_data = register_hook_data(callback);
+
+        // Safety: the name and help text are null-terminated C strings
+        let hook = unsafe {
+            self.raw.hexchat_hook_command(
+                c"".as_ptr(),
+                c_int::from(priority.as_raw()),
+                hook_input_callback::<P, F>,
+                ptr::null(),
+                user_data,
             )
         };
 
         let hook = NonNull::new(hook)
             .unwrap_or_else(|| panic!("Hook handle was null, should be infallible"));
 
+        set_hook_data_owner(user_data, hook);
+
         // Safety: hook was returned by HexChat; hook is not used after this
         unsafe { HookHandle::new(hook) }
     }
@@ -983,6 +2568,8 @@ impl<'ph, P> PluginHandle<'ph, P> {
     ///
     /// Note that `callback` is a function pointer, so it cannot capture any variables.
     ///
+    /// Panics if HexChat does not return a hook handle; use [`PluginHandle::try_hook_print`] to handle this case instead.
+    ///
     /// Analogous to [`hexchat_hook_print`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_print).
     ///
     /// # Examples
@@ -1002,49 +2589,262 @@ impl<'ph, P> PluginHandle<'ph, P> {
     ///     });
     /// }
     /// ```
-    pub fn hook_print<E: PrintEvent<N>, const N: usize>(
+    pub fn hook_print<E: HookablePrintEvent<N>, const N: usize>(
         self,
         event: E,
         priority: Priority,
         callback: fn(plugin: &P, ph: PluginHandle<'_, P>, args: [&HexStr; N]) -> Eat,
     ) -> HookHandle {
-        extern "C" fn hook_print_callback<P: 'static, E: PrintEvent<N>, const N: usize>(
+        self.try_hook_print(event, priority, callback)
+            .unwrap_or_else(|_| panic!("Hook handle was null, should be infallible"))
+    }
+
+    /// Registers a print event hook with HexChat, returning an error instead of panicking if registration fails.
+    ///
+    /// Behaves the same as [`PluginHandle::hook_print`], which panics if HexChat does not return a hook handle.
+    ///
+    /// Analogous to [`hexchat_hook_print`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_print).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::event::print::YouPartWithReason;
+    /// use hexavalent::hook::{Eat, HookError, HookHandle, Priority};
+    ///
+    /// fn hook_you_part<P>(ph: PluginHandle<'_, P>) -> Result<HookHandle, HookError> {
+    ///     ph.try_hook_print(YouPartWithReason, Priority::Normal, |plugin, ph, args| {
+    ///         let [your_nick, your_host, channel, reason] = args;
+    ///         ph.print(format!("You left channel {}: {}.", channel, reason));
+    ///         Eat::HexChat
+    ///     })
+    /// }
+    /// ```
+    pub fn try_hook_print<E: HookablePrintEvent<N>, const N: usize>(
+        self,
+        event: E,
+        priority: Priority,
+        callback: fn(plugin: &P, ph: PluginHandle<'_, P>, args: [&HexStr; N]) -> Eat,
+    ) -> Result<HookHandle, HookError> {
+        extern "C" fn hook_print_callback<P: 'static, E: HookablePrintEvent<N>, const N: usize>(
             word: *mut *mut c_char,
             user_data: *mut c_void,
         ) -> c_int {
             catch_and_log_unwind("hook_print_callback", || {
-                // Safety: this is exactly the type we pass into user_data below
+                // Safety: this is exactly the type we pass into user_data below, and the hook is still registered
                 let callback: fn(plugin: &P, ph: PluginHandle<'_, P>, args: [&HexStr; N]) -> Eat =
-                    unsafe { mem::transmute(user_data) };
+                    *unsafe { hook_data(user_data) };
+
+                let arena = LossyArena::default();
 
                 // Safety: `word` is a valid word pointer for this entire callback
-                let word = unsafe { word_to_iter(&word) };
+                let word = unsafe { word_to_iter(&word, &arena) };
                 let args = E::args_from_words(word, iter::empty());
 
                 with_plugin_state(|plugin, ph| callback(plugin, ph, args))
             })
-            .unwrap_or(Eat::None) as c_int
+            .unwrap_or_else(|()| {
+                handle_hook_panic::<P>(user_data);
+                Eat::None
+            }) as c_int
         }
 
         let _ = event;
 
+        let user_data = register_hook_data(callback);
+
         // Safety: NAME is a null-terminated C string
         let hook = unsafe {
             self.raw.hexchat_hook_print(
                 E::NAME.as_ptr(),
-                priority as c_int,
+                c_int::from(priority.as_raw()),
                 hook_print_callback::<P, E, N>,
-                callback as *mut c_void,
+                user_data,
+            )
+        };
+
+        let hook = match NonNull::new(hook) {
+            Some(hook) => hook,
+            None => {
+                release_hook_data(user_data);
+                return Err(HookError::Null);
+            }
+        };
+
+        set_hook_data_owner(user_data, hook);
+
+        // Safety: hook was returned by HexChat; hook is not used after this
+        Ok(unsafe { HookHandle::new(hook) })
+    }
+
+    /// Registers a print event hook with HexChat, using a closure which may capture variables.
+    ///
+    /// Behaves the same as [`PluginHandle::hook_print`], but `callback` is stored on the heap.
+    /// It is dropped when the hook is passed to [`PluginHandle::unhook`], or when the plugin is unloaded.
+    pub(crate) fn hook_print_boxed<E: HookablePrintEvent<N>, F, const N: usize>(
+        self,
+        event: E,
+        priority: Priority,
+        callback: F,
+    ) -> HookHandle
+    where
+        F: Fn(&P, PluginHandle<'_, P>, [&HexStr; N]) -> Eat + 'static,
+    {
+        extern "C" fn hook_print_boxed_callback<
+            P: 'static,
+            E: HookablePrintEvent<N>,
+            F,
+            const N: usize,
+        >(
+            word: *mut *mut c_char,
+            user_data: *mut c_void,
+        ) -> c_int
+        where
+            F: Fn(&P, PluginHandle<'_, P>, [&HexStr; N]) -> Eat + 'static,
+        {
+            catch_and_log_unwind("hook_print_boxed_callback", || {
+                // Safety: this is exactly the type we pass into user_data below, and the hook is still registered
+                let callback = unsafe { hook_data::<F>(user_data) };
+
+                let arena = LossyArena::default();
+
+                // Safety: `word` is a valid word pointer for this entire callback
+                let word = unsafe { word_to_iter(&word, &arena) };
+                let args = E::args_from_words(word, iter::empty());
+
+                with_plugin_state(|plugin, ph| callback(plugin, ph, args))
+            })
+            .unwrap_or_else(|()| {
+                handle_hook_panic::<P>(user_data);
+                Eat::None
+            }) as c_int
+        }
+
+        let _ = event;
+
+        let user_data = register_hook_data(callback);
+
+        // Safety: NAME is a null-terminated C string
+        let hook = unsafe {
+            self.raw.hexchat_hook_print(
+                E::NAME.as_ptr(),
+                c_int::from(priority.as_raw()),
+                hook_print_boxed_callback::<P, E, F, N>,
+                user_data,
             )
         };
 
         let hook = NonNull::new(hook)
             .unwrap_or_else(|| panic!("Hook handle was null, should be infallible"));
 
+        set_hook_data_owner(user_data, hook);
+
         // Safety: hook was returned by HexChat; hook is not used after this
         unsafe { HookHandle::new(hook) }
     }
 
+    /// Registers hooks which collect the lines of each `/WHOIS` reply into a single [`WhoisResult`].
+    ///
+    /// `callback` is called once per reply, when the [`WhoisEnd`](crate::event::print::WhoisEnd) event is received,
+    /// with every line received for that nick on the same server since the previous reply. The lines are still printed as usual.
+    ///
+    /// Nicks are compared using [RFC1459 casemapping](crate::rfc1459).
+    /// Incomplete replies are discarded when their server disconnects, or after a minute without a `WhoisEnd`.
+    ///
+    /// Returns the handles of all of the registered hooks; pass each of them to [`PluginHandle::unhook`] to stop collecting replies.
+    ///
+    /// Analogous to calling [`hexchat_hook_print`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_print)
+    /// for each of the `WhoIs` print events.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn log_whois_replies<P>(ph: PluginHandle<'_, P>) {
+    ///     ph.hook_whois(|plugin, ph, whois| {
+    ///         let real_name = whois.real_name().map_or("<unknown>", |name| name.as_str());
+    ///         ph.print(format!("{} is {}, in {} channels", whois.nick(), real_name, whois.channels().len()));
+    ///     });
+    /// }
+    /// ```
+    pub fn hook_whois<F>(self, callback: F) -> Vec<HookHandle>
+    where
+        F: Fn(&P, PluginHandle<'_, P>, &WhoisResult) + 'static,
+    {
+        const TIMEOUT: Duration = Duration::from_secs(60);
+
+        type Pending = HashMap<(Option<i32>, IrcName), (Instant, WhoisResult)>;
+        let pending: Rc<RefCell<Pending>> = Default::default();
+
+        macro_rules! collect {
+            ($event:expr, |$whois:ident, $args:pat_param| $update:expr) => {{
+                let pending = Rc::clone(&pending);
+                self.hook_print_boxed($event, Priority::Normal, move |_, ph, args| {
+                    let $args = args;
+                    let mut pending = pending.borrow_mut();
+                    let key = (ph.current_server_id(), IrcName::new(args[0].to_owned()));
+                    if !pending.contains_key(&key) {
+                        pending.retain(|_, (started, _)| started.elapsed() < TIMEOUT);
+                    }
+                    let (_, $whois) = pending
+                        .entry(key)
+                        .or_insert_with(|| (Instant::now(), WhoisResult::new(args[0])));
+                    $update;
+                    Eat::None
+                })
+            }};
+        }
+
+        let mut hooks = vec![
+            collect!(WhoisNameLine, |whois, [_, user, host, real_name]| {
+                whois.set_name_line(user, host, real_name)
+            }),
+            collect!(WhoisServerLine, |whois, [_, info]| whois.set_server(info)),
+            collect!(WhoisChannelOperLine, |whois, [_, membership]| {
+                whois.add_channel_line(membership)
+            }),
+            collect!(WhoisIdleLine, |whois, [_, idle]| whois.set_idle(idle, None)),
+            collect!(WhoisIdleLineWithSignon, |whois, [_, idle, signon]| {
+                whois.set_idle(idle, Some(signon))
+            }),
+            collect!(WhoisAwayLine, |whois, [_, reason]| whois.set_away(reason)),
+            collect!(WhoisAuthenticated, |whois, [_, _, account]| {
+                whois.set_account(account)
+            }),
+            collect!(WhoisRealHost, |whois, [_, real_host, real_ip, _]| {
+                whois.set_real_host(real_host, real_ip)
+            }),
+            collect!(WhoisIdentified, |whois, [_, message, _]| {
+                whois.add_other_line(message)
+            }),
+            collect!(WhoisSpecial, |whois, [_, message, _]| {
+                whois.add_other_line(message)
+            }),
+        ];
+
+        hooks.push(self.hook_print_boxed(Disconnected, Priority::Normal, {
+            let pending = Rc::clone(&pending);
+            move |_, ph, _| {
+                let server_id = ph.current_server_id();
+                pending.borrow_mut().retain(|(id, _), _| *id != server_id);
+                Eat::None
+            }
+        }));
+
+        hooks.push(
+            self.hook_print_boxed(WhoisEnd, Priority::Normal, move |plugin, ph, [nick]| {
+                let key = (ph.current_server_id(), IrcName::new(nick.to_owned()));
+                let whois = pending.borrow_mut().remove(&key).map(|(_, whois)| whois);
+                let whois = whois.unwrap_or_else(|| WhoisResult::new(nick));
+                callback(plugin, ph, &whois);
+                Eat::None
+            }),
+        );
+
+        hooks
+    }
+
     /// Registers a print event hook with HexChat, capturing the event's attributes.
     ///
     /// See the [`event::print`](crate::event::print) submodule for a list of print events.
@@ -1070,7 +2870,7 @@ impl<'ph, P> PluginHandle<'ph, P> {
     ///     });
     /// }
     /// ```
-    pub fn hook_print_attrs<E: PrintEvent<N>, const N: usize>(
+    pub fn hook_print_attrs<E: HookablePrintEvent<N>, const N: usize>(
         self,
         event: E,
         priority: Priority,
@@ -1081,134 +2881,401 @@ impl<'ph, P> PluginHandle<'ph, P> {
             args: [&HexStr; N],
         ) -> Eat,
     ) -> HookHandle {
-        extern "C" fn hook_print_attrs_callback<P: 'static, E: PrintEvent<N>, const N: usize>(
+        extern "C" fn hook_print_attrs_callback<
+            P: 'static,
+            E: HookablePrintEvent<N>,
+            const N: usize,
+        >(
+            word: *mut *mut c_char,
+            attrs: *mut hexchat_event_attrs,
+            user_data: *mut c_void,
+        ) -> c_int {
+            catch_and_log_unwind("hook_print_attrs_callback", || {
+                // Safety: this is exactly the type we pass into user_data below, and the hook is still registered
+                let callback: fn(
+                    plugin: &P,
+                    ph: PluginHandle<'_, P>,
+                    attrs: EventAttrs<'_>,
+                    args: [&HexStr; N],
+                ) -> Eat = *unsafe { hook_data(user_data) };
+
+                // Safety: attrs is a valid hexchat_event_attrs pointer
+                let timestamp = unsafe { (*attrs).server_time_utc };
+                let timestamp = timestamp_to_datetime(timestamp, "hexchat_event_attrs");
+
+                let arena = LossyArena::default();
+
+                // Safety: attrs is a valid hexchat_event_attrs pointer; ircv3_line is a valid string; temporary does not outlive this function
+                #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
+                let ircv3_line = unsafe { CStr::from_ptr((*attrs).ircv3_line) };
+                #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
+                let ircv3_line = arena.hexstr(ircv3_line, "hexchat_event_attrs").as_str();
+
+                let attrs = EventAttrs::new(
+                    timestamp,
+                    #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
+                    ircv3_line,
+                );
+
+                // Safety: `word` is a valid word pointer for this entire callback
+                let word = unsafe { word_to_iter(&word, &arena) };
+                let args = E::args_from_words(word, iter::empty());
+
+                with_plugin_state(|plugin, ph| callback(plugin, ph, attrs, args))
+            })
+            .unwrap_or_else(|()| {
+                handle_hook_panic::<P>(user_data);
+                Eat::None
+            }) as c_int
+        }
+
+        let _ = event;
+
+        let user_data = register_hook_data(callback);
+
+        // Safety: NAME is a null-terminated C string
+        let hook = unsafe {
+            self.raw.hexchat_hook_print_attrs(
+                E::NAME.as_ptr(),
+                c_int::from(priority.as_raw()),
+                hook_print_attrs_callback::<P, E, N>,
+                user_data,
+            )
+        };
+
+        let hook = NonNull::new(hook)
+            .unwrap_or_else(|| panic!("Hook handle was null, should be infallible"));
+
+        set_hook_data_owner(user_data, hook);
+
+        // Safety: hook was returned by HexChat; hook is not used after this
+        unsafe { HookHandle::new(hook) }
+    }
+
+    /// Registers a server event hook with HexChat.
+    ///
+    /// See the [`event::server`](crate::event::server) submodule for a list of server events.
+    ///
+    /// Note that `callback` is a function pointer, so it cannot capture any variables.
+    ///
+    /// `callback` runs in the server context where the event was received, which can be obtained with [`PluginHandle::get_context`].
+    ///
+    /// Panics if HexChat does not return a hook handle; use [`PluginHandle::try_hook_server`] to handle this case instead.
+    ///
+    /// Analogous to [`hexchat_hook_server`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_server).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::event::server::Part;
+    /// use hexavalent::hook::{Eat, Priority};
+    ///
+    /// struct MyPlugin;
+    ///
+    /// fn hook_part(ph: PluginHandle<'_, MyPlugin>) {
+    ///     ph.hook_server(Part, Priority::Normal, |plugin, ph, args| {
+    ///         let [sender, _, channel, reason] = args;
+    ///         ph.print(format!("{} left channel {}: {}.", sender, channel, reason));
+    ///         Eat::None
+    ///     });
+    /// }
+    /// ```
+    pub fn hook_server<E: ServerEvent<N>, const N: usize>(
+        self,
+        event: E,
+        priority: Priority,
+        callback: fn(plugin: &P, ph: PluginHandle<'_, P>, args: [&HexStr; N]) -> Eat,
+    ) -> HookHandle {
+        self.try_hook_server(event, priority, callback)
+            .unwrap_or_else(|_| panic!("Hook handle was null, should be infallible"))
+    }
+
+    /// Registers a server event hook with HexChat, returning an error instead of panicking if registration fails.
+    ///
+    /// Behaves the same as [`PluginHandle::hook_server`], which panics if HexChat does not return a hook handle.
+    ///
+    /// Analogous to [`hexchat_hook_server`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_server).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::event::server::Part;
+    /// use hexavalent::hook::{Eat, HookError, HookHandle, Priority};
+    ///
+    /// fn hook_part<P>(ph: PluginHandle<'_, P>) -> Result<HookHandle, HookError> {
+    ///     ph.try_hook_server(Part, Priority::Normal, |plugin, ph, args| {
+    ///         let [sender, _, channel, reason] = args;
+    ///         ph.print(format!("{} left channel {}: {}.", sender, channel, reason));
+    ///         Eat::None
+    ///     })
+    /// }
+    /// ```
+    pub fn try_hook_server<E: ServerEvent<N>, const N: usize>(
+        self,
+        event: E,
+        priority: Priority,
+        callback: fn(plugin: &P, ph: PluginHandle<'_, P>, args: [&HexStr; N]) -> Eat,
+    ) -> Result<HookHandle, HookError> {
+        extern "C" fn hook_server_callback<P: 'static, E: ServerEvent<N>, const N: usize>(
+            word: *mut *mut c_char,
+            word_eol: *mut *mut c_char,
+            user_data: *mut c_void,
+        ) -> c_int {
+            catch_and_log_unwind("hook_server_callback", || {
+                // Safety: this is exactly the type we pass into user_data below, and the hook is still registered
+                let callback: fn(plugin: &P, ph: PluginHandle<'_, P>, args: [&HexStr; N]) -> Eat =
+                    *unsafe { hook_data(user_data) };
+
+                let arena = LossyArena::default();
+
+                // Safety: `word` is a valid word pointer for this entire callback
+                let word = unsafe { word_to_iter(&word, &arena) };
+
+                // Safety: `word_eol` is a valid word pointer for this entire callback
+                let word_eol = unsafe { word_to_iter(&word_eol, &arena) };
+                let args = E::args_from_words(word, word_eol);
+
+                with_plugin_state(|plugin, ph| callback(plugin, ph, args))
+            })
+            .unwrap_or_else(|()| {
+                handle_hook_panic::<P>(user_data);
+                Eat::None
+            }) as c_int
+        }
+
+        let _ = event;
+
+        let user_data = register_hook_data(callback);
+
+        // Safety: NAME is a null-terminated C string
+        let hook = unsafe {
+            self.raw.hexchat_hook_server(
+                E::NAME.as_ptr(),
+                c_int::from(priority.as_raw()),
+                hook_server_callback::<P, E, N>,
+                user_data,
+            )
+        };
+
+        let hook = match NonNull::new(hook) {
+            Some(hook) => hook,
+            None => {
+                release_hook_data(user_data);
+                return Err(HookError::Null);
+            }
+        };
+
+        set_hook_data_owner(user_data, hook);
+
+        // Safety: hook was returned by HexChat; hook is not used after this
+        Ok(unsafe { HookHandle::new(hook) })
+    }
+
+    /// Registers a server event hook with HexChat, which only runs `callback` for events received on the named network.
+    ///
+    /// Network names are compared case-insensitively against [`Network`](crate::info::Network) in the server context where the event was received.
+    /// Events from other networks are passed through, as if `callback` returned [`Eat::None`].
+    ///
+    /// Behaves the same as [`PluginHandle::hook_server`] otherwise.
+    ///
+    /// Analogous to [`hexchat_hook_server`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_server).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::event::server::Privmsg;
+    /// use hexavalent::hook::{Eat, Priority};
+    ///
+    /// struct MyPlugin;
+    ///
+    /// fn hook_libera_messages(ph: PluginHandle<'_, MyPlugin>) {
+    ///     ph.hook_server_on("Libera.Chat", Privmsg, Priority::Normal, |plugin, ph, args| {
+    ///         let [sender, _, target, text] = args;
+    ///         if text.starts_with("!ping") {
+    ///             ph.command(format!("MSG {} pong", target));
+    ///         }
+    ///         Eat::None
+    ///     });
+    /// }
+    /// ```
+    pub fn hook_server_on<E: ServerEvent<N>, const N: usize>(
+        self,
+        network: &str,
+        event: E,
+        priority: Priority,
+        callback: fn(plugin: &P, ph: PluginHandle<'_, P>, args: [&HexStr; N]) -> Eat,
+    ) -> HookHandle {
+        let network = network.to_owned();
+        self.hook_server_boxed(event, priority, move |plugin, ph, args| {
+            let on_network = ph
+                .get_info(Network)
+                .is_some_and(|n| n.eq_ignore_ascii_case(&network));
+            if on_network {
+                callback(plugin, ph, args)
+            } else {
+                Eat::None
+            }
+        })
+    }
+
+    /// Registers a server event hook with HexChat, using a closure which may capture variables.
+    ///
+    /// Behaves the same as [`PluginHandle::hook_server`], but `callback` is stored on the heap.
+    /// It is dropped when the hook is passed to [`PluginHandle::unhook`], or when the plugin is unloaded.
+    pub(crate) fn hook_server_boxed<E: ServerEvent<N>, F, const N: usize>(
+        self,
+        event: E,
+        priority: Priority,
+        callback: F,
+    ) -> HookHandle
+    where
+        F: Fn(&P, PluginHandle<'_, P>, [&HexStr; N]) -> Eat + 'static,
+    {
+        extern "C" fn hook_server_boxed_callback<P: 'static, E: ServerEvent<N>, F, const N: usize>(
             word: *mut *mut c_char,
-            attrs: *mut hexchat_event_attrs,
+            word_eol: *mut *mut c_char,
             user_data: *mut c_void,
-        ) -> c_int {
-            catch_and_log_unwind("hook_print_attrs_callback", || {
-                // Safety: this is exactly the type we pass into user_data below
-                let callback: fn(
-                    plugin: &P,
-                    ph: PluginHandle<'_, P>,
-                    attrs: EventAttrs<'_>,
-                    args: [&HexStr; N],
-                ) -> Eat = unsafe { mem::transmute(user_data) };
-
-                // Safety: attrs is a valid hexchat_event_attrs pointer
-                let timestamp = unsafe { (*attrs).server_time_utc };
-                let timestamp =
-                    OffsetDateTime::from_unix_timestamp(timestamp).unwrap_or_else(|e| {
-                        panic!("Invalid timestamp from `hexchat_event_attrs`: {}", e)
-                    });
-
-                // Safety: attrs is a valid hexchat_event_attrs pointer; ircv3_line is a valid string; temporary does not outlive this function
-                #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
-                let ircv3_line = unsafe { CStr::from_ptr((*attrs).ircv3_line) }
-                    .to_str()
-                    .unwrap_or_else(|e| panic!("Invalid UTF8 from `hexchat_event_attrs`: {}", e));
+        ) -> c_int
+        where
+            F: Fn(&P, PluginHandle<'_, P>, [&HexStr; N]) -> Eat + 'static,
+        {
+            catch_and_log_unwind("hook_server_boxed_callback", || {
+                // Safety: this is exactly the type we pass into user_data below, and the hook is still registered
+                let callback = unsafe { hook_data::<F>(user_data) };
 
-                let attrs = EventAttrs::new(
-                    timestamp,
-                    #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
-                    ircv3_line,
-                );
+                let arena = LossyArena::default();
 
                 // Safety: `word` is a valid word pointer for this entire callback
-                let word = unsafe { word_to_iter(&word) };
-                let args = E::args_from_words(word, iter::empty());
+                let word = unsafe { word_to_iter(&word, &arena) };
 
-                with_plugin_state(|plugin, ph| callback(plugin, ph, attrs, args))
+                // Safety: `word_eol` is a valid word pointer for this entire callback
+                let word_eol = unsafe { word_to_iter(&word_eol, &arena) };
+                let args = E::args_from_words(word, word_eol);
+
+                with_plugin_state(|plugin, ph| callback(plugin, ph, args))
             })
-            .unwrap_or(Eat::None) as c_int
+            .unwrap_or_else(|()| {
+                handle_hook_panic::<P>(user_data);
+                Eat::None
+            }) as c_int
         }
 
         let _ = event;
 
+        let user_data = register_hook_data(callback);
+
         // Safety: NAME is a null-terminated C string
         let hook = unsafe {
-            self.raw.hexchat_hook_print_attrs(
+            self.raw.hexchat_hook_server(
                 E::NAME.as_ptr(),
-                priority as c_int,
-                hook_print_attrs_callback::<P, E, N>,
-                callback as *mut c_void,
+                c_int::from(priority.as_raw()),
+                hook_server_boxed_callback::<P, E, F, N>,
+                user_data,
             )
         };
 
         let hook = NonNull::new(hook)
             .unwrap_or_else(|| panic!("Hook handle was null, should be infallible"));
 
+        set_hook_data_owner(user_data, hook);
+
         // Safety: hook was returned by HexChat; hook is not used after this
         unsafe { HookHandle::new(hook) }
     }
 
-    /// Registers a server event hook with HexChat.
+    /// Registers a hook for every line received from the server, regardless of its command.
     ///
-    /// See the [`event::server`](crate::event::server) submodule for a list of server events.
+    /// Each element of `word` is a word of the line, and each element of `word_eol` is the rest of the line from that word onwards.
+    /// `word[0]` is the sender (with its leading `:`) and `word[1]` is the command.
+    /// Like [`PluginHandle::hook_command`], both are padded with empty strings to at least 32 elements.
+    ///
+    /// This hooks the special [`RawLine`](crate::event::server::special::RawLine) event, but provides every word of the line,
+    /// rather than only the sender, command, and arguments.
     ///
     /// Note that `callback` is a function pointer, so it cannot capture any variables.
     ///
-    /// Analogous to [`hexchat_hook_server`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_server).
+    /// Analogous to [`hexchat_hook_server`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_server)
+    /// with `"RAW LINE"`.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use hexavalent::PluginHandle;
-    /// use hexavalent::event::server::Part;
     /// use hexavalent::hook::{Eat, Priority};
     ///
     /// struct MyPlugin;
     ///
-    /// fn hook_part(ph: PluginHandle<'_, MyPlugin>) {
-    ///     ph.hook_server(Part, Priority::Normal, |plugin, ph, args| {
-    ///         let [sender, _, channel, reason] = args;
-    ///         ph.print(format!("{} left channel {}: {}.", sender, channel, reason));
+    /// fn log_all_lines(ph: PluginHandle<'_, MyPlugin>) {
+    ///     ph.hook_all_server(Priority::Normal, |plugin, ph, word, word_eol| {
+    ///         eprintln!("[{}] {}", word[1], word_eol[0]);
     ///         Eat::None
     ///     });
     /// }
     /// ```
-    pub fn hook_server<E: ServerEvent<N>, const N: usize>(
+    pub fn hook_all_server(
         self,
-        event: E,
         priority: Priority,
-        callback: fn(plugin: &P, ph: PluginHandle<'_, P>, args: [&HexStr; N]) -> Eat,
+        callback: fn(
+            plugin: &P,
+            ph: PluginHandle<'_, P>,
+            word: &[&HexStr],
+            word_eol: &[&HexStr],
+        ) -> Eat,
     ) -> HookHandle {
-        extern "C" fn hook_server_callback<P: 'static, E: ServerEvent<N>, const N: usize>(
+        extern "C" fn hook_all_server_callback<P: 'static>(
             word: *mut *mut c_char,
             word_eol: *mut *mut c_char,
             user_data: *mut c_void,
         ) -> c_int {
-            catch_and_log_unwind("hook_server_callback", || {
-                // Safety: this is exactly the type we pass into user_data below
-                let callback: fn(plugin: &P, ph: PluginHandle<'_, P>, args: [&HexStr; N]) -> Eat =
-                    unsafe { mem::transmute(user_data) };
+            catch_and_log_unwind("hook_all_server_callback", || {
+                // Safety: this is exactly the type we pass into user_data below, and the hook is still registered
+                let callback: fn(
+                    plugin: &P,
+                    ph: PluginHandle<'_, P>,
+                    word: &[&HexStr],
+                    word_eol: &[&HexStr],
+                ) -> Eat = *unsafe { hook_data(user_data) };
+
+                let arena = LossyArena::default();
 
                 // Safety: `word` is a valid word pointer for this entire callback
-                let word = unsafe { word_to_iter(&word) };
+                let word = unsafe { word_to_iter(&word, &arena) };
+
                 // Safety: `word_eol` is a valid word pointer for this entire callback
-                let word_eol = unsafe { word_to_iter(&word_eol) };
-                let args = E::args_from_words(word, word_eol);
+                let word_eol = unsafe { word_to_iter(&word_eol, &arena) };
 
-                with_plugin_state(|plugin, ph| callback(plugin, ph, args))
+                with_padded_words(word, |word| {
+                    with_padded_words(word_eol, |word_eol| {
+                        with_plugin_state(|plugin, ph| callback(plugin, ph, word, word_eol))
+                    })
+                })
             })
-            .unwrap_or(Eat::None) as c_int
+            .unwrap_or_else(|()| {
+                handle_hook_panic::<P>(user_data);
+                Eat::None
+            }) as c_int
         }
 
-        let _ = event;
+        let user_data = register_hook_data(callback);
 
-        // Safety: NAME is a null-terminated C string
+        // Safety: the name is a null-terminated C string
         let hook = unsafe {
             self.raw.hexchat_hook_server(
-                E::NAME.as_ptr(),
-                priority as c_int,
-                hook_server_callback::<P, E, N>,
-                callback as *mut c_void,
+                c"RAW LINE".as_ptr(),
+                c_int::from(priority.as_raw()),
+                hook_all_server_callback::<P>,
+                user_data,
             )
         };
 
         let hook = NonNull::new(hook)
             .unwrap_or_else(|| panic!("Hook handle was null, should be infallible"));
 
+        set_hook_data_owner(user_data, hook);
+
         // Safety: hook was returned by HexChat; hook is not used after this
         unsafe { HookHandle::new(hook) }
     }
@@ -1256,26 +3323,25 @@ impl<'ph, P> PluginHandle<'ph, P> {
             user_data: *mut c_void,
         ) -> c_int {
             catch_and_log_unwind("hook_server_attrs_callback", || {
-                // Safety: this is exactly the type we pass into user_data below
+                // Safety: this is exactly the type we pass into user_data below, and the hook is still registered
                 let callback: fn(
                     plugin: &P,
                     ph: PluginHandle<'_, P>,
                     attrs: EventAttrs<'_>,
                     args: [&HexStr; N],
-                ) -> Eat = unsafe { mem::transmute(user_data) };
+                ) -> Eat = *unsafe { hook_data(user_data) };
 
                 // Safety: attrs is a valid hexchat_event_attrs pointer
                 let timestamp = unsafe { (*attrs).server_time_utc };
-                let timestamp =
-                    OffsetDateTime::from_unix_timestamp(timestamp).unwrap_or_else(|e| {
-                        panic!("Invalid timestamp from `hexchat_event_attrs`: {}", e)
-                    });
+                let timestamp = timestamp_to_datetime(timestamp, "hexchat_event_attrs");
+
+                let arena = LossyArena::default();
 
                 // Safety: attrs is a valid hexchat_event_attrs pointer; ircv3_line is a valid string; temporary does not outlive this function
                 #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
-                let ircv3_line = unsafe { CStr::from_ptr((*attrs).ircv3_line) }
-                    .to_str()
-                    .unwrap_or_else(|e| panic!("Invalid UTF8 from `hexchat_event_attrs`: {}", e));
+                let ircv3_line = unsafe { CStr::from_ptr((*attrs).ircv3_line) };
+                #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
+                let ircv3_line = arena.hexstr(ircv3_line, "hexchat_event_attrs").as_str();
 
                 let attrs = EventAttrs::new(
                     timestamp,
@@ -1284,31 +3350,39 @@ impl<'ph, P> PluginHandle<'ph, P> {
                 );
 
                 // Safety: `word` is a valid word pointer for this entire callback
-                let word = unsafe { word_to_iter(&word) };
+                let word = unsafe { word_to_iter(&word, &arena) };
+
                 // Safety: `word_eol` is a valid word pointer for this entire callback
-                let word_eol = unsafe { word_to_iter(&word_eol) };
+                let word_eol = unsafe { word_to_iter(&word_eol, &arena) };
                 let args = E::args_from_words(word, word_eol);
 
                 with_plugin_state(|plugin, ph| callback(plugin, ph, attrs, args))
             })
-            .unwrap_or(Eat::None) as c_int
+            .unwrap_or_else(|()| {
+                handle_hook_panic::<P>(user_data);
+                Eat::None
+            }) as c_int
         }
 
         let _ = event;
 
+        let user_data = register_hook_data(callback);
+
         // Safety: NAME is a null-terminated C string
         let hook = unsafe {
             self.raw.hexchat_hook_server_attrs(
                 E::NAME.as_ptr(),
-                priority as c_int,
+                c_int::from(priority.as_raw()),
                 hook_server_attrs_callback::<P, E, N>,
-                callback as *mut c_void,
+                user_data,
             )
         };
 
         let hook = NonNull::new(hook)
             .unwrap_or_else(|| panic!("Hook handle was null, should be infallible"));
 
+        set_hook_data_owner(user_data, hook);
+
         // Safety: hook was returned by HexChat; hook is not used after this
         unsafe { HookHandle::new(hook) }
     }
@@ -1316,6 +3390,8 @@ impl<'ph, P> PluginHandle<'ph, P> {
     /// Registers a timer hook with HexChat.
     ///
     /// `callback` will be called at the interval specified by `timeout`, with a resolution of 1 millisecond.
+    /// Sub-millisecond parts of `timeout` are truncated, except that a nonzero `timeout` of less than 1 millisecond
+    /// is rounded up to 1 millisecond, so that it does not become a timer which runs on every iteration of the main loop.
     ///
     /// Note that `callback` is a function pointer, so it cannot capture any variables.
     ///
@@ -1373,37 +3449,145 @@ impl<'ph, P> PluginHandle<'ph, P> {
         callback: fn(plugin: &P, ph: PluginHandle<'_, P>) -> Timer,
     ) -> HookHandle {
         extern "C" fn hook_timer_callback<P: 'static>(user_data: *mut c_void) -> c_int {
-            catch_and_log_unwind("hook_timer_callback", || {
-                // Safety: this is exactly the type we pass into user_data below
-                let callback: fn(plugin: &P, ph: PluginHandle<'_, P>) -> Timer =
-                    unsafe { mem::transmute(user_data) };
+            let timer = catch_and_log_unwind("hook_timer_callback", || {
+                // Safety: this is exactly the type we pass into user_data below, and the hook is still registered
+                let callback = unsafe {
+                    hook_data::<fn(plugin: &P, ph: PluginHandle<'_, P>) -> Timer>(user_data)
+                };
 
-                with_plugin_state(callback)
+                with_plugin_state(*callback)
             })
-            .unwrap_or(Timer::Stop) as c_int
+            .unwrap_or(Timer::Stop);
+
+            if let Timer::Stop = timer {
+                release_stopped_timer::<P>(user_data);
+            }
+
+            timer as c_int
         }
 
-        let milliseconds = timeout
-            .as_millis()
-            .try_into()
-            .unwrap_or_else(|e| panic!("Timeout duration too long: {}", e));
+        let milliseconds = timeout_to_millis(timeout);
+
+        // the callback is stored in the registry so that `release_stopped_timer` can find the hook when it stops
+        let user_data = register_hook_data(callback);
 
         // Safety: no precondition
         let hook = unsafe {
-            self.raw.hexchat_hook_timer(
-                milliseconds,
-                hook_timer_callback::<P>,
-                callback as *mut c_void,
-            )
+            self.raw
+                .hexchat_hook_timer(milliseconds, hook_timer_callback::<P>, user_data)
+        };
+
+        let hook = NonNull::new(hook)
+            .unwrap_or_else(|| panic!("Hook handle was null, should be infallible"));
+
+        set_hook_data_owner(user_data, hook);
+
+        // Safety: hook was returned by HexChat; hook is not used after this
+        unsafe { HookHandle::new(hook) }
+    }
+
+    /// Registers a timer hook with HexChat, using a closure which may capture variables.
+    ///
+    /// Behaves the same as [`PluginHandle::hook_timer`], but `callback` is stored on the heap.
+    /// It is dropped when the callback returns [`Timer::Stop`](crate::hook::Timer::Stop) or panics,
+    /// when the hook is passed to [`PluginHandle::unhook`], or when the plugin is unloaded.
+    ///
+    /// Analogous to [`hexchat_hook_timer`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_hook_timer).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::cell::Cell;
+    /// use std::time::Duration;
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::hook::Timer;
+    ///
+    /// fn countdown<P>(ph: PluginHandle<'_, P>, from: u32) {
+    ///     let remaining = Cell::new(from);
+    ///     ph.hook_timer_boxed(Duration::from_secs(1), move |plugin, ph| {
+    ///         ph.print(format!("{}...", remaining.get()));
+    ///         remaining.set(remaining.get().saturating_sub(1));
+    ///         if remaining.get() > 0 {
+    ///             Timer::Continue
+    ///         } else {
+    ///             Timer::Stop
+    ///         }
+    ///     });
+    /// }
+    /// ```
+    pub fn hook_timer_boxed<F>(self, timeout: Duration, callback: F) -> HookHandle
+    where
+        F: Fn(&P, PluginHandle<'_, P>) -> Timer + 'static,
+    {
+        extern "C" fn hook_timer_boxed_callback<P: 'static, F>(user_data: *mut c_void) -> c_int
+        where
+            F: Fn(&P, PluginHandle<'_, P>) -> Timer + 'static,
+        {
+            let timer = catch_and_log_unwind("hook_timer_boxed_callback", || {
+                // Safety: this is exactly the type we pass into user_data below, and the hook is still registered
+                let callback = unsafe { hook_data::<F>(user_data) };
+
+                with_plugin_state(|plugin, ph| callback(plugin, ph))
+            })
+            .unwrap_or(Timer::Stop);
+
+            if let Timer::Stop = timer {
+                release_stopped_timer::<P>(user_data);
+            }
+
+            timer as c_int
+        }
+
+        let milliseconds = timeout_to_millis(timeout);
+
+        let user_data = register_hook_data(callback);
+
+        // Safety: no precondition
+        let hook = unsafe {
+            self.raw
+                .hexchat_hook_timer(milliseconds, hook_timer_boxed_callback::<P, F>, user_data)
         };
 
         let hook = NonNull::new(hook)
             .unwrap_or_else(|| panic!("Hook handle was null, should be infallible"));
 
+        set_hook_data_owner(user_data, hook);
+
         // Safety: hook was returned by HexChat; hook is not used after this
         unsafe { HookHandle::new(hook) }
     }
 
+    /// Sets the number of times a hook's callback may panic before the hook is automatically unregistered.
+    ///
+    /// By default (`None`), panics in hook callbacks are caught and logged, but the hook remains registered,
+    /// so a callback which panics on every invocation will keep doing so.
+    /// With a limit, each hook's panics are counted, and once a hook reaches the limit, it is unhooked as if by [`PluginHandle::unhook`].
+    ///
+    /// This applies to every command, print, and server hook, whether registered with a plain `fn` pointer
+    /// (e.g. [`PluginHandle::hook_command`]) or with a closure (e.g. [`PluginHandle::hook_command_boxed`]).
+    /// Timers registered with [`PluginHandle::hook_timer`] or [`PluginHandle::hook_timer_boxed`]
+    /// always stop after their callback panics.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::num::NonZeroU32;
+    /// use hexavalent::{Plugin, PluginHandle};
+    ///
+    /// #[derive(Default)]
+    /// struct MyPlugin;
+    ///
+    /// impl Plugin for MyPlugin {
+    ///     fn init(&self, ph: PluginHandle<'_, Self>) {
+    ///         // unhook misbehaving callbacks after they panic 3 times
+    ///         ph.set_hook_panic_limit(NonZeroU32::new(3));
+    ///     }
+    /// }
+    /// ```
+    pub fn set_hook_panic_limit(self, limit: Option<NonZeroU32>) {
+        set_hook_panic_limit(limit);
+    }
+
     /// Unregisters a hook from HexChat.
     ///
     /// Used with hook registrations functions such as [`PluginHandle::hook_command`].
@@ -1411,6 +3595,9 @@ impl<'ph, P> PluginHandle<'ph, P> {
     /// HexChat automatically unhooks any remaining hooks after your plugin finishes unloading,
     /// so this function is only useful if you need to unhook a hook while your plugin is running.
     ///
+    /// If the hook is no longer registered, e.g. because it is a timer which returned [`Timer::Stop`](crate::hook::Timer::Stop),
+    /// this does nothing. See [`PluginHandle::is_hook_registered`].
+    ///
     /// # Example
     ///
     /// ```rust
@@ -1443,11 +3630,126 @@ impl<'ph, P> PluginHandle<'ph, P> {
     /// }
     /// ```
     pub fn unhook(self, hook: HookHandle) {
-        let hook = hook.into_raw();
+        if let Some(hook) = hook.as_live_raw() {
+            // Safety: hook is still registered
+            unsafe { self.unhook_raw(hook) };
+        }
+    }
 
-        // Safety: hook is valid due to HookHandle invariant
-        let _ = unsafe { self.raw.hexchat_unhook(hook.as_ptr()) };
+    /// Whether a hook is still registered with HexChat.
+    ///
+    /// Hooks are unregistered by [`PluginHandle::unhook`], when a timer returns [`Timer::Stop`](crate::hook::Timer::Stop),
+    /// or when a hook is unhooked after reaching the [panic limit](PluginHandle::set_hook_panic_limit).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::hook::HookHandle;
+    ///
+    /// fn report_timer<P>(ph: PluginHandle<'_, P>, timer: &HookHandle) {
+    ///     if ph.is_hook_registered(timer) {
+    ///         ph.print("The timer is still running.");
+    ///     } else {
+    ///         ph.print("The timer has stopped.");
+    ///     }
+    /// }
+    /// ```
+    pub fn is_hook_registered(self, hook: &HookHandle) -> bool {
+        hook.as_live_raw().is_some()
+    }
+
+    /// In debug builds, warns if this plugin has already hooked the command `name`, which is usually a mistake.
+    fn check_command_collision(self, name: &CStr, hook: NonNull<hexchat_hook>) {
+        #[cfg(debug_assertions)]
+        if register_command_hook(hook, &name.to_string_lossy()) {
+            let message = format!(
+                "WARNING: `hexavalent` plugin hooked command `{}` more than once",
+                name.to_string_lossy()
+            );
+            eprintln!("{}", message);
+            self.print(message);
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = (name, hook);
+    }
+
+    /// Unregisters a hook from HexChat, freeing any data it owns.
+    ///
+    /// # Safety
+    ///
+    /// `hook` must still be registered.
+    unsafe fn unhook_raw(self, hook: NonNull<hexchat_hook>) {
+        forget_live_hook(hook);
+
+        // Safety: hook is still registered, due to precondition
+        let user_data = unsafe { self.raw.hexchat_unhook(hook.as_ptr()) };
+
+        // if this hook owns any data (e.g. from `hook_command_boxed`), free it
+        release_hook_data(user_data);
+    }
+}
+
+/// Checks that `word` can be used as a single argument to a command, e.g. a channel or nick.
+fn command_word(word: &str) -> Result<&str, ()> {
+    if word.is_empty() || word.contains(|c: char| c.is_whitespace() || c.is_control()) {
+        return Err(());
+    }
+    Ok(word)
+}
+
+/// Checks that `text` can be used as the trailing argument to a command, e.g. a message or reason.
+fn command_text(text: &str) -> Result<&str, ()> {
+    if text.contains(['\0', '\r', '\n']) {
+        return Err(());
+    }
+    Ok(text)
+}
+
+fn non_empty(text: &str) -> Result<&str, ()> {
+    if text.is_empty() {
+        return Err(());
     }
+    Ok(text)
+}
+
+/// Converts a timer interval to milliseconds, rounding nonzero intervals of less than 1 millisecond up to 1 millisecond.
+///
+/// # Panics
+///
+/// If `timeout` is more than `i32::MAX` milliseconds.
+fn timeout_to_millis(timeout: Duration) -> c_int {
+    let milliseconds = match timeout.as_millis() {
+        0 if !timeout.is_zero() => 1,
+        milliseconds => milliseconds,
+    };
+    milliseconds
+        .try_into()
+        .unwrap_or_else(|e| panic!("Timeout duration too long: {}", e))
+}
+
+/// Frees the data owned by a timer which returned `Timer::Stop`, since HexChat unhooks it after the callback returns.
+fn release_stopped_timer<P: 'static>(user_data: *mut c_void) {
+    let _ = catch_and_log_unwind("release_stopped_timer", || {
+        with_plugin_state(|_: &P, _| release_hook_data(user_data))
+    });
+}
+
+/// Counts a panic in the callback of the hook which owns `user_data`, and unhooks it if it has reached the panic limit.
+#[cold]
+#[inline(never)]
+fn handle_hook_panic<P: 'static>(user_data: *mut c_void) {
+    let _ = catch_and_log_unwind("handle_hook_panic", || {
+        with_plugin_state(|_: &P, ph| {
+            if let Some(hook) = record_hook_panic(user_data) {
+                ph.print(
+                    c"WARNING: `hexavalent` unhooked a callback which panicked too many times",
+                );
+                // Safety: hook was returned by HexChat, and is still registered since its data has not been released
+                unsafe { ph.unhook_raw(hook) };
+            }
+        })
+    });
 }
 
 /// [Context Functions](https://hexchat.readthedocs.io/en/latest/plugins.html#context-functions)
@@ -1468,41 +3770,161 @@ impl<'ph, P> PluginHandle<'ph, P> {
     /// Returns a [`ContextHandle`](crate::context::ContextHandle) which should be passed to
     /// [`PluginHandle::with_context`] to enter the context.
     ///
-    /// Analogous to [`hexchat_find_context`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_find_context).
+    /// Analogous to [`hexchat_find_context`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_find_context).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::context::Context;
+    ///
+    /// fn find_context_example<P>(ph: PluginHandle<'_, P>) {
+    ///     if let Some(ctxt) = ph.find_context(Context::focused()) {
+    ///         ph.with_context(ctxt, || ph.print(c"This tab is focused!"));
+    ///     }
+    ///     if let Some(ctxt) = ph.find_context(Context::channel(c"#help")) {
+    ///         ph.with_context(ctxt, || ph.print(c"This tab is #help!"));
+    ///     }
+    ///     if let Some(ctxt) = ph.find_context(Context::frontmost("Snoonet")) {
+    ///         ph.with_context(ctxt, || ph.print("This tab is frontmost on snoonet!"));
+    ///     }
+    /// }
+    /// ```
+    pub fn find_context<S>(self, find: Context<S>) -> Option<ContextHandle<'ph>>
+    where
+        S: IntoCStr,
+    {
+        let servname = find.servname.map(|s| s.into_cstr());
+        let channel = find.channel.map(|c| c.into_cstr());
+
+        let servname = servname.as_ref().map_or_else(ptr::null, |s| s.as_ptr());
+        let channel = channel.as_ref().map_or_else(ptr::null, |c| c.as_ptr());
+
+        // Safety: `servname` and `channel` are null-terminated C strings or null
+        let context = unsafe { self.raw.hexchat_find_context(servname, channel) };
+
+        // Safety: context is either a valid hexchat_context pointer or null
+        NonNull::new(context).map(|c| unsafe { ContextHandle::new(c) })
+    }
+
+    /// Finds all server/channel contexts matching the criteria.
+    ///
+    /// Unlike [`PluginHandle::find_context`], which returns at most one context,
+    /// [`Context::channel`](crate::context::Context::channel) matches the channel on every server where it is open,
+    /// and [`Context::fully_qualified`](crate::context::Context::fully_qualified) matches the channel on every server with that name or network.
+    /// Channel names are compared using [RFC1459 casemapping](crate::rfc1459).
+    ///
+    /// [`Context::focused`](crate::context::Context::focused) and [`Context::frontmost`](crate::context::Context::frontmost)
+    /// always match at most one context.
+    ///
+    /// Like those returned by [`PluginHandle::find_context`], the returned handles are only valid until the current callback returns.
+    ///
+    /// Analogous to [`hexchat_find_context`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_find_context)
+    /// combined with [`hexchat_list_get`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_list_get) for `"channels"`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::context::Context;
+    ///
+    /// fn broadcast<P>(ph: PluginHandle<'_, P>, message: &str) {
+    ///     for ctxt in ph.find_contexts(Context::channel(c"#rust")) {
+    ///         ph.with_context(ctxt, || ph.command(format!("SAY {}", message)));
+    ///     }
+    /// }
+    /// ```
+    pub fn find_contexts<S>(self, find: Context<S>) -> Vec<ContextHandle<'ph>>
+    where
+        S: IntoCStr,
+    {
+        let servname = find.servname.map(|s| s.into_cstr());
+        let channel = find.channel.map(|c| c.into_cstr());
+
+        let channel = match channel {
+            Some(channel) => channel,
+            None => {
+                let find = Context {
+                    servname: servname.as_deref(),
+                    channel: None,
+                };
+                return self.find_context(find).into_iter().collect();
+            }
+        };
+
+        // names which aren't valid UTF-8 can't match anything in the channel list
+        let (servname, channel) = match (servname.as_deref().map(CStr::to_str), channel.to_str()) {
+            (Some(Err(_)), _) | (_, Err(_)) => return Vec::new(),
+            (Some(Ok(servname)), Ok(channel)) => (Some(servname), channel),
+            (None, Ok(channel)) => (None, channel),
+        };
+
+        let channels = match self.get_list_with_fields(Channels, |elem| elem.context()) {
+            Ok(channels) => channels,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut contexts: Vec<ContextHandle<'ph>> = Vec::new();
+        for (elem, context) in channels {
+            if !rfc1459::eq(elem.name(), channel) {
+                continue;
+            }
+            if let Some(servname) = servname {
+                let matches_server = elem.servname().eq_ignore_ascii_case(servname)
+                    || elem.network().eq_ignore_ascii_case(servname);
+                if !matches_server {
+                    continue;
+                }
+            }
+            // use the element's own context, since several connections may share a server or network name
+            if let Some(context) = context {
+                // Safety: the channel list contains valid contexts
+                contexts.push(unsafe { ContextHandle::new(context) });
+            }
+        }
+        contexts
+    }
+
+    /// Gets the current server/channel context.
+    ///
+    /// Inside a hook callback, this is the context the callback runs in:
+    /// for example, in a [`PluginHandle::hook_server`] callback it is the server context where the event was received,
+    /// which can be used to reply on the same connection from within a different context.
+    ///
+    /// Like those returned by [`PluginHandle::find_context`], the returned handle is only valid until the current callback returns.
+    ///
+    /// Analogous to [`hexchat_get_context`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_get_context).
     ///
     /// # Examples
     ///
     /// ```rust
     /// use hexavalent::PluginHandle;
     /// use hexavalent::context::Context;
+    /// use hexavalent::event::server::Invite;
+    /// use hexavalent::hook::{Eat, Priority};
     ///
-    /// fn find_context_example<P>(ph: PluginHandle<'_, P>) {
-    ///     if let Some(ctxt) = ph.find_context(Context::focused()) {
-    ///         ph.with_context(ctxt, || ph.print(c"This tab is focused!"));
-    ///     }
-    ///     if let Some(ctxt) = ph.find_context(Context::channel(c"#help")) {
-    ///         ph.with_context(ctxt, || ph.print(c"This tab is #help!"));
-    ///     }
-    ///     if let Some(ctxt) = ph.find_context(Context::frontmost("Snoonet")) {
-    ///         ph.with_context(ctxt, || ph.print("This tab is frontmost on snoonet!"));
-    ///     }
+    /// struct MyPlugin;
+    ///
+    /// fn hook_invite(ph: PluginHandle<'_, MyPlugin>) {
+    ///     ph.hook_server(Invite, Priority::Normal, |plugin, ph, args| {
+    ///         let server = ph.get_context();
+    ///         if let Some(ctxt) = ph.find_context(Context::focused()) {
+    ///             ph.with_context(ctxt, || ph.print(format!("Invited to {}", args[3])));
+    ///         }
+    ///         ph.with_context(server, || ph.command(format!("JOIN {}", args[3])));
+    ///         Eat::None
+    ///     });
     /// }
     /// ```
-    pub fn find_context<S>(self, find: Context<S>) -> Option<ContextHandle<'ph>>
-    where
-        S: IntoCStr,
-    {
-        let servname = find.servname.map(|s| s.into_cstr());
-        let channel = find.channel.map(|c| c.into_cstr());
-
-        let servname = servname.as_ref().map_or_else(ptr::null, |s| s.as_ptr());
-        let channel = channel.as_ref().map_or_else(ptr::null, |c| c.as_ptr());
+    pub fn get_context(self) -> ContextHandle<'ph> {
+        // Safety: no preconditions
+        let context = unsafe { self.raw.hexchat_get_context() };
 
-        // Safety: `servname` and `channel` are null-terminated C strings or null
-        let context = unsafe { self.raw.hexchat_find_context(servname, channel) };
+        let context = NonNull::new(context)
+            .unwrap_or_else(|| panic!("Current context was null, should be infallible"));
 
-        // Safety: context is either a valid hexchat_context pointer or null
-        NonNull::new(context).map(|c| unsafe { ContextHandle::new(c) })
+        // Safety: context is a valid hexchat_context pointer
+        unsafe { ContextHandle::new(context) }
     }
 
     /// Executes a function in a different server/channel context.
@@ -1535,14 +3957,42 @@ impl<'ph, P> PluginHandle<'ph, P> {
     /// }
     /// ```
     pub fn with_context<R>(self, context: ContextHandle<'_>, f: impl FnOnce() -> R) -> R {
+        self.try_with_context(context, f)
+            // this should be infallible, since the lifetime on ContextHandle prevents it from being stored,
+            // and it should not be invalidated while our code is running
+            .unwrap_or_else(|_| panic!("Channel invalidated while plugin running"))
+    }
+
+    /// Executes a function in a different server/channel context, returning an error instead of panicking if it cannot be entered.
+    ///
+    /// Behaves the same as [`PluginHandle::with_context`], but returns [`ContextError::Invalidated`]
+    /// if HexChat refuses to switch to the context.
+    ///
+    /// Analogous to [`hexchat_get_context`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_get_context) and
+    /// [`hexchat_set_context`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_set_context).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::context::{Context, ContextError};
+    ///
+    /// fn print_in_focused<P>(ph: PluginHandle<'_, P>, message: &str) -> Result<(), ContextError> {
+    ///     let ctxt = ph.find_context(Context::focused()).ok_or(ContextError::NotFound)?;
+    ///     ph.try_with_context(ctxt, || ph.print(message))
+    /// }
+    /// ```
+    pub fn try_with_context<R>(
+        self,
+        context: ContextHandle<'_>,
+        f: impl FnOnce() -> R,
+    ) -> Result<R, ContextError> {
         // Safety: no preconditions
         let old_context = unsafe { self.raw.hexchat_get_context() };
 
         // Safety: `context` contains a valid context pointer
         int_to_result(unsafe { self.raw.hexchat_set_context(context.into_raw().as_ptr()) })
-            // this should be infallible, since the lifetime on ContextHandle prevents it from being stored,
-            // and it should not be invalidated while our code is running
-            .unwrap_or_else(|_| panic!("Channel invalidated while plugin running"));
+            .map_err(|()| ContextError::Invalidated)?;
 
         // Safety: `old_context` is a valid context pointer
         defer! {
@@ -1550,7 +4000,149 @@ impl<'ph, P> PluginHandle<'ph, P> {
                 .unwrap_or_else(|_| panic!("Failed to switch back to original context"))
         };
 
-        f()
+        Ok(f())
+    }
+
+    /// Executes a function in the specified channel.
+    ///
+    /// The channel is found as with [`Context::channel`]: in the current server if possible, otherwise in any server.
+    ///
+    /// Returns [`ContextError::NotFound`] if no such channel is open,
+    /// or [`ContextError::Invalidated`] if HexChat refuses to switch to it.
+    ///
+    /// Behaves the same as [`PluginHandle::find_context`] followed by [`PluginHandle::try_with_context`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::context::ContextError;
+    ///
+    /// fn announce<P>(ph: PluginHandle<'_, P>, message: &str) {
+    ///     match ph.in_channel("#announcements", || ph.print(message)) {
+    ///         Ok(()) => {}
+    ///         Err(ContextError::NotFound) => ph.print("Not in #announcements"),
+    ///         Err(e) => ph.print(format!("Failed to announce: {}", e)),
+    ///     }
+    /// }
+    /// ```
+    pub fn in_channel<R>(self, name: &str, f: impl FnOnce() -> R) -> Result<R, ContextError> {
+        let context = self
+            .find_context(Context::channel(name))
+            .ok_or(ContextError::NotFound)?;
+        self.try_with_context(context, f)
+    }
+
+    /// Prints text to the server tab of the current server, rather than the current channel.
+    ///
+    /// Returns [`ContextError::NotFound`] if there is no current server or its server tab is closed,
+    /// or [`ContextError::Invalidated`] if HexChat refuses to switch to it.
+    ///
+    /// Behaves the same as [`PluginHandle::print`] in the server tab's context.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn report_reconnect<P>(ph: PluginHandle<'_, P>, attempt: u32) {
+    ///     if ph.print_to_server(format!("Reconnect attempt {}", attempt)).is_err() {
+    ///         ph.print(format!("Reconnect attempt {}", attempt));
+    ///     }
+    /// }
+    /// ```
+    pub fn print_to_server(self, text: impl IntoCStr) -> Result<(), ContextError> {
+        let id = self.current_server_id().ok_or(ContextError::NotFound)?;
+        let context = self.find_server_context(|channel| channel.server_id() == id)?;
+        self.try_with_context(context, || self.print(text))
+    }
+
+    /// Prints text to the server tab of the named network.
+    ///
+    /// Network names are compared case-insensitively.
+    /// If multiple servers are open for the network, one of their server tabs is chosen arbitrarily.
+    ///
+    /// Returns [`ContextError::NotFound`] if no server tab is open for the network,
+    /// or [`ContextError::Invalidated`] if HexChat refuses to switch to it.
+    ///
+    /// Behaves the same as [`PluginHandle::print`] in the server tab's context.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn report_sync<P>(ph: PluginHandle<'_, P>) {
+    ///     let _ = ph.print_to_network("Libera.Chat", "Channel list synced.");
+    /// }
+    /// ```
+    pub fn print_to_network(self, network: &str, text: impl IntoCStr) -> Result<(), ContextError> {
+        let context =
+            self.find_server_context(|channel| channel.network().eq_ignore_ascii_case(network))?;
+        self.try_with_context(context, || self.print(text))
+    }
+
+    /// Finds the context of the first server tab matching `pred`.
+    fn find_server_context(
+        self,
+        pred: impl Fn(&Channel) -> bool,
+    ) -> Result<ContextHandle<'ph>, ContextError> {
+        // use the server tab's own context, since several connections may share a server or network name
+        let context = self
+            .get_list_with_fields(Channels, |elem| elem.context())
+            .ok()
+            .and_then(|mut channels| {
+                channels.find(|(channel, _)| channel.is_server() && pred(channel))
+            })
+            .and_then(|(_, context)| context)
+            .ok_or(ContextError::NotFound)?;
+        // Safety: the channel list contains valid contexts
+        Ok(unsafe { ContextHandle::new(context) })
+    }
+
+    /// Gets an owned key identifying the current channel and its network, e.g. for keying per-channel state.
+    ///
+    /// Returns `None` if the current network is not known.
+    ///
+    /// # Examples
+    ///
+    /// See [`ContextKey`](crate::context::ContextKey).
+    pub fn current_context_key(self) -> Option<ContextKey> {
+        let network = self.get_info(Network)?;
+        let channel = self.get_info(crate::info::Channel);
+        Some(ContextKey::new(network, channel))
+    }
+
+    /// Saves the current server/channel context, so it can be re-entered in a later callback.
+    ///
+    /// The context is identified by server name (or network name, if the server name is not known) and channel name.
+    ///
+    /// Used with [`PluginHandle::enter_saved`].
+    ///
+    /// # Examples
+    ///
+    /// See [`SavedContext`](crate::context::SavedContext).
+    pub fn save_context(self) -> SavedContext {
+        let servname = self.get_info(Server).or_else(|| self.get_info(Network));
+        let channel = self.get_info(crate::info::Channel);
+        match servname {
+            Some(servname) => SavedContext::fully_qualified(servname, channel),
+            None => SavedContext::channel(channel),
+        }
+    }
+
+    /// Executes a function in a saved server/channel context.
+    ///
+    /// Returns `Err(())` if the context no longer exists, e.g. because the channel was closed.
+    ///
+    /// Behaves the same as [`PluginHandle::find_context`] followed by [`PluginHandle::with_context`].
+    ///
+    /// # Examples
+    ///
+    /// See [`SavedContext`](crate::context::SavedContext).
+    pub fn enter_saved<R>(self, saved: &SavedContext, f: impl FnOnce() -> R) -> Result<R, ()> {
+        let context = self.find_context(saved.as_context()).ok_or(())?;
+        Ok(self.with_context(context, f))
     }
 }
 
@@ -1560,7 +4152,8 @@ impl<'ph, P> PluginHandle<'ph, P> {
 impl<'ph, P> PluginHandle<'ph, P> {
     /// Sets a plugin-specific string preference.
     ///
-    /// Fails if `value` exceeds 511 bytes in length.
+    /// Fails if `value` exceeds 511 bytes in length,
+    /// or if `name` is reserved for the chunks of [`PluginHandle::pluginpref_set_large`] (i.e. contains `__chunk_`).
     ///
     /// Analogous to [`hexchat_pluginpref_set_str`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_pluginpref_set_str).
     ///
@@ -1575,6 +4168,14 @@ impl<'ph, P> PluginHandle<'ph, P> {
     /// ```
     pub fn pluginpref_set_str(self, name: impl IntoCStr, value: impl IntoCStr) -> Result<(), ()> {
         let name = name.into_cstr();
+        if is_reserved_pref_name(name.to_bytes()) {
+            return Err(());
+        }
+        self.pluginpref_set_str_unchecked(&name, value)
+    }
+
+    /// Sets a plugin-specific string preference, without checking whether `name` is reserved.
+    fn pluginpref_set_str_unchecked(self, name: &CStr, value: impl IntoCStr) -> Result<(), ()> {
         let value = value.into_cstr();
 
         // Undocumented limit of 512 characters
@@ -1659,16 +4260,16 @@ impl<'ph, P> PluginHandle<'ph, P> {
         let str = CStr::from_bytes_until_nul(&buf)
             .unwrap_or_else(|e| panic!("Buffer overrun in `hexchat_pluginpref_get_str`: {}", e));
 
-        let str = HexStr::from_cstr(str)
-            .unwrap_or_else(|e| panic!("Invalid UTF8 from `hexchat_pluginpref_get_str`: {}", e));
-
-        f(Ok(str))
+        let arena = LossyArena::default();
+        f(Ok(arena.hexstr(str, "hexchat_pluginpref_get_str")))
     }
 
     /// Sets a plugin-specific int preference.
     ///
     /// `-1` is a reserved value and cannot be used.
     ///
+    /// Fails if `name` is reserved for the chunks of [`PluginHandle::pluginpref_set_large`] (i.e. contains `__chunk_`).
+    ///
     /// Analogous to [`hexchat_pluginpref_set_int`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_pluginpref_set_int).
     ///
     /// # Examples
@@ -1682,6 +4283,9 @@ impl<'ph, P> PluginHandle<'ph, P> {
     /// ```
     pub fn pluginpref_set_int(self, name: impl IntoCStr, value: i32) -> Result<(), ()> {
         let name = name.into_cstr();
+        if is_reserved_pref_name(name.to_bytes()) {
+            return Err(());
+        }
 
         // Safety: `name` is a null-terminated C string
         int_to_result(unsafe { self.raw.hexchat_pluginpref_set_int(name.as_ptr(), value) })
@@ -1735,6 +4339,156 @@ impl<'ph, P> PluginHandle<'ph, P> {
         int_to_result(unsafe { self.raw.hexchat_pluginpref_delete(name.as_ptr()) })
     }
 
+    /// Sets a plugin-specific string preference which may exceed 511 bytes in length.
+    ///
+    /// The value is escaped, so it may contain any characters, including newlines and leading whitespace,
+    /// which HexChat's config file cannot otherwise store.
+    /// The escaped value is split into chunks stored under `name__chunk_0`, `name__chunk_1`, etc.,
+    /// and the number of chunks is stored as an int preference under `name`.
+    /// Chunks left over from a previous, longer value are deleted.
+    ///
+    /// Names containing `__chunk_` are reserved for chunks, so [`PluginHandle::pluginpref_set_str`] and
+    /// [`PluginHandle::pluginpref_set_int`] reject them, and chunks cannot collide with other preferences.
+    ///
+    /// Must be read with [`PluginHandle::pluginpref_get_large`], and deleted with [`PluginHandle::pluginpref_delete_large`].
+    ///
+    /// Note that each chunk counts towards the ~4095 byte limit on the total length of preference names
+    /// returned by [`PluginHandle::pluginpref_list`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn save_history<P>(ph: PluginHandle<'_, P>, history: &[String]) -> Result<(), ()> {
+    ///     ph.pluginpref_set_large("history", &history.join("\n"))
+    /// }
+    /// ```
+    pub fn pluginpref_set_large(self, name: &str, value: &str) -> Result<(), ()> {
+        let old_count = self.pluginpref_get_int(name).unwrap_or(0);
+
+        let chunks = encode_large_pref(value);
+        for (i, chunk) in chunks.iter().enumerate() {
+            let chunk_name = large_pref_chunk_name(name, i).into_cstr();
+            self.pluginpref_set_str_unchecked(&chunk_name, &**chunk)?;
+        }
+
+        let count = i32::try_from(chunks.len()).map_err(|_| ())?;
+        self.pluginpref_set_int(name, count)?;
+
+        for stale in chunks.len()..usize::try_from(old_count).unwrap_or(0) {
+            self.pluginpref_delete(large_pref_chunk_name(name, stale))?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets a plugin-specific string preference which was set with [`PluginHandle::pluginpref_set_large`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn load_history<P>(ph: PluginHandle<'_, P>) -> Vec<String> {
+    ///     match ph.pluginpref_get_large("history") {
+    ///         Ok(history) => history.lines().map(ToOwned::to_owned).collect(),
+    ///         Err(()) => Vec::new(),
+    ///     }
+    /// }
+    /// ```
+    pub fn pluginpref_get_large(self, name: &str) -> Result<String, ()> {
+        let count = self.pluginpref_get_int(name)?;
+
+        let mut encoded = String::new();
+        for i in 0..usize::try_from(count).map_err(|_| ())? {
+            self.pluginpref_get_str_with(large_pref_chunk_name(name, i), |chunk| {
+                chunk.map(|chunk| encoded.push_str(chunk))
+            })?;
+        }
+
+        decode_large_pref(&encoded)
+    }
+
+    /// Deletes a plugin-specific string preference which was set with [`PluginHandle::pluginpref_set_large`].
+    ///
+    /// Returns `Ok(())` both when an existing preference is deleted and when no preference with `name` exists.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn clear_history<P>(ph: PluginHandle<'_, P>) -> Result<(), ()> {
+    ///     ph.pluginpref_delete_large("history")
+    /// }
+    /// ```
+    pub fn pluginpref_delete_large(self, name: &str) -> Result<(), ()> {
+        let count = self.pluginpref_get_int(name).unwrap_or(0);
+
+        for i in 0..usize::try_from(count).unwrap_or(0) {
+            self.pluginpref_delete(large_pref_chunk_name(name, i))?;
+        }
+
+        self.pluginpref_delete(name)
+    }
+
+    /// Sets a plugin-specific preference to the JSON serialization of `value`.
+    ///
+    /// The JSON is serialized compactly and stored with [`PluginHandle::pluginpref_set_large`],
+    /// so it may exceed 511 bytes in length, and strings within it may contain any characters.
+    /// It can be read with [`PluginHandle::pluginpref_get_json`], and deleted with [`PluginHandle::pluginpref_delete_large`].
+    ///
+    /// Fails if `value` cannot be serialized, or if the preference cannot be set.
+    ///
+    /// Requires the `serde` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    ///
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn save_aliases<P>(ph: PluginHandle<'_, P>, aliases: &BTreeMap<String, String>) -> Result<(), ()> {
+    ///     ph.pluginpref_set_json("aliases", aliases)
+    /// }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn pluginpref_set_json<T>(self, name: &str, value: &T) -> Result<(), ()>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        let json = serde_json::to_string(value).map_err(|_| ())?;
+        self.pluginpref_set_large(name, &json)
+    }
+
+    /// Gets a plugin-specific preference which was set with [`PluginHandle::pluginpref_set_json`].
+    ///
+    /// Fails if the preference does not exist, or if it cannot be deserialized as `T`.
+    ///
+    /// Requires the `serde` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    ///
+    /// use hexavalent::PluginHandle;
+    ///
+    /// fn load_aliases<P>(ph: PluginHandle<'_, P>) -> BTreeMap<String, String> {
+    ///     ph.pluginpref_get_json("aliases").unwrap_or_default()
+    /// }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn pluginpref_get_json<T>(self, name: &str) -> Result<T, ()>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let json = self.pluginpref_get_large(name)?;
+        serde_json::from_str(&json).map_err(|_| ())
+    }
+
     /// Lists the names of all plugin-specific preferences.
     ///
     /// Note that the total length of all preference names is limited to about 4095 bytes.
@@ -1821,9 +4575,10 @@ impl<'ph, P> PluginHandle<'ph, P> {
         let buf = buf.map(|x| x as u8);
 
         let str = CStr::from_bytes_until_nul(&buf)
-            .unwrap_or_else(|e| panic!("Buffer overrun in `hexchat_pluginpref_list`: {}", e))
-            .to_str()
-            .unwrap_or_else(|e| panic!("Invalid UTF8 from `hexchat_pluginpref_list`: {}", e));
+            .unwrap_or_else(|e| panic!("Buffer overrun in `hexchat_pluginpref_list`: {}", e));
+
+        let arena = LossyArena::default();
+        let str = arena.hexstr(str, "hexchat_pluginpref_list").as_str();
 
         let str = str.trim_end_matches(',');
 
@@ -1877,6 +4632,23 @@ impl<'ph, P> PluginHandle<'ph, P> {
         unsafe { FakePluginHandle::new(gui) }
     }
 
+    /// Adds a fake plugin to the plugin GUI, which is removed when the returned guard is dropped.
+    ///
+    /// Behaves the same as [`PluginHandle::plugingui_add`], but returns a [`FakePluginGuard`](crate::gui::FakePluginGuard)
+    /// which calls [`PluginHandle::plugingui_remove`] on drop.
+    ///
+    /// Analogous to [`hexchat_plugingui_add`](https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_plugingui_add).
+    pub fn plugingui_add_scoped(
+        self,
+        filename: impl IntoCStr,
+        name: impl IntoCStr,
+        desc: impl IntoCStr,
+        version: impl IntoCStr,
+    ) -> FakePluginGuard<'ph, P> {
+        let gui = self.plugingui_add(filename, name, desc, version);
+        FakePluginGuard::new(self, gui)
+    }
+
     /// Removes a fake plugin from the plugin GUI.
     ///
     /// Used with [`PluginHandle::plugingui_add`].
@@ -1889,3 +4661,173 @@ impl<'ph, P> PluginHandle<'ph, P> {
         unsafe { self.raw.hexchat_plugingui_remove(gui.as_ptr()) };
     }
 }
+
+/// Marks the names of chunks stored by `pluginpref_set_large`, after HexChat canonicalizes them.
+const LARGE_PREF_CHUNK_MARKER: &[u8] = b"__chunk_";
+
+/// The longest chunk stored by `pluginpref_set_large`, leaving room for the null terminator.
+const LARGE_PREF_MAX_CHUNK_LEN: usize = 511;
+
+fn large_pref_chunk_name(name: &str, index: usize) -> String {
+    format!("{}__chunk_{}", name, index)
+}
+
+/// Whether `name` could collide with a chunk stored by `pluginpref_set_large`.
+fn is_reserved_pref_name(name: &[u8]) -> bool {
+    // HexChat lowercases names and replaces characters other than `[a-zA-Z0-9_]` with `_`
+    // https://github.com/hexchat/hexchat/blob/57478b65758e6b697b1d82ce21075e74aa475efc/src/common/plugin.c#L1878
+    let canonical: Vec<u8> = name
+        .iter()
+        .map(|&b| {
+            if b.is_ascii_alphanumeric() {
+                b.to_ascii_lowercase()
+            } else {
+                b'_'
+            }
+        })
+        .collect();
+    canonical
+        .windows(LARGE_PREF_CHUNK_MARKER.len())
+        .any(|w| w == LARGE_PREF_CHUNK_MARKER)
+}
+
+/// Escapes `value` so that it contains no whitespace, and splits it into chunks which fit in a string preference.
+///
+/// HexChat's config file is line-based and skips whitespace before each value,
+/// so newlines and leading whitespace would otherwise be lost.
+fn encode_large_pref(value: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    let mut buf = [0; 4];
+
+    for c in value.chars() {
+        let escaped = match c {
+            '\\' => "\\\\",
+            ' ' => "\\s",
+            '\t' => "\\t",
+            '\n' => "\\n",
+            '\r' => "\\r",
+            c => c.encode_utf8(&mut buf),
+        };
+        // escapes are never split across chunks
+        if chunk.len() + escaped.len() > LARGE_PREF_MAX_CHUNK_LEN {
+            chunks.push(mem::take(&mut chunk));
+        }
+        chunk.push_str(escaped);
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+
+    chunks
+}
+
+/// Reverses `encode_large_pref`, given the concatenated chunks.
+fn decode_large_pref(encoded: &str) -> Result<String, ()> {
+    let mut value = String::with_capacity(encoded.len());
+    let mut chars = encoded.chars();
+    while let Some(c) = chars.next() {
+        value.push(match c {
+            '\\' => match chars.next() {
+                Some('\\') => '\\',
+                Some('s') => ' ',
+                Some('t') => '\t',
+                Some('n') => '\n',
+                Some('r') => '\r',
+                _ => return Err(()),
+            },
+            c => c,
+        });
+    }
+    Ok(value)
+}
+
+/// Groups consecutive items with equal keys, preserving the order of items.
+fn group_consecutive<K: PartialEq, T>(items: impl IntoIterator<Item = (K, T)>) -> Vec<(K, Vec<T>)> {
+    let mut groups: Vec<(K, Vec<T>)> = Vec::new();
+    for (key, item) in items {
+        match groups.last_mut() {
+            Some((last, group)) if *last == key => group.push(item),
+            _ => groups.push((key, vec![item])),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn large_pref_round_trip() {
+        let value = format!(
+            "  leading\n\tindented\r\nback\\slash {}",
+            "\u{e9}".repeat(300)
+        );
+        let chunks = encode_large_pref(&value);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.len() <= LARGE_PREF_MAX_CHUNK_LEN);
+            assert!(!chunk.contains(char::is_whitespace));
+        }
+        assert_eq!(decode_large_pref(&chunks.concat()), Ok(value));
+
+        assert_eq!(encode_large_pref(""), Vec::<String>::new());
+        assert_eq!(decode_large_pref("trailing\\"), Err(()));
+    }
+
+    #[test]
+    fn reserved_pref_names() {
+        assert!(is_reserved_pref_name(b"history__chunk_0"));
+        assert!(is_reserved_pref_name(b"History..Chunk.0"));
+        assert!(!is_reserved_pref_name(b"history"));
+        assert!(!is_reserved_pref_name(b"history_0"));
+    }
+
+    #[test]
+    fn mode_changes_keep_order() {
+        let groups = group_consecutive([
+            ((Sign::Add, b'o'), "alice"),
+            ((Sign::Remove, b'o'), "alice"),
+            ((Sign::Add, b'o'), "alice"),
+            ((Sign::Add, b'o'), "bob"),
+        ]);
+        assert_eq!(
+            groups,
+            [
+                ((Sign::Add, b'o'), vec!["alice"]),
+                ((Sign::Remove, b'o'), vec!["alice"]),
+                ((Sign::Add, b'o'), vec!["alice", "bob"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn command_args() {
+        assert_eq!(command_word("#rust"), Ok("#rust"));
+        assert_eq!(command_word(""), Err(()));
+        assert_eq!(command_word("#rust 0"), Err(()));
+        assert_eq!(command_word("#rust\r\nQUIT"), Err(()));
+        assert_eq!(command_word("nick\0"), Err(()));
+
+        assert_eq!(command_text("hello world"), Ok("hello world"));
+        assert_eq!(command_text(""), Ok(""));
+        assert_eq!(command_text("hi\nQUIT"), Err(()));
+        assert_eq!(command_text("hi\r"), Err(()));
+    }
+
+    #[test]
+    fn timeout_rounding() {
+        assert_eq!(timeout_to_millis(Duration::ZERO), 0);
+        assert_eq!(timeout_to_millis(Duration::from_nanos(1)), 1);
+        assert_eq!(timeout_to_millis(Duration::from_micros(500)), 1);
+        assert_eq!(timeout_to_millis(Duration::from_micros(1500)), 1);
+        assert_eq!(timeout_to_millis(Duration::from_secs(2)), 2000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn timeout_too_long() {
+        timeout_to_millis(Duration::from_millis(i32::MAX as u64 + 1));
+    }
+}
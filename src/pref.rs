@@ -1,5 +1,8 @@
 //! Global preferences.
 
+use std::ffi::CStr;
+use std::fmt;
+
 use crate::str::HexString;
 
 /// The value of a HexChat setting.
@@ -40,11 +43,103 @@ pub(crate) mod private {
 
     #[allow(unreachable_pub)]
     pub trait FromPrefValue: Sized {
+        const KIND: super::PrefKind;
+
         fn from_pref_value(pref: PrefValue<'_>) -> Result<Self, ()>;
     }
 }
 
+/// The name and type of a preference, for listing preferences at runtime.
+///
+/// See [`ALL`] and [`special::ALL`], and use with [`PluginHandle::get_pref_data`](crate::PluginHandle::get_pref_data).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PrefInfo {
+    name: &'static CStr,
+    kind: PrefKind,
+}
+
+impl PrefInfo {
+    pub(crate) const fn new(name: &'static CStr, kind: PrefKind) -> Self {
+        Self { name, kind }
+    }
+
+    /// The name of the preference, as used by `/set`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::pref;
+    ///
+    /// assert!(pref::ALL.iter().any(|pref| pref.name() == "irc_nick1"));
+    /// ```
+    pub fn name(&self) -> &'static str {
+        match self.name.to_str() {
+            Ok(name) => name,
+            Err(_) => unreachable!("pref names are ASCII"),
+        }
+    }
+
+    /// The type of the preference's value.
+    pub fn kind(&self) -> PrefKind {
+        self.kind
+    }
+
+    pub(crate) fn name_cstr(&self) -> &'static CStr {
+        self.name
+    }
+}
+
+/// The type of a preference's value.
+///
+/// Part of [`PrefInfo`].
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PrefKind {
+    /// A string, read as [`HexString`].
+    Str,
+    /// An integer, read as `i32`.
+    Int,
+    /// A boolean, read as `bool`.
+    Bool,
+}
+
+/// The value of a preference whose type is only known at runtime.
+///
+/// Returned from [`PluginHandle::get_pref_data`](crate::PluginHandle::get_pref_data).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrefData {
+    /// A string value.
+    Str(HexString),
+    /// An integer value.
+    Int(i32),
+    /// A boolean value.
+    Bool(bool),
+}
+
+impl fmt::Display for PrefData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Str(x) => fmt::Display::fmt(x, f),
+            Self::Int(x) => fmt::Display::fmt(x, f),
+            Self::Bool(x) => fmt::Display::fmt(x, f),
+        }
+    }
+}
+
+impl PrefData {
+    pub(crate) fn from_pref_value(pref: private::PrefValue<'_>) -> Self {
+        match pref {
+            private::PrefValue::Str(x) => Self::Str(x.to_owned()),
+            private::PrefValue::Int(x) => Self::Int(x),
+            private::PrefValue::Bool(x) => Self::Bool(x),
+        }
+    }
+}
+
 impl private::FromPrefValue for HexString {
+    const KIND: PrefKind = PrefKind::Str;
+
     fn from_pref_value(pref: private::PrefValue<'_>) -> Result<Self, ()> {
         match pref {
             private::PrefValue::Str(x) => Ok(x.to_owned()),
@@ -54,6 +149,8 @@ impl private::FromPrefValue for HexString {
 }
 
 impl private::FromPrefValue for i32 {
+    const KIND: PrefKind = PrefKind::Int;
+
     fn from_pref_value(pref: private::PrefValue<'_>) -> Result<Self, ()> {
         match pref {
             private::PrefValue::Int(x) => Ok(x),
@@ -63,6 +160,8 @@ impl private::FromPrefValue for i32 {
 }
 
 impl private::FromPrefValue for bool {
+    const KIND: PrefKind = PrefKind::Bool;
+
     fn from_pref_value(pref: private::PrefValue<'_>) -> Result<Self, ()> {
         match pref {
             private::PrefValue::Bool(x) => Ok(x),
@@ -90,6 +189,13 @@ macro_rules! pref {
         impl crate::pref::Pref for $struct_name {
             type Type = $ty;
         }
+
+        impl $struct_name {
+            pub(crate) const INFO: crate::pref::PrefInfo = crate::pref::PrefInfo::new(
+                <Self as crate::pref::private::PrefImpl>::NAME,
+                <$ty as crate::pref::private::FromPrefValue>::KIND,
+            );
+        }
     };
 }
 
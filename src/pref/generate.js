@@ -37,9 +37,20 @@ function typeToRust(type) {
 }
 
 function* generateRustLines() {
+	const names = [];
+
 	for (const { name, type } of readPrefs()) {
+		names.push(nameToCamelCase(name));
 		yield `pref!(${nameToCamelCase(name)}, "${name}", ${typeToRust(type)});`;
 	}
+
+	yield '';
+	yield '/// Information about every preference in this module, not including [special](crate::pref::special) preferences.';
+	yield 'pub const ALL: &[crate::pref::PrefInfo] = &[';
+	for (const name of names) {
+		yield `    ${name}::INFO,`;
+	}
+	yield '];';
 }
 
 function main() {
@@ -209,3 +209,216 @@ pref!(TextWordwrap, "text_wordwrap", bool);
 pref!(UrlGrabber, "url_grabber", bool);
 pref!(UrlGrabberLimit, "url_grabber_limit", i32);
 pref!(UrlLogging, "url_logging", bool);
+
+/// Information about every preference in this module, not including [special](crate::pref::special) preferences.
+pub const ALL: &[crate::pref::PrefInfo] = &[
+    AwayAutoUnmark::INFO,
+    AwayOmitAlerts::INFO,
+    AwayReason::INFO,
+    AwayShowOnce::INFO,
+    AwaySizeMax::INFO,
+    AwayTimeout::INFO,
+    AwayTrack::INFO,
+    CompletionAmount::INFO,
+    CompletionAuto::INFO,
+    CompletionSort::INFO,
+    CompletionSuffix::INFO,
+    DccAutoChat::INFO,
+    DccAutoRecv::INFO,
+    DccAutoResume::INFO,
+    DccBlocksize::INFO,
+    DccCompletedDir::INFO,
+    DccDir::INFO,
+    DccGlobalMaxGetCps::INFO,
+    DccGlobalMaxSendCps::INFO,
+    DccIp::INFO,
+    DccIpFromServer::INFO,
+    DccMaxGetCps::INFO,
+    DccMaxSendCps::INFO,
+    DccPermissions::INFO,
+    DccPortFirst::INFO,
+    DccPortLast::INFO,
+    DccRemove::INFO,
+    DccSaveNick::INFO,
+    DccSendFillspaces::INFO,
+    DccStallTimeout::INFO,
+    DccTimeout::INFO,
+    FloodCtcpNum::INFO,
+    FloodCtcpTime::INFO,
+    FloodMsgNum::INFO,
+    FloodMsgTime::INFO,
+    GuiAutoopenChat::INFO,
+    GuiAutoopenDialog::INFO,
+    GuiAutoopenRecv::INFO,
+    GuiAutoopenSend::INFO,
+    GuiChanlistMaxusers::INFO,
+    GuiChanlistMinusers::INFO,
+    GuiCompact::INFO,
+    GuiDialogHeight::INFO,
+    GuiDialogLeft::INFO,
+    GuiDialogTop::INFO,
+    GuiDialogWidth::INFO,
+    GuiFilesizeIec::INFO,
+    GuiFocusOmitalerts::INFO,
+    GuiHideMenu::INFO,
+    GuiInputAttr::INFO,
+    GuiInputIcon::INFO,
+    GuiInputNick::INFO,
+    GuiInputSpell::INFO,
+    GuiInputStyle::INFO,
+    GuiJoinDialog::INFO,
+    GuiLagometer::INFO,
+    GuiLang::INFO,
+    GuiModeButtons::INFO,
+    GuiPaneDividerPosition::INFO,
+    GuiPaneLeftSize::INFO,
+    GuiPaneRightSize::INFO,
+    GuiPaneRightSizeMin::INFO,
+    GuiQuitDialog::INFO,
+    GuiSearchPos::INFO,
+    GuiSingle::INFO,
+    GuiSlistFav::INFO,
+    GuiSlistSelect::INFO,
+    GuiSlistSkip::INFO,
+    GuiTabChans::INFO,
+    GuiTabDialogs::INFO,
+    GuiTabDots::INFO,
+    GuiTabIcons::INFO,
+    GuiTabLayout::INFO,
+    GuiTabMiddleclose::INFO,
+    GuiTabNewtofront::INFO,
+    GuiTabPos::INFO,
+    GuiTabScrollchans::INFO,
+    GuiTabServer::INFO,
+    GuiTabSmall::INFO,
+    GuiTabSort::INFO,
+    GuiTabTrunc::INFO,
+    GuiTabUtils::INFO,
+    GuiThrottlemeter::INFO,
+    GuiTopicbar::INFO,
+    GuiTransparency::INFO,
+    GuiTray::INFO,
+    GuiTrayAway::INFO,
+    GuiTrayBlink::INFO,
+    GuiTrayClose::INFO,
+    GuiTrayMinimize::INFO,
+    GuiTrayQuiet::INFO,
+    GuiUlistButtons::INFO,
+    GuiUlistColor::INFO,
+    GuiUlistCount::INFO,
+    GuiUlistDoubleclick::INFO,
+    GuiUlistHide::INFO,
+    GuiUlistIcons::INFO,
+    GuiUlistPos::INFO,
+    GuiUlistShowHosts::INFO,
+    GuiUlistSort::INFO,
+    GuiUlistStyle::INFO,
+    GuiUrlMod::INFO,
+    GuiUsermenu::INFO,
+    GuiWinHeight::INFO,
+    GuiWinFullscreen::INFO,
+    GuiWinLeft::INFO,
+    GuiWinModes::INFO,
+    GuiWinSave::INFO,
+    GuiWinState::INFO,
+    GuiWinSwap::INFO,
+    GuiWinTop::INFO,
+    GuiWinUcount::INFO,
+    GuiWinWidth::INFO,
+    IdentdServer::INFO,
+    IdentdPort::INFO,
+    InputBalloonChans::INFO,
+    InputBalloonHilight::INFO,
+    InputBalloonPriv::INFO,
+    InputBeepChans::INFO,
+    InputBeepHilight::INFO,
+    InputBeepPriv::INFO,
+    InputCommandChar::INFO,
+    InputFilterBeep::INFO,
+    InputFlashChans::INFO,
+    InputFlashHilight::INFO,
+    InputFlashPriv::INFO,
+    InputPercAscii::INFO,
+    InputPercColor::INFO,
+    InputTrayChans::INFO,
+    InputTrayHilight::INFO,
+    InputTrayPriv::INFO,
+    IrcAutoRejoin::INFO,
+    IrcReconnectRejoin::INFO,
+    IrcBanType::INFO,
+    IrcCapServerTime::INFO,
+    IrcConfMode::INFO,
+    IrcExtraHilight::INFO,
+    IrcHideNickchange::INFO,
+    IrcHideVersion::INFO,
+    IrcHidehost::INFO,
+    IrcIdNtext::INFO,
+    IrcIdYtext::INFO,
+    IrcInvisible::INFO,
+    IrcJoinDelay::INFO,
+    IrcLogging::INFO,
+    IrcLogmask::INFO,
+    IrcNick1::INFO,
+    IrcNick2::INFO,
+    IrcNick3::INFO,
+    IrcNickHilight::INFO,
+    IrcNoHilight::INFO,
+    IrcNoticePos::INFO,
+    IrcPartReason::INFO,
+    IrcQuitReason::INFO,
+    IrcRawModes::INFO,
+    IrcRealName::INFO,
+    IrcServernotice::INFO,
+    IrcSkipMotd::INFO,
+    IrcUserName::INFO,
+    IrcWallops::INFO,
+    IrcWhoJoin::INFO,
+    IrcWhoisFront::INFO,
+    NetAutoReconnect::INFO,
+    NetBindHost::INFO,
+    NetPingTimeout::INFO,
+    NetProxyAuth::INFO,
+    NetProxyHost::INFO,
+    NetProxyPass::INFO,
+    NetProxyPort::INFO,
+    NetProxyType::INFO,
+    NetProxyUse::INFO,
+    NetProxyUser::INFO,
+    NetReconnectDelay::INFO,
+    NetThrottle::INFO,
+    NotifyTimeout::INFO,
+    NotifyWhoisOnline::INFO,
+    PerlWarnings::INFO,
+    StampLog::INFO,
+    StampLogFormat::INFO,
+    StampText::INFO,
+    StampTextFormat::INFO,
+    TextAutocopyColor::INFO,
+    TextAutocopyStamp::INFO,
+    TextAutocopyText::INFO,
+    TextBackground::INFO,
+    TextColorNicks::INFO,
+    TextFont::INFO,
+    TextFontMain::INFO,
+    TextFontAlternative::INFO,
+    TextIndent::INFO,
+    TextMaxIndent::INFO,
+    TextMaxLines::INFO,
+    TextReplay::INFO,
+    TextSearchCaseMatch::INFO,
+    TextSearchHighlightAll::INFO,
+    TextSearchFollow::INFO,
+    TextSearchRegexp::INFO,
+    TextShowMarker::INFO,
+    TextShowSep::INFO,
+    TextSpellLangs::INFO,
+    TextStripcolorMsg::INFO,
+    TextStripcolorReplay::INFO,
+    TextStripcolorTopic::INFO,
+    TextThinSep::INFO,
+    TextTransparent::INFO,
+    TextWordwrap::INFO,
+    UrlGrabber::INFO,
+    UrlGrabberLimit::INFO,
+    UrlLogging::INFO,
+];
@@ -1,2 +1,5 @@
 pref!(CursorPositionInChars, "state_cursor", i32);
 pref!(ServerId, "id", i32);
+
+/// Information about every special preference.
+pub const ALL: &[crate::pref::PrefInfo] = &[CursorPositionInChars::INFO, ServerId::INFO];
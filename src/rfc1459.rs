@@ -0,0 +1,297 @@
+//! RFC1459 casemapping, without going through HexChat.
+//!
+//! [RFC1459 says](https://tools.ietf.org/html/rfc1459#section-2.2):
+//!
+//! > Because of IRC's scandanavian origin, the characters {}| are
+//! > considered to be the lower case equivalents of the characters \[\]\\,
+//! > respectively. This is a critical issue when determining the
+//! > equivalence of two nicknames.
+//!
+//! Like HexChat, this module also treats `~` as the lower case equivalent of `^`.
+//!
+//! This module always implements HexChat's `rfc1459` casemapping, regardless of the server's `CASEMAPPING`,
+//! so it gives different results on `ascii` and `strict-rfc1459` networks.
+//! Use [`PluginHandle::nickcmp`](crate::PluginHandle::nickcmp) to compare names according to the current server.
+//!
+//! These functions do not allocate or call into HexChat, so they are suitable for hot loops,
+//! and can be used where a [`PluginHandle`](crate::PluginHandle) is not available.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+use crate::str::{HexStr, HexString};
+
+/// Converts a single byte to its RFC1459 lower case equivalent.
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::rfc1459;
+///
+/// assert_eq!(rfc1459::to_lower(b'A'), b'a');
+/// assert_eq!(rfc1459::to_lower(b'['), b'{');
+/// assert_eq!(rfc1459::to_lower(b'\\'), b'|');
+/// assert_eq!(rfc1459::to_lower(b']'), b'}');
+/// assert_eq!(rfc1459::to_lower(b'^'), b'~');
+/// assert_eq!(rfc1459::to_lower(b'1'), b'1');
+/// ```
+pub const fn to_lower(b: u8) -> u8 {
+    match b {
+        b'A'..=b'Z' | b'[' | b'\\' | b']' | b'^' => b + 32,
+        _ => b,
+    }
+}
+
+/// Compares two nicknames or channel names, using RFC1459 casemapping.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::cmp::Ordering;
+/// use hexavalent::rfc1459;
+///
+/// assert_eq!(rfc1459::cmp("Nick[away]", "nick{AWAY}"), Ordering::Equal);
+/// assert_eq!(rfc1459::cmp("alice", "Bob"), Ordering::Less);
+/// ```
+pub fn cmp(a: &str, b: &str) -> Ordering {
+    let a = a.bytes().map(to_lower);
+    let b = b.bytes().map(to_lower);
+    a.cmp(b)
+}
+
+/// Checks whether two nicknames or channel names are equal, using RFC1459 casemapping.
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::rfc1459;
+///
+/// assert!(rfc1459::eq("#Rust^Lang", "#rust~lang"));
+/// assert!(!rfc1459::eq("alice", "bob"));
+/// ```
+pub fn eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .all(|(a, b)| to_lower(a) == to_lower(b))
+}
+
+/// A borrowed string which compares and hashes using RFC1459 casemapping.
+///
+/// Useful as a key in a `HashMap` or `BTreeMap` of nicknames or channels.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use hexavalent::rfc1459::Casefolded;
+///
+/// let mut last_seen = HashMap::new();
+/// last_seen.insert(Casefolded("Alice[m]"), 1);
+///
+/// assert_eq!(last_seen.get(&Casefolded("alice{M}")), Some(&1));
+/// ```
+#[derive(Copy, Clone)]
+pub struct Casefolded<'a>(pub &'a str);
+
+impl PartialEq for Casefolded<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        eq(self.0, other.0)
+    }
+}
+
+impl Eq for Casefolded<'_> {}
+
+impl PartialOrd for Casefolded<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Casefolded<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp(self.0, other.0)
+    }
+}
+
+impl Hash for Casefolded<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_casefolded(self.0, state);
+    }
+}
+
+fn hash_casefolded<H: Hasher>(s: &str, state: &mut H) {
+    for b in s.bytes() {
+        state.write_u8(to_lower(b));
+    }
+    // match the prefix-freedom of `str`'s `Hash` impl
+    state.write_u8(0xff);
+}
+
+impl fmt::Debug for Casefolded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
+impl fmt::Display for Casefolded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.0, f)
+    }
+}
+
+/// An owned nickname or channel name which compares and hashes using RFC1459 casemapping.
+///
+/// This is the owned version of [`Casefolded`], for storing as a key in a `HashMap`, `HashSet`, or `BTreeMap`.
+/// The original case is preserved, and is used when displaying the name.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::BTreeMap;
+/// use hexavalent::rfc1459::IrcName;
+/// use hexavalent::str::HexStr;
+///
+/// fn count_message(counts: &mut BTreeMap<IrcName, u32>, nick: &HexStr) {
+///     *counts.entry(IrcName::new(nick.to_owned())).or_insert(0) += 1;
+/// }
+///
+/// let mut counts = BTreeMap::new();
+/// count_message(&mut counts, HexStr::from_bytes_with_nul(b"Alice[m]\0").unwrap());
+/// count_message(&mut counts, HexStr::from_bytes_with_nul(b"alice{M}\0").unwrap());
+/// assert_eq!(counts.len(), 1);
+/// assert_eq!(counts.keys().next().unwrap().as_str(), "Alice[m]");
+/// ```
+#[derive(Clone)]
+pub struct IrcName(HexString);
+
+impl IrcName {
+    /// Wraps a name, so that it compares and hashes using RFC1459 casemapping.
+    pub fn new(name: HexString) -> Self {
+        Self(name)
+    }
+
+    /// Gets the name, in its original case.
+    pub fn as_hexstr(&self) -> &HexStr {
+        &self.0
+    }
+
+    /// Borrows the name as a [`Casefolded`], e.g. to compare it with a borrowed name.
+    pub fn as_casefolded(&self) -> Casefolded<'_> {
+        Casefolded(&self.0)
+    }
+
+    /// Extracts the name, in its original case.
+    pub fn into_inner(self) -> HexString {
+        self.0
+    }
+}
+
+impl From<HexString> for IrcName {
+    fn from(name: HexString) -> Self {
+        Self::new(name)
+    }
+}
+
+impl Deref for IrcName {
+    type Target = HexStr;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl PartialEq for IrcName {
+    fn eq(&self, other: &Self) -> bool {
+        eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for IrcName {}
+
+impl PartialOrd for IrcName {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for IrcName {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp(&self.0, &other.0)
+    }
+}
+
+impl Hash for IrcName {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_casefolded(&self.0, state);
+    }
+}
+
+impl fmt::Debug for IrcName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for IrcName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    fn hash(s: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        Casefolded(s).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn lowercase_all_special_chars() {
+        assert!(eq("[]\\^", "{}|~"));
+        assert!(eq("ABCXYZ", "abcxyz"));
+        assert!(!eq("@", "`"));
+        assert!(!eq("_", "\x7f"));
+    }
+
+    #[test]
+    fn different_lengths() {
+        assert!(!eq("nick", "nick_"));
+        assert_eq!(cmp("nick", "nick_"), Ordering::Less);
+        assert_eq!(cmp("Nick_", "nick"), Ordering::Greater);
+    }
+
+    #[test]
+    fn non_ascii_is_unchanged() {
+        assert!(eq("Ünïcode", "Ünïcode"));
+        assert!(!eq("Ü", "ü"));
+    }
+
+    #[test]
+    fn casefolded_hash_matches_eq() {
+        assert_eq!(hash("Foo[Bar]"), hash("foo{bar}"));
+        assert_eq!(Casefolded("Foo[Bar]"), Casefolded("foo{bar}"));
+        assert_ne!(hash("ab"), hash("abc"));
+    }
+
+    #[test]
+    fn irc_name_matches_casefolded() {
+        let name = |s: &[u8]| IrcName::new(HexStr::from_bytes_with_nul(s).unwrap().to_owned());
+        let upper = name(b"Foo[Bar]\0");
+        let lower = name(b"foo{bar}\0");
+
+        assert_eq!(upper, lower);
+        assert_eq!(upper.as_str(), "Foo[Bar]");
+
+        let mut hasher = DefaultHasher::new();
+        upper.hash(&mut hasher);
+        assert_eq!(hasher.finish(), hash("foo{bar}"));
+    }
+}
@@ -1,15 +1,17 @@
 use std::any::Any;
-use std::cell::UnsafeCell;
+use std::cell::{Cell, RefCell, UnsafeCell};
+use std::collections::HashMap;
+use std::num::NonZeroU32;
 use std::ops::Deref;
-use std::os::raw::c_int;
+use std::os::raw::{c_int, c_void};
 use std::panic::{catch_unwind, UnwindSafe};
 use std::process;
 use std::ptr;
 use std::ptr::NonNull;
-use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
-use std::usize;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
 
-use crate::ffi::{hexchat_plugin, result_to_int, RawPluginHandle};
+use crate::ffi::{hexchat_hook, hexchat_plugin, result_to_int, set_lossy_utf8, RawPluginHandle};
 use crate::plugin::{Plugin, PluginHandle};
 
 /// Plugin handle used to log caught panics, when the normal (safe) plugin context might not be available.
@@ -39,10 +41,8 @@ pub(crate) fn catch_and_log_unwind<R>(
     fn handle_plugin_panic(ctxt_msg: &str, e: Box<dyn Any + Send>) {
         let panic_msg = if let Some(s) = e.downcast_ref::<String>() {
             s.as_str()
-        } else if let Some(s) = e.downcast_ref::<&'static str>() {
-            s
         } else {
-            &"<unknown>"
+            e.downcast_ref::<&'static str>().unwrap_or(&"<unknown>")
         };
 
         eprintln!(
@@ -75,6 +75,21 @@ pub(crate) fn catch_and_log_unwind<R>(
     .unwrap_or_else(|_| abort_process_due_to_panic_in_panic_logger())
 }
 
+/// Whether `Plugin::deinit` is currently running.
+static DEINITIALIZING: AtomicBool = AtomicBool::new(false);
+
+/// Whether `PluginHandle::print` writes to stderr instead of HexChat while `Plugin::deinit` is running.
+static DEINIT_PRINTS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_deinit_prints_to_stderr(to_stderr: bool) {
+    DEINIT_PRINTS_TO_STDERR.store(to_stderr, Ordering::Relaxed);
+}
+
+/// Whether `PluginHandle::print` should write to stderr instead of HexChat.
+pub(crate) fn prints_to_stderr() -> bool {
+    DEINITIALIZING.load(Ordering::Relaxed) && DEINIT_PRINTS_TO_STDERR.load(Ordering::Relaxed)
+}
+
 const NO_READERS: usize = 0;
 const LOCKED: usize = usize::MAX;
 
@@ -106,6 +121,26 @@ struct GlobalPlugin {
     thread_id: std::thread::ThreadId,
     plugin: Box<dyn Any>,
     plugin_handle: NonNull<hexchat_plugin>,
+    /// Data owned by registered hooks (e.g. closures), keyed by the `user_data` pointer passed to HexChat.
+    hook_data: RefCell<HashMap<usize, HookData>>,
+    /// Number of panics after which a hook which owns data is automatically unhooked, if any.
+    hook_panic_limit: Cell<Option<NonZeroU32>>,
+    /// Hooks which are currently registered, keyed by the `hexchat_hook` pointer, with the id of their `HookHandle`.
+    live_hooks: RefCell<HashMap<usize, u64>>,
+    /// The id to assign to the next registered hook.
+    next_hook_id: Cell<u64>,
+    /// Names of commands hooked by this plugin, keyed by the `hexchat_hook` pointer, to detect duplicates.
+    #[cfg(debug_assertions)]
+    command_hooks: RefCell<HashMap<usize, String>>,
+}
+
+struct HookData {
+    /// Keeps the data alive; `hook_data` accesses it through the `user_data` pointer.
+    data: Rc<dyn Any>,
+    /// The hook which owns this data, once HexChat has returned it.
+    hook: Option<NonNull<hexchat_hook>>,
+    /// Number of times the hook's callback has panicked.
+    panics: u32,
 }
 
 /// Global handle to the user's plugin data and the global HexChat plugin context.
@@ -119,7 +154,13 @@ static PLUGIN: ExtSync<Option<GlobalPlugin>> = ExtSync(UnsafeCell::new(None));
 ///
 /// `plugin_handle` must point to a valid `hexchat_plugin`.
 pub(crate) unsafe fn hexchat_plugin_init<P: Plugin>(plugin_handle: *mut hexchat_plugin) -> c_int {
-    result_to_int(catch_and_log_unwind("init", || {
+    if let Some(reason) = already_initialized() {
+        // Safety: forwarded to caller
+        unsafe { log_rejected_init(plugin_handle, reason) };
+        return result_to_int(Err(()));
+    }
+
+    let result = catch_and_log_unwind("init", || {
         LAST_RESORT_PLUGIN_HANDLE.store(plugin_handle, Ordering::Relaxed);
 
         let plugin_handle = match NonNull::new(plugin_handle) {
@@ -140,12 +181,69 @@ pub(crate) unsafe fn hexchat_plugin_init<P: Plugin>(plugin_handle: *mut hexchat_
                     thread_id: std::thread::current().id(),
                     plugin: Box::<P>::default(),
                     plugin_handle,
+                    hook_data: Default::default(),
+                    hook_panic_limit: Cell::new(None),
+                    live_hooks: Default::default(),
+                    next_hook_id: Cell::new(0),
+                    #[cfg(debug_assertions)]
+                    command_hooks: Default::default(),
                 });
             }
         }
 
-        with_plugin_state(|plugin: &P, ph| plugin.init(ph));
-    }))
+        with_plugin_state(|plugin: &P, ph| match plugin.try_init(ph) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                ph.print(format!("ERROR: plugin failed to initialize: {}", e));
+                Err(())
+            }
+        })
+    });
+
+    let result = result.and_then(|r| r);
+    if result.is_err() {
+        // HexChat does not call `hexchat_plugin_deinit` after a failed init, so clean up here
+        let _ = catch_and_log_unwind("init", clear_plugin_state);
+    }
+    result_to_int(result)
+}
+
+/// Checks whether the plugin is already initialized or running, returning a description of the problem if so.
+///
+/// HexChat should never do this, but rejecting the load is better than panicking or replacing the running plugin's state.
+fn already_initialized() -> Option<&'static str> {
+    let state = STATE.load(Ordering::Relaxed);
+    if state != NO_READERS {
+        return Some("while running");
+    }
+    // Safety: no references to the plugin state exist, since there are no readers
+    if unsafe { (*PLUGIN.get()).is_some() } {
+        return Some("while already initialized");
+    }
+    None
+}
+
+/// Logs that `hexchat_plugin_init` was rejected, to stderr and (if possible) using `hexchat_print`.
+///
+/// # Safety
+///
+/// `plugin_handle` must be null or point to a valid `hexchat_plugin`.
+#[cold]
+#[inline(never)]
+unsafe fn log_rejected_init(plugin_handle: *mut hexchat_plugin, reason: &str) {
+    eprintln!(
+        "ERROR: `hexavalent` plugin initialized {}, refusing to load",
+        reason
+    );
+
+    if !plugin_handle.is_null() {
+        let message = format!(
+            "ERROR: `hexavalent` plugin initialized {}, refusing to load\0",
+            reason
+        );
+        // Safety: message is null-terminated, plugin_handle is valid
+        unsafe { ((*plugin_handle).hexchat_print)(plugin_handle, message.as_ptr().cast()) }
+    }
 }
 
 /// Deinitializes a plugin of type `P`.
@@ -156,24 +254,41 @@ pub(crate) unsafe fn hexchat_plugin_init<P: Plugin>(plugin_handle: *mut hexchat_
 pub(crate) unsafe fn hexchat_plugin_deinit<P: Plugin>(plugin_handle: *mut hexchat_plugin) -> c_int {
     let _ = plugin_handle;
     result_to_int(catch_and_log_unwind("deinit", || {
-        with_plugin_state(|plugin: &P, ph| plugin.deinit(ph));
-
         {
-            STATE
-                .compare_exchange(NO_READERS, LOCKED, Ordering::Relaxed, Ordering::Relaxed)
-                .unwrap_or_else(|e| panic!("Plugin deinitialized while running, state: {}", e));
-            defer! { STATE.store(NO_READERS, Ordering::Relaxed) };
+            DEINITIALIZING.store(true, Ordering::Relaxed);
+            defer! { DEINITIALIZING.store(false, Ordering::Relaxed) };
 
-            // Safety: STATE guarantees unique access to handles
-            unsafe {
-                *PLUGIN.get() = None;
-            }
+            with_plugin_state(|plugin: &P, ph| plugin.deinit(ph));
         }
 
-        LAST_RESORT_PLUGIN_HANDLE.store(ptr::null_mut(), Ordering::Relaxed);
+        clear_plugin_state();
     }))
 }
 
+/// Drops the plugin and resets all global state, after deinitialization or a failed initialization.
+///
+/// # Panics
+///
+/// If the plugin state is currently borrowed.
+fn clear_plugin_state() {
+    {
+        STATE
+            .compare_exchange(NO_READERS, LOCKED, Ordering::Relaxed, Ordering::Relaxed)
+            .unwrap_or_else(|e| panic!("Plugin deinitialized while running, state: {}", e));
+        defer! { STATE.store(NO_READERS, Ordering::Relaxed) };
+
+        // Safety: STATE guarantees unique access to handles
+        // Note: this also frees all hook data, since HexChat will not invoke any more hooks after we return
+        unsafe {
+            *PLUGIN.get() = None;
+        }
+    }
+
+    LAST_RESORT_PLUGIN_HANDLE.store(ptr::null_mut(), Ordering::Relaxed);
+    set_lossy_utf8(false);
+    DEINIT_PRINTS_TO_STDERR.store(false, Ordering::Relaxed);
+}
+
 /// Gets a safe reference to the current HexChat plugin handle and a plugin of type `P`.
 ///
 /// # Panics
@@ -251,3 +366,199 @@ pub(crate) fn with_plugin_state<P: 'static, R>(f: impl FnOnce(&P, PluginHandle<'
 
     f(plugin, ph)
 }
+
+/// Runs a closure with the global plugin state, from within a plugin callback.
+///
+/// # Panics
+///
+/// If not called from within `with_plugin_state`, e.g. from a method on `PluginHandle`.
+fn with_global_plugin<R>(f: impl FnOnce(&GlobalPlugin) -> R) -> R {
+    let state = STATE.load(Ordering::Relaxed);
+    assert!(
+        state != NO_READERS && state != LOCKED,
+        "hook data accessed outside of plugin callback, state: {}",
+        state
+    );
+
+    // Safety: STATE guarantees that there are only readers active, and one of them is on the stack above us
+    let global_plugin = unsafe { (*PLUGIN.get()).as_ref() }
+        .unwrap_or_else(|| panic!("Plugin invoked while uninitialized"));
+
+    f(global_plugin)
+}
+
+/// Runs a closure with the registry of data owned by hooks.
+///
+/// # Panics
+///
+/// If not called from within `with_plugin_state`, e.g. from a method on `PluginHandle`.
+fn with_hook_data<R>(f: impl FnOnce(&mut HashMap<usize, HookData>) -> R) -> R {
+    with_global_plugin(|global_plugin| {
+        let mut hook_data = global_plugin.hook_data.borrow_mut();
+        f(&mut hook_data)
+    })
+}
+
+/// Stores data owned by a hook, returning a pointer to be passed to HexChat as `user_data`.
+///
+/// The data is freed when passed to `release_hook_data`, or when the plugin is unloaded.
+///
+/// # Panics
+///
+/// If not called from within `with_plugin_state`, e.g. from a method on `PluginHandle`.
+pub(crate) fn register_hook_data<T: 'static>(data: T) -> *mut c_void {
+    let data = Rc::new(data);
+    let user_data = Rc::as_ptr(&data) as *mut c_void;
+    let data = HookData {
+        data,
+        hook: None,
+        panics: 0,
+    };
+    with_hook_data(|hook_data| hook_data.insert(user_data as usize, data));
+    user_data
+}
+
+/// Associates data registered with `register_hook_data` with the hook that owns it.
+///
+/// # Panics
+///
+/// If not called from within `with_plugin_state`, e.g. from a method on `PluginHandle`.
+pub(crate) fn set_hook_data_owner(user_data: *mut c_void, hook: NonNull<hexchat_hook>) {
+    with_hook_data(|hook_data| {
+        if let Some(data) = hook_data.get_mut(&(user_data as usize)) {
+            data.hook = Some(hook);
+        }
+    });
+}
+
+/// Sets the number of panics after which a hook which owns data is automatically unhooked.
+///
+/// # Panics
+///
+/// If not called from within `with_plugin_state`, e.g. from a method on `PluginHandle`.
+pub(crate) fn set_hook_panic_limit(limit: Option<NonZeroU32>) {
+    with_global_plugin(|global_plugin| global_plugin.hook_panic_limit.set(limit));
+}
+
+/// Records that the callback of the hook owning `user_data` panicked.
+///
+/// Returns the hook if it has now reached the panic limit, and should be unhooked.
+///
+/// # Panics
+///
+/// If not called from within `with_plugin_state`, e.g. from a method on `PluginHandle`.
+pub(crate) fn record_hook_panic(user_data: *mut c_void) -> Option<NonNull<hexchat_hook>> {
+    with_global_plugin(|global_plugin| {
+        let limit = global_plugin.hook_panic_limit.get();
+        let mut hook_data = global_plugin.hook_data.borrow_mut();
+        let data = hook_data.get_mut(&(user_data as usize))?;
+        data.panics = data.panics.saturating_add(1);
+        match limit {
+            Some(limit) if data.panics >= limit.get() => data.hook,
+            _ => None,
+        }
+    })
+}
+
+/// Gets a reference to data owned by a hook.
+///
+/// The returned `Rc` keeps the data alive even if the hook is unregistered while it is running.
+///
+/// # Safety
+///
+/// `user_data` must have been returned by `register_hook_data::<T>`, and not yet released.
+pub(crate) unsafe fn hook_data<T: 'static>(user_data: *mut c_void) -> Rc<T> {
+    let data = user_data as *const T;
+    // Safety: `data` was returned by `Rc::as_ptr` and the registry still holds a strong reference, due to precondition
+    unsafe {
+        Rc::increment_strong_count(data);
+        Rc::from_raw(data)
+    }
+}
+
+/// Frees data owned by a hook, if `user_data` was returned by `register_hook_data`.
+///
+/// # Panics
+///
+/// If not called from within `with_plugin_state`, e.g. from a method on `PluginHandle`.
+pub(crate) fn release_hook_data(user_data: *mut c_void) {
+    let data = with_hook_data(|hook_data| hook_data.remove(&(user_data as usize)));
+    if let Some(hook) = data.as_ref().and_then(|data| data.hook) {
+        forget_live_hook(hook);
+    }
+    let data = data.map(|hook_data| hook_data.data);
+    // drop outside of the registry borrow, in case the data's destructor interacts with the registry
+    drop(data);
+}
+
+/// Records that `hook` has been registered, returning an id which distinguishes it from any earlier hook
+/// that HexChat allocated at the same address.
+///
+/// # Panics
+///
+/// If not called from within `with_plugin_state`, e.g. from a method on `PluginHandle`.
+pub(crate) fn register_live_hook(hook: NonNull<hexchat_hook>) -> u64 {
+    with_global_plugin(|global_plugin| {
+        let id = global_plugin.next_hook_id.get();
+        global_plugin.next_hook_id.set(id + 1);
+        global_plugin
+            .live_hooks
+            .borrow_mut()
+            .insert(hook.as_ptr() as usize, id);
+        id
+    })
+}
+
+/// Whether the hook registered with `register_live_hook` as `id` is still registered.
+///
+/// # Panics
+///
+/// If not called from within `with_plugin_state`, e.g. from a method on `PluginHandle`.
+pub(crate) fn is_live_hook(hook: NonNull<hexchat_hook>, id: u64) -> bool {
+    with_global_plugin(|global_plugin| {
+        global_plugin
+            .live_hooks
+            .borrow()
+            .get(&(hook.as_ptr() as usize))
+            == Some(&id)
+    })
+}
+
+/// Records that `hook` has been unregistered, either by `hexchat_unhook` or by HexChat itself.
+///
+/// # Panics
+///
+/// If not called from within `with_plugin_state`, e.g. from a method on `PluginHandle`.
+pub(crate) fn forget_live_hook(hook: NonNull<hexchat_hook>) {
+    with_global_plugin(|global_plugin| {
+        global_plugin
+            .live_hooks
+            .borrow_mut()
+            .remove(&(hook.as_ptr() as usize));
+        #[cfg(debug_assertions)]
+        global_plugin
+            .command_hooks
+            .borrow_mut()
+            .remove(&(hook.as_ptr() as usize));
+    });
+}
+
+/// Records that `hook` was registered for the command `name`,
+/// returning whether another registered hook from this plugin was already hooked to the same command.
+///
+/// Command names are compared ignoring ASCII case, like HexChat does.
+///
+/// # Panics
+///
+/// If not called from within `with_plugin_state`, e.g. from a method on `PluginHandle`.
+#[cfg(debug_assertions)]
+pub(crate) fn register_command_hook(hook: NonNull<hexchat_hook>, name: &str) -> bool {
+    with_global_plugin(|global_plugin| {
+        let mut command_hooks = global_plugin.command_hooks.borrow_mut();
+        let duplicate = command_hooks
+            .values()
+            .any(|hooked| hooked.eq_ignore_ascii_case(name));
+        command_hooks.insert(hook.as_ptr() as usize, name.to_owned());
+        duplicate
+    })
+}
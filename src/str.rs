@@ -1,11 +1,13 @@
 //! Conversion to and from C strings.
 
 use std::borrow::Borrow;
+use std::error::Error;
 use std::ffi::{CStr, CString};
 use std::fmt::{self, Debug, Display};
 use std::mem;
 use std::ops::Deref;
 use std::str::Utf8Error;
+use std::sync::Arc;
 
 /// Converts various string types to C strings ([`CStr`]), which are required by HexChat.
 ///
@@ -74,6 +76,15 @@ pub(crate) mod private {
         type CSTR: Deref<Target = CStr>;
 
         fn into_cstr(self) -> Self::CSTR;
+
+        /// Converts to a C string, using `scratch` as the buffer if a copy is required.
+        fn with_cstr<R>(self, scratch: &mut Vec<u8>, f: impl FnOnce(&CStr) -> R) -> R
+        where
+            Self: Sized,
+        {
+            let _ = scratch;
+            f(&self.into_cstr())
+        }
     }
 
     /// Does the initial conversion from the tuple of `IntoCStr` types to a tuple of each type's `IntoCStr::CSTR` type.
@@ -101,12 +112,21 @@ impl IntoCStr for &HexStr {}
 
 impl IntoCStr for HexString {}
 
-impl<'a> private::IntoCStrImpl for &'a str {
+impl private::IntoCStrImpl for &str {
     type CSTR = CString;
 
     fn into_cstr(self) -> Self::CSTR {
         CString::new(self).unwrap()
     }
+
+    fn with_cstr<R>(self, scratch: &mut Vec<u8>, f: impl FnOnce(&CStr) -> R) -> R {
+        scratch.clear();
+        scratch.extend_from_slice(self.as_bytes());
+        scratch.push(0);
+        let cstr = CStr::from_bytes_with_nul(scratch)
+            .unwrap_or_else(|e| panic!("Invalid C string {:?}: {}", self, e));
+        f(cstr)
+    }
 }
 
 impl private::IntoCStrImpl for String {
@@ -333,6 +353,73 @@ impl HexStr {
     pub fn as_cstr(&self) -> &CStr {
         self.as_ref()
     }
+
+    /// Convert this `HexStr` to a byte slice, _including_ the trailing null byte.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::str::HexStr;
+    ///
+    /// let hex = HexStr::from_bytes_with_nul(b"hello\0").unwrap();
+    /// assert_eq!(hex.as_bytes_with_nul(), b"hello\0");
+    /// assert_eq!(hex.as_bytes(), b"hello");
+    /// ```
+    pub fn as_bytes_with_nul(&self) -> &[u8] {
+        self.inner.as_bytes()
+    }
+
+    /// Creates a `HexStr` from a byte slice which ends with a null byte.
+    ///
+    /// Fails if `bytes` is not null-terminated, contains an interior null byte, or is not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hexavalent::str::HexStr;
+    ///
+    /// assert_eq!(HexStr::from_bytes_with_nul(b"hello\0").unwrap().as_str(), "hello");
+    /// assert!(HexStr::from_bytes_with_nul(b"hello").is_err());
+    /// assert!(HexStr::from_bytes_with_nul(b"he\0llo\0").is_err());
+    /// assert!(HexStr::from_bytes_with_nul(b"\xff\0").is_err());
+    /// ```
+    pub fn from_bytes_with_nul(bytes: &[u8]) -> Result<&HexStr, FromBytesWithNulError> {
+        let cstr =
+            CStr::from_bytes_with_nul(bytes).map_err(|_| FromBytesWithNulError::InvalidNul)?;
+        HexStr::from_cstr(cstr).map_err(FromBytesWithNulError::InvalidUtf8)
+    }
+}
+
+/// An error from converting a byte slice to a [`HexStr`].
+///
+/// Returned from [`HexStr::from_bytes_with_nul`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FromBytesWithNulError {
+    /// The bytes were not null-terminated, or contained an interior null byte.
+    InvalidNul,
+    /// The bytes were not valid UTF-8.
+    InvalidUtf8(Utf8Error),
+}
+
+impl Display for FromBytesWithNulError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidNul => {
+                f.write_str("data is not null-terminated or contains an interior null byte")
+            }
+            Self::InvalidUtf8(e) => write!(f, "data is not valid UTF-8: {}", e),
+        }
+    }
+}
+
+impl Error for FromBytesWithNulError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidNul => None,
+            Self::InvalidUtf8(e) => Some(e),
+        }
+    }
 }
 
 impl Debug for HexStr {
@@ -381,6 +468,15 @@ impl ToOwned for HexStr {
     }
 }
 
+impl From<&HexStr> for Arc<HexStr> {
+    fn from(hex: &HexStr) -> Self {
+        let arc: Arc<str> = Arc::from(&hex.inner);
+        // SAFETY: `HexStr` is a repr(transparent) wrapper over `str`, so the pointer cast is safe
+        // SAFETY: the string was copied from a `HexStr`, so this upholds the type's invariants
+        unsafe { Arc::from_raw(Arc::into_raw(arc) as *const HexStr) }
+    }
+}
+
 /// An owned string returned from HexChat.
 ///
 /// This is the owned version of [`HexStr`] and behaves in the same way, acting like a [`String`]
@@ -393,6 +489,8 @@ impl ToOwned for HexStr {
 /// Unlike [`String`], `HexString` is not mutable, as this could allow the null byte to be removed.
 /// If you need mutability, call [`into_string`](HexString::into_string) to extract the underlying string
 /// (with the trailing null byte removed).
+///
+/// With the `serde` feature, `HexString` serializes and deserializes as a string, without the null byte.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct HexString {
     /// Invariant 1: Always null-terminated.
@@ -455,12 +553,210 @@ impl Borrow<HexStr> for HexString {
     }
 }
 
+/// Builds a [`HexString`] incrementally.
+///
+/// Since [`HexString`] is not mutable, assembling one from many pieces otherwise requires building a [`String`]
+/// and converting it at the end. `HexStringBuilder` does the same, but the null byte is only added once, in [`build`](HexStringBuilder::build).
+///
+/// Pieces that contain an interior null byte will panic.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::fmt::Write;
+/// use hexavalent::PluginHandle;
+/// use hexavalent::list::Users;
+/// use hexavalent::str::HexStringBuilder;
+///
+/// fn print_nicks<P>(ph: PluginHandle<'_, P>) {
+///     let mut builder = HexStringBuilder::new();
+///     builder.push_str("Users:");
+///     for user in ph.get_list(Users).into_iter().flatten() {
+///         write!(builder, " {}", user.nick()).unwrap();
+///     }
+///     ph.print(builder.build());
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HexStringBuilder {
+    /// Invariant: contains no null bytes.
+    inner: String,
+}
+
+impl HexStringBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty builder with space for at least `capacity` bytes, not including the null byte.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: String::with_capacity(capacity + 1),
+        }
+    }
+
+    /// Appends a string slice.
+    ///
+    /// # Panics
+    ///
+    /// If `str` contains a null byte.
+    pub fn push_str(&mut self, str: &str) -> &mut Self {
+        assert!(
+            !str.contains('\0'),
+            "HexStringBuilder::push_str called with string containing null byte: {:?}",
+            str
+        );
+        self.inner.push_str(str);
+        self
+    }
+
+    /// Appends a character.
+    ///
+    /// # Panics
+    ///
+    /// If `ch` is a null byte.
+    pub fn push(&mut self, ch: char) -> &mut Self {
+        assert!(ch != '\0', "HexStringBuilder::push called with null byte");
+        self.inner.push(ch);
+        self
+    }
+
+    /// The length of the string built so far in bytes, not including the null byte.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Whether nothing has been appended.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Finishes building, adding the null byte.
+    pub fn build(self) -> HexString {
+        let mut inner = self.inner;
+        inner.push('\0');
+        // SAFETY: the builder's invariant ensures there are no interior null bytes, and we just added the trailing null
+        unsafe { HexString::from_null_terminated_string(inner) }
+    }
+}
+
+impl fmt::Write for HexStringBuilder {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+/// Formats `args` as a C string and passes it to `f`.
+///
+/// Short strings are formatted into a buffer on the stack, so they do not allocate.
+///
+/// # Panics
+///
+/// If the formatted string contains an interior null byte.
+pub(crate) fn with_formatted_cstr<R>(args: fmt::Arguments<'_>, f: impl FnOnce(&CStr) -> R) -> R {
+    struct StackBuf {
+        buf: [u8; 256],
+        len: usize,
+    }
+
+    impl fmt::Write for StackBuf {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            // leave room for the null terminator
+            let end = self.len + s.len();
+            if end >= self.buf.len() {
+                return Err(fmt::Error);
+            }
+            self.buf[self.len..end].copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    let mut stack = StackBuf {
+        buf: [0; 256],
+        len: 0,
+    };
+
+    if fmt::write(&mut stack, args).is_ok() {
+        let cstr = CStr::from_bytes_with_nul(&stack.buf[..=stack.len])
+            .unwrap_or_else(|e| panic!("Invalid C string {:?}: {}", args, e));
+        f(cstr)
+    } else {
+        let cstring = CString::new(fmt::format(args)).unwrap();
+        f(&cstring)
+    }
+}
+
+/// `HexStr` and `HexString` serialize as strings, without the null byte.
+///
+/// `HexString` deserializes from a string, adding the null byte; strings containing a null byte are rejected.
+///
+/// Requires the `serde` feature.
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use std::fmt;
+
+    use serde::de::{self, Deserialize, Deserializer, Unexpected, Visitor};
+    use serde::ser::{Serialize, Serializer};
+
+    use super::{HexStr, HexString, HexStringBuilder};
+
+    impl Serialize for HexStr {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.as_str())
+        }
+    }
+
+    impl Serialize for HexString {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.as_str())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for HexString {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct HexStringVisitor;
+
+            impl Visitor<'_> for HexStringVisitor {
+                type Value = HexString;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str("a string without null bytes")
+                }
+
+                fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                    if v.contains('\0') {
+                        return Err(E::invalid_value(Unexpected::Str(v), &self));
+                    }
+                    let mut builder = HexStringBuilder::with_capacity(v.len());
+                    builder.push_str(v);
+                    Ok(builder.build())
+                }
+
+                fn visit_string<E: de::Error>(self, mut v: String) -> Result<Self::Value, E> {
+                    if v.contains('\0') {
+                        return Err(E::invalid_value(Unexpected::Str(&v), &self));
+                    }
+                    v.push('\0');
+                    // Safety: we just checked that there are no interior null bytes, and added the trailing null byte
+                    Ok(unsafe { HexString::from_null_terminated_string(v) })
+                }
+            }
+
+            deserializer.deserialize_string(HexStringVisitor)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::private::*;
     use super::*;
 
     #[test]
+    #[allow(clippy::useless_asref)]
     fn intocstr_str() {
         let owner = "hello".into_cstr();
         assert_eq!(owner.as_ref(), c"hello");
@@ -475,6 +771,23 @@ mod tests {
         assert_eq!(owner.as_ref(), c"hello");
     }
 
+    #[test]
+    fn intocstr_with_scratch() {
+        let mut scratch = Vec::new();
+        "hello".with_cstr(&mut scratch, |cstr| assert_eq!(cstr, c"hello"));
+        "hi".with_cstr(&mut scratch, |cstr| assert_eq!(cstr, c"hi"));
+        assert_eq!(scratch, b"hi\0");
+
+        c"world".with_cstr(&mut scratch, |cstr| assert_eq!(cstr, c"world"));
+        String::from("owned").with_cstr(&mut scratch, |cstr| assert_eq!(cstr, c"owned"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn intocstr_with_scratch_invalid() {
+        "hel\0lo".with_cstr(&mut Vec::new(), |_| ());
+    }
+
     #[test]
     #[should_panic]
     fn intocstr_str_invalid_no_null() {
@@ -559,4 +872,52 @@ mod tests {
         let hex: HexString = HexStr::from_cstr(c"hello").unwrap().to_owned();
         assert_eq!(hex.as_str(), "hello");
     }
+
+    #[test]
+    fn builder() {
+        let mut builder = HexStringBuilder::with_capacity(8);
+        builder.push_str("hello").push(' ').push_str("world");
+        assert_eq!(builder.len(), 11);
+        let built = builder.build();
+        assert_eq!(built.as_str(), "hello world");
+        assert_eq!(built.as_cstr(), c"hello world");
+
+        assert_eq!(HexStringBuilder::new().build().as_cstr(), c"");
+    }
+
+    #[test]
+    #[should_panic]
+    fn builder_null() {
+        HexStringBuilder::new().push_str("a\0b");
+    }
+
+    #[test]
+    fn formatted_cstr() {
+        with_formatted_cstr(format_args!("JOIN #{}", "rust"), |cstr| {
+            assert_eq!(cstr, c"JOIN #rust");
+        });
+
+        let long = "x".repeat(300);
+        with_formatted_cstr(format_args!("SAY {}", long), |cstr| {
+            assert_eq!(cstr.to_bytes().len(), 304);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn formatted_cstr_interior_null() {
+        with_formatted_cstr(format_args!("SAY {}", "a\0b"), |_| {});
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        let string = HexStr::from_cstr(c"#rust").unwrap().to_owned();
+        let json = serde_json::to_string(&string).unwrap();
+        assert_eq!(json, "\"#rust\"");
+
+        let string: HexString = serde_json::from_str(&json).unwrap();
+        assert_eq!(string.as_bytes_with_nul(), b"#rust\0");
+        assert!(serde_json::from_str::<HexString>(r#""a\u0000b""#).is_err());
+    }
 }
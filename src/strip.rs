@@ -1,38 +1,75 @@
 //! String format stripping.
 
+use std::borrow::Cow;
 use std::ffi::CStr;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::ops::Deref;
+use std::os::raw::c_char;
 use std::ptr::NonNull;
-use std::str::Utf8Error;
 
-use crate::ffi::RawPluginHandle;
+use crate::ffi::{LossyArena, RawPluginHandle};
 use crate::str::HexStr;
 
 /// Whether to strip mIRC color attributes.
 ///
 /// Used with [`PluginHandle::strip`](crate::PluginHandle::strip).
+///
+/// Defaults to [`MircColors::Remove`].
 #[non_exhaustive]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Default)]
 pub enum MircColors {
     /// Preserve mIRC colors.
     Keep,
     /// Strip mIRC colors.
+    #[default]
     Remove,
 }
 
 /// Whether to strip text attributes (bold, underline, etc.).
 ///
 /// Used with [`PluginHandle::strip`](crate::PluginHandle::strip).
+///
+/// Defaults to [`TextAttrs::Remove`].
 #[non_exhaustive]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Default)]
 pub enum TextAttrs {
     /// Preserve text attributes.
     Keep,
     /// Strip text attributes.
+    #[default]
     Remove,
 }
 
+/// Which kinds of formatting to strip, combining [`MircColors`] and [`TextAttrs`].
+///
+/// Used with [`PluginHandle::strip_mode`](crate::PluginHandle::strip_mode).
+///
+/// Defaults to [`StripMode::All`].
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, Default)]
+pub enum StripMode {
+    /// Strip mIRC colors and text attributes.
+    #[default]
+    All,
+    /// Strip mIRC colors, preserving text attributes.
+    ColorsOnly,
+    /// Strip text attributes, preserving mIRC colors.
+    AttrsOnly,
+    /// Preserve everything.
+    None,
+}
+
+impl StripMode {
+    pub(crate) fn into_parts(self) -> (MircColors, TextAttrs) {
+        match self {
+            Self::All => (MircColors::Remove, TextAttrs::Remove),
+            Self::ColorsOnly => (MircColors::Remove, TextAttrs::Keep),
+            Self::AttrsOnly => (MircColors::Keep, TextAttrs::Remove),
+            Self::None => (MircColors::Keep, TextAttrs::Keep),
+        }
+    }
+}
+
 /// A stripped string.
 ///
 /// Derefs to [`&HexStr`](crate::str::HexStr).
@@ -56,8 +93,11 @@ pub enum TextAttrs {
 /// ```
 pub struct StrippedStr<'a> {
     raw: RawPluginHandle<'a>,
-    /// Always points to a valid `HexStr`.
+    /// The string returned by `hexchat_strip`, which must be freed.
+    raw_ptr: NonNull<c_char>,
+    /// Always points to a valid `HexStr`, either `raw_ptr` itself or a lossily-decoded copy in `_arena`.
     stripped_ptr: NonNull<HexStr>,
+    _arena: LossyArena,
 }
 
 impl<'a> StrippedStr<'a> {
@@ -68,24 +108,30 @@ impl<'a> StrippedStr<'a> {
     /// `stripped_ptr` must point to a string returned by `hexchat_strip` which is valid for the entire lifetime `'a``.
     ///
     /// This function takes ownership of `stripped_ptr`; the underlying object must not be used afterwards.
-    pub(crate) unsafe fn new(
-        raw: RawPluginHandle<'a>,
-        stripped_ptr: &CStr,
-    ) -> Result<Self, Utf8Error> {
-        let stripped_ptr = HexStr::from_cstr(stripped_ptr)?;
-        Ok(Self {
+    ///
+    /// # Panics
+    ///
+    /// If the string is not valid UTF-8 and lossy decoding is not enabled.
+    pub(crate) unsafe fn new(raw: RawPluginHandle<'a>, stripped_ptr: NonNull<c_char>) -> Self {
+        let arena = LossyArena::default();
+        // Safety: `stripped_ptr` points to a valid null-terminated string, per precondition
+        let cstr = unsafe { CStr::from_ptr(stripped_ptr.as_ptr()) };
+        let stripped = NonNull::from(arena.hexstr(cstr, "hexchat_strip"));
+        Self {
             raw,
-            stripped_ptr: NonNull::from(stripped_ptr),
-        })
+            raw_ptr: stripped_ptr,
+            stripped_ptr: stripped,
+            _arena: arena,
+        }
     }
 }
 
 impl Drop for StrippedStr<'_> {
     fn drop(&mut self) {
-        // Safety: stripped_ptr was returned from hexchat_strip;
+        // Safety: raw_ptr was returned from hexchat_strip;
         //         we have conceptual ownership of stripped_str due to StrippedStr precondition
         unsafe {
-            self.raw.hexchat_free(self.stripped_ptr.as_ptr().cast());
+            self.raw.hexchat_free(self.raw_ptr.as_ptr().cast());
         }
     }
 }
@@ -94,7 +140,7 @@ impl Deref for StrippedStr<'_> {
     type Target = HexStr;
 
     fn deref(&self) -> &Self::Target {
-        // SAFETY: pointer is always valid.
+        // SAFETY: pointer is always valid; strings in `_arena` are not freed until `self` is dropped.
         unsafe { self.stripped_ptr.as_ref() }
     }
 }
@@ -110,3 +156,79 @@ impl Debug for StrippedStr<'_> {
         Debug::fmt(self.deref(), f)
     }
 }
+
+/// Whether `b` starts an mIRC color code.
+fn is_color(b: u8) -> bool {
+    matches!(b, b'\x03' | b'\x04')
+}
+
+/// Whether `b` is a text attribute (bold, underline, etc.) or other control character removed by stripping.
+fn is_attr(b: u8) -> bool {
+    matches!(
+        b,
+        b'\x02' | b'\x07' | b'\x08' | b'\x0f' | b'\x11' | b'\x16' | b'\x1d' | b'\x1e' | b'\x1f'
+    )
+}
+
+/// Checks whether `str` contains any formatting codes which `hexchat_strip` might remove.
+///
+/// This may return `true` for strings that are unchanged by stripping, but never returns `false` for strings that would be changed.
+pub(crate) fn may_need_stripping(str: &str, mirc: MircColors, attrs: TextAttrs) -> bool {
+    // https://github.com/hexchat/hexchat/blob/57478b65758e6b697b1d82ce21075e74aa475efc/src/common/text.c (strip_color2)
+    str.bytes().any(|b| {
+        (matches!(mirc, MircColors::Remove) && is_color(b))
+            || (matches!(attrs, TextAttrs::Remove) && is_attr(b))
+    })
+}
+
+/// Removes formatting control characters from `text`, so that it is displayed literally.
+///
+/// Unlike [`PluginHandle::strip`](crate::PluginHandle::strip), this does not call into HexChat,
+/// and only the control characters themselves are removed, not the parameters of color codes.
+/// For example, `"\x0304red"` becomes `"04red"`, so no part of untrusted text can change how the rest is displayed.
+///
+/// Used by [`PluginHandle::print_plain`](crate::PluginHandle::print_plain).
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::strip::remove_control_chars;
+///
+/// assert_eq!(remove_control_chars("\x02bold\x02 and \x0304red"), "bold and 04red");
+/// assert_eq!(remove_control_chars("100% plain"), "100% plain");
+/// ```
+pub fn remove_control_chars(text: &str) -> Cow<'_, str> {
+    let is_control = |c: char| u8::try_from(c).is_ok_and(|b| is_color(b) || is_attr(b));
+    if text.contains(is_control) {
+        Cow::Owned(text.replace(is_control, ""))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn needs_stripping() {
+        use MircColors as M;
+        use TextAttrs as T;
+
+        assert!(!may_need_stripping("plain text", M::Remove, T::Remove));
+        assert!(may_need_stripping("\x0304red", M::Remove, T::Keep));
+        assert!(!may_need_stripping("\x0304red", M::Keep, T::Remove));
+        assert!(may_need_stripping("\x02bold", M::Keep, T::Remove));
+        assert!(!may_need_stripping("\x02bold", M::Remove, T::Keep));
+        assert!(!may_need_stripping("\x02\x03", M::Keep, T::Keep));
+    }
+
+    #[test]
+    fn control_chars() {
+        assert!(matches!(
+            remove_control_chars("plain"),
+            Cow::Borrowed("plain")
+        ));
+        assert_eq!(remove_control_chars("\x1fa\x04FF0000b\x0f\x07"), "aFF0000b");
+    }
+}
@@ -0,0 +1,1499 @@
+//! Testing plugins without loading them into HexChat.
+//!
+//! Requires the `testing` feature.
+//!
+//! [`TestHarness`] initializes a plugin against a mock HexChat, which records everything the plugin outputs,
+//! and allows firing synthetic commands, print events, server lines, and timers at the plugin's hooks.
+//!
+//! The mock is not a full implementation of HexChat. Notably:
+//! - There is a single context, and no lists, so [`PluginHandle::get_list`](crate::PluginHandle::get_list) always fails.
+//! - [Preferences](crate::PluginHandle::get_pref) are not available, but [plugin preferences](crate::PluginHandle::pluginpref_set_str) are stored in memory.
+//! - Commands and print events emitted by the plugin are recorded, but not executed or passed to hooks.
+//! - [Stripping](crate::PluginHandle::strip) returns strings unchanged.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use hexavalent::{Plugin, PluginHandle};
+//! use hexavalent::hook::{Eat, Priority};
+//! use hexavalent::testing::{Output, TestHarness};
+//!
+//! #[derive(Default)]
+//! struct GreetPlugin;
+//!
+//! impl Plugin for GreetPlugin {
+//!     fn init(&self, ph: PluginHandle<'_, Self>) {
+//!         ph.hook_command(c"greet", c"Usage: GREET <name>", Priority::Normal, |_, ph, words| {
+//!             ph.print(format!("Hello {}!", words[1]));
+//!             Eat::All
+//!         });
+//!     }
+//! }
+//!
+//! let harness = TestHarness::<GreetPlugin>::new();
+//! assert_eq!(harness.command("greet world"), Eat::All);
+//! assert_eq!(harness.take_output(), [Output::Print("Hello world!".to_owned())]);
+//! ```
+
+use std::cell::RefCell;
+use std::cmp::{Ordering as CmpOrdering, Reverse};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr::{self, NonNull};
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use time::OffsetDateTime;
+
+use crate::event::print::HookablePrintEvent;
+use crate::ffi::{
+    hexchat_context, hexchat_event_attrs, hexchat_hook, hexchat_list, hexchat_plugin,
+};
+use crate::hook::Eat;
+use crate::plugin::{Plugin, PluginHandle};
+use crate::rfc1459;
+use crate::state::{hexchat_plugin_deinit, hexchat_plugin_init, with_plugin_state};
+
+/// Something the plugin sent to the mock HexChat.
+///
+/// Returned from [`TestHarness::take_output`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Output {
+    /// Text printed with [`PluginHandle::print`](crate::PluginHandle::print).
+    ///
+    /// Panics caught in the plugin's callbacks are also logged this way.
+    Print(String),
+    /// A command executed with [`PluginHandle::command`](crate::PluginHandle::command), without the leading `/`.
+    Command(String),
+    /// A print event emitted with [`PluginHandle::emit_print`](crate::PluginHandle::emit_print)
+    /// or [`PluginHandle::emit_print_attrs`](crate::PluginHandle::emit_print_attrs).
+    EmitPrint {
+        /// The name of the event, e.g. `"Channel Message"`.
+        event: String,
+        /// The event's arguments.
+        args: Vec<String>,
+    },
+}
+
+/// Number of entries in HexChat's `word` and `word_eol` arrays, including the reserved first entry.
+const PDIWORDS: usize = 32;
+
+type CommandCallback =
+    unsafe extern "C" fn(*mut *mut c_char, *mut *mut c_char, *mut c_void) -> c_int;
+type PrintCallback = unsafe extern "C" fn(*mut *mut c_char, *mut c_void) -> c_int;
+type PrintAttrsCallback =
+    unsafe extern "C" fn(*mut *mut c_char, *mut hexchat_event_attrs, *mut c_void) -> c_int;
+type ServerAttrsCallback = unsafe extern "C" fn(
+    *mut *mut c_char,
+    *mut *mut c_char,
+    *mut hexchat_event_attrs,
+    *mut c_void,
+) -> c_int;
+type TimerCallback = unsafe extern "C" fn(*mut c_void) -> c_int;
+
+#[derive(Copy, Clone)]
+enum Callback {
+    Command(CommandCallback),
+    Server(CommandCallback),
+    ServerAttrs(ServerAttrsCallback),
+    Print(PrintCallback),
+    PrintAttrs(PrintAttrsCallback),
+    Timer(TimerCallback),
+}
+
+struct MockHook {
+    /// Points to a leaked `Box<u8>`, to give each hook a unique address.
+    hook: NonNull<hexchat_hook>,
+    name: String,
+    priority: c_int,
+    callback: Callback,
+    user_data: *mut c_void,
+}
+
+#[derive(Default)]
+struct MockState {
+    hooks: Vec<MockHook>,
+    output: Vec<Output>,
+    info: HashMap<String, CString>,
+    int_prefs: HashMap<String, c_int>,
+    pluginprefs: BTreeMap<String, CString>,
+}
+
+/// A `hexchat_plugin` whose functions record calls into `state`.
+#[repr(C)]
+struct MockPlugin {
+    /// Must be the first field, so that the `hexchat_plugin` pointer can be cast back to `MockPlugin`.
+    vtable: hexchat_plugin,
+    /// The address of this field is used as the only context.
+    context: u8,
+    state: RefCell<MockState>,
+}
+
+/// Prevents multiple harnesses from running at once, since plugin state is global.
+static HARNESS_LOCK: Mutex<()> = Mutex::new(());
+
+/// The mock used by the current harness, if any.
+static ACTIVE_MOCK: AtomicPtr<hexchat_plugin> = AtomicPtr::new(ptr::null_mut());
+
+/// Runs a plugin of type `P` against a mock HexChat.
+///
+/// Creating a harness initializes the plugin, and dropping it deinitializes the plugin.
+/// Since plugin state is global, only one harness can exist at a time;
+/// creating another blocks until the first is dropped, so tests using harnesses run one at a time.
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::{Plugin, PluginHandle};
+/// use hexavalent::event::print::ChannelMessage;
+/// use hexavalent::hook::{Eat, Priority};
+/// use hexavalent::testing::{Output, TestHarness};
+///
+/// #[derive(Default)]
+/// struct EchoPlugin;
+///
+/// impl Plugin for EchoPlugin {
+///     fn init(&self, ph: PluginHandle<'_, Self>) {
+///         ph.hook_print(ChannelMessage, Priority::Normal, |_, ph, [nick, text, ..]| {
+///             if text.starts_with("!echo ") {
+///                 ph.command(format!("SAY {}: {}", nick, &text["!echo ".len()..]));
+///             }
+///             Eat::None
+///         });
+///     }
+/// }
+///
+/// let harness = TestHarness::<EchoPlugin>::new();
+/// harness.print_event(ChannelMessage, ["alice", "!echo hi", "", ""]);
+/// assert_eq!(harness.take_output(), [Output::Command("SAY alice: hi".to_owned())]);
+/// ```
+pub struct TestHarness<P: Plugin> {
+    /// Points to a leaked `Box<MockPlugin>`, freed on drop.
+    mock: NonNull<MockPlugin>,
+    /// Whether `hexchat_plugin_init` succeeded, so `hexchat_plugin_deinit` should be called on drop.
+    initialized: bool,
+    _lock: MutexGuard<'static, ()>,
+    _plugin: PhantomData<*const P>,
+}
+
+impl<P: Plugin> TestHarness<P> {
+    /// Initializes a plugin of type `P` against a new mock HexChat.
+    ///
+    /// # Panics
+    ///
+    /// If the plugin's [`init`](crate::Plugin::init) panics.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        let lock = HARNESS_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mock = Box::new(MockPlugin {
+            vtable: mock_vtable(),
+            context: 0,
+            state: Default::default(),
+        });
+        let mock = NonNull::from(Box::leak(mock));
+        let plugin_handle = mock.as_ptr().cast::<hexchat_plugin>();
+        ACTIVE_MOCK.store(plugin_handle, Ordering::Relaxed);
+
+        let mut harness = Self {
+            mock,
+            initialized: false,
+            _lock: lock,
+            _plugin: PhantomData,
+        };
+
+        // Safety: `plugin_handle` points to a valid `hexchat_plugin`, which lives as long as the harness
+        let initialized = unsafe { hexchat_plugin_init::<P>(plugin_handle) };
+        if initialized == 0 {
+            let output = harness.take_output();
+            // a failed init cleans up after itself, so dropping the harness only frees the mock
+            drop(harness);
+            panic!("Plugin failed to initialize, output: {:?}", output);
+        }
+        harness.initialized = true;
+
+        harness
+    }
+
+    /// Runs a closure with the plugin and a handle to the mock HexChat,
+    /// e.g. to inspect the plugin's state or call [`PluginHandle`] methods directly.
+    pub fn with_plugin<R>(&self, f: impl FnOnce(&P, PluginHandle<'_, P>) -> R) -> R {
+        with_plugin_state(f)
+    }
+
+    /// Removes and returns everything the plugin has output so far.
+    pub fn take_output(&self) -> Vec<Output> {
+        mem::take(&mut self.mock().state.borrow_mut().output)
+    }
+
+    /// Sets the value returned from [`PluginHandle::get_info`](crate::PluginHandle::get_info) for `id`, e.g. `"channel"`.
+    ///
+    /// # Panics
+    ///
+    /// If `value` contains a null byte.
+    pub fn set_info(&self, id: &str, value: Option<&str>) {
+        let mut state = self.mock().state.borrow_mut();
+        match value {
+            Some(value) => {
+                let value = CString::new(value).expect("info value contains a null byte");
+                state.info.insert(id.to_owned(), value);
+            }
+            None => {
+                state.info.remove(id);
+            }
+        }
+    }
+
+    /// Sets the value returned from [`PluginHandle::get_pref`](crate::PluginHandle::get_pref) for an integer preference `name`,
+    /// e.g. `"id"` for [`PluginHandle::current_server_id`](crate::PluginHandle::current_server_id).
+    pub fn set_int_pref(&self, name: &str, value: Option<i32>) {
+        let mut state = self.mock().state.borrow_mut();
+        match value {
+            Some(value) => {
+                state.int_prefs.insert(name.to_owned(), value);
+            }
+            None => {
+                state.int_prefs.remove(name);
+            }
+        }
+    }
+
+    /// Executes a command as if typed by the user, without the leading `/`, e.g. `"greet world"`.
+    ///
+    /// Calls the plugin's [command hooks](crate::PluginHandle::hook_command) for the command in priority order,
+    /// returning how the event was eaten.
+    ///
+    /// # Panics
+    ///
+    /// If `command` contains a null byte.
+    pub fn command(&self, command: &str) -> Eat {
+        let words = Words::split(command);
+        let name = words.word_str(1).to_owned();
+        self.dispatch(
+            |hook| {
+                matches!(hook.callback, Callback::Command(_))
+                    && hook.name.eq_ignore_ascii_case(&name)
+            },
+            |callback, user_data| match callback {
+                // Safety: the callback was registered with this hook's user data; the word arrays live until it returns
+                Callback::Command(callback) => unsafe {
+                    callback(words.word_ptr(), words.word_eol_ptr(), user_data)
+                },
+                _ => unreachable!(),
+            },
+        )
+    }
+
+    /// Emits a print event, as if printed by HexChat.
+    ///
+    /// Calls the plugin's [print hooks](crate::PluginHandle::hook_print) for the event in priority order,
+    /// returning how the event was eaten. Hooks registered with attributes receive the current time.
+    ///
+    /// # Panics
+    ///
+    /// If any of `args` contains a null byte.
+    pub fn print_event<E: HookablePrintEvent<N>, const N: usize>(
+        &self,
+        event: E,
+        args: [&str; N],
+    ) -> Eat {
+        let _ = event;
+        let name = E::NAME.to_string_lossy();
+        let words = Words::new(&args, &[]);
+        let ircv3_line = CString::default();
+        let mut attrs = event_attrs(&ircv3_line);
+        self.dispatch(
+            |hook| {
+                matches!(hook.callback, Callback::Print(_) | Callback::PrintAttrs(_))
+                    && hook.name == name
+            },
+            |callback, user_data| match callback {
+                // Safety: the callback was registered with this hook's user data; the word array lives until it returns
+                Callback::Print(callback) => unsafe { callback(words.word_ptr(), user_data) },
+                // Safety: as above; `attrs` is fully initialized
+                Callback::PrintAttrs(callback) => unsafe {
+                    callback(words.word_ptr(), &mut attrs, user_data)
+                },
+                _ => unreachable!(),
+            },
+        )
+    }
+
+    /// Receives a raw line from the server, e.g. `":alice!a@host PRIVMSG #rust :hello"`.
+    ///
+    /// Calls the plugin's [`RAW LINE`](crate::event::server::special::RawLine) hooks,
+    /// then its [server hooks](crate::PluginHandle::hook_server) for the line's command (or numeric),
+    /// returning how the line was eaten. Hooks registered with attributes receive the current time.
+    ///
+    /// # Panics
+    ///
+    /// If `line` contains a null byte.
+    pub fn server_line(&self, line: &str) -> Eat {
+        let words = Words::split(line);
+        let command = if words.word_str(1).starts_with(':') {
+            words.word_str(2)
+        } else {
+            words.word_str(1)
+        }
+        .to_owned();
+        let ircv3_line = CString::new(line).expect("line contains a null byte");
+        let mut attrs = event_attrs(&ircv3_line);
+
+        let mut eat = Eat::None;
+        for name in ["RAW LINE", &command] {
+            eat = eat.or(self.dispatch(
+                |hook| {
+                    matches!(
+                        hook.callback,
+                        Callback::Server(_) | Callback::ServerAttrs(_)
+                    ) && hook.name.eq_ignore_ascii_case(name)
+                },
+                |callback, user_data| match callback {
+                    // Safety: the callback was registered with this hook's user data; the word arrays live until it returns
+                    Callback::Server(callback) => unsafe {
+                        callback(words.word_ptr(), words.word_eol_ptr(), user_data)
+                    },
+                    // Safety: as above; `attrs` is fully initialized
+                    Callback::ServerAttrs(callback) => unsafe {
+                        callback(
+                            words.word_ptr(),
+                            words.word_eol_ptr(),
+                            &mut attrs,
+                            user_data,
+                        )
+                    },
+                    _ => unreachable!(),
+                },
+            ));
+            if eat == Eat::Plugin || eat == Eat::All {
+                break;
+            }
+        }
+        eat
+    }
+
+    /// Runs every registered [timer](crate::PluginHandle::hook_timer) once, regardless of its timeout.
+    ///
+    /// Timers which return [`Timer::Stop`](crate::hook::Timer::Stop) are unhooked, as in HexChat.
+    pub fn run_timers(&self) {
+        let timers: Vec<_> = self
+            .mock()
+            .state
+            .borrow()
+            .hooks
+            .iter()
+            .filter(|hook| matches!(hook.callback, Callback::Timer(_)))
+            .map(|hook| hook.hook)
+            .collect();
+
+        for hook in timers {
+            let Some((callback, user_data)) = self.mock().find_hook(hook) else {
+                continue;
+            };
+            let Callback::Timer(callback) = callback else {
+                unreachable!()
+            };
+            // Safety: the callback was registered with this hook's user data
+            let keep_running = unsafe { callback(user_data) };
+            if keep_running == 0 {
+                self.mock().remove_hook(hook);
+            }
+        }
+    }
+
+    fn mock(&self) -> &MockPlugin {
+        // Safety: `mock` is valid until the harness is dropped
+        unsafe { self.mock.as_ref() }
+    }
+
+    /// Calls each hook matching `filter` in priority order, until one eats the event from other plugins.
+    fn dispatch(
+        &self,
+        filter: impl Fn(&MockHook) -> bool,
+        mut call: impl FnMut(Callback, *mut c_void) -> c_int,
+    ) -> Eat {
+        let mut hooks: Vec<_> = self
+            .mock()
+            .state
+            .borrow()
+            .hooks
+            .iter()
+            .filter(|hook| filter(hook))
+            .map(|hook| (hook.priority, hook.hook))
+            .collect();
+        // stable, so hooks with the same priority run in the order they were registered
+        hooks.sort_by_key(|&(priority, _)| Reverse(priority));
+
+        let mut eat = Eat::None;
+        for (_, hook) in hooks {
+            // the hook may have been unhooked by an earlier callback
+            let Some((callback, user_data)) = self.mock().find_hook(hook) else {
+                continue;
+            };
+            eat = eat.or(eat_from_int(call(callback, user_data)));
+            if eat == Eat::Plugin || eat == Eat::All {
+                break;
+            }
+        }
+        eat
+    }
+}
+
+impl<P: Plugin> Drop for TestHarness<P> {
+    fn drop(&mut self) {
+        let plugin_handle = self.mock.as_ptr().cast::<hexchat_plugin>();
+
+        if self.initialized {
+            // Safety: `plugin_handle` points to a valid `hexchat_plugin`
+            unsafe { hexchat_plugin_deinit::<P>(plugin_handle) };
+        }
+
+        ACTIVE_MOCK.store(ptr::null_mut(), Ordering::Relaxed);
+
+        // Safety: `mock` was leaked from a `Box` in `new`, and the plugin no longer has access to it
+        let mock = unsafe { Box::from_raw(self.mock.as_ptr()) };
+        for hook in mock.state.into_inner().hooks {
+            // Safety: each hook was leaked from a `Box<u8>` in `register_hook`
+            drop(unsafe { Box::from_raw(hook.hook.as_ptr().cast::<u8>()) });
+        }
+    }
+}
+
+impl<P: Plugin> fmt::Debug for TestHarness<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.mock().state.borrow();
+        f.debug_struct("TestHarness")
+            .field("hooks", &state.hooks.len())
+            .field("output", &state.output)
+            .finish()
+    }
+}
+
+impl MockPlugin {
+    fn find_hook(&self, hook: NonNull<hexchat_hook>) -> Option<(Callback, *mut c_void)> {
+        self.state
+            .borrow()
+            .hooks
+            .iter()
+            .find(|h| h.hook == hook)
+            .map(|h| (h.callback, h.user_data))
+    }
+
+    fn remove_hook(&self, hook: NonNull<hexchat_hook>) -> Option<*mut c_void> {
+        let mut state = self.state.borrow_mut();
+        let index = state.hooks.iter().position(|h| h.hook == hook)?;
+        let removed = state.hooks.remove(index);
+        // Safety: each hook was leaked from a `Box<u8>` in `register_hook`, and has now been removed
+        drop(unsafe { Box::from_raw(removed.hook.as_ptr().cast::<u8>()) });
+        Some(removed.user_data)
+    }
+
+    fn context(&self) -> *mut hexchat_context {
+        ptr::addr_of!(self.context).cast_mut().cast()
+    }
+}
+
+/// The `word` and `word_eol` arrays passed to hook callbacks.
+struct Words {
+    /// Keeps the strings alive; the arrays point into them.
+    _strings: Vec<CString>,
+    word: Vec<*mut c_char>,
+    word_eol: Vec<*mut c_char>,
+}
+
+impl Words {
+    /// Splits a line on spaces, like HexChat does for commands and server lines.
+    fn split(line: &str) -> Self {
+        let starts: Vec<usize> = line
+            .char_indices()
+            .filter(|&(i, c)| c != ' ' && (i == 0 || line.as_bytes()[i - 1] == b' '))
+            .map(|(i, _)| i)
+            .collect();
+        let word: Vec<&str> = starts
+            .iter()
+            .map(|&start| line[start..].split(' ').next().unwrap_or(""))
+            .collect();
+        let word_eol: Vec<&str> = starts.iter().map(|&start| &line[start..]).collect();
+        Self::new(&word, &word_eol)
+    }
+
+    /// Builds arrays from `word` and `word_eol`, padded with empty strings like HexChat's.
+    fn new(word: &[&str], word_eol: &[&str]) -> Self {
+        let mut strings = Vec::new();
+        let mut to_array = |words: &[&str]| {
+            let mut array: Vec<*mut c_char> = Vec::new();
+            let len = PDIWORDS.max(words.len() + 1);
+            // the first entry is reserved
+            let padded = [""].iter().chain(words).chain([""].iter().cycle());
+            for word in padded.take(len) {
+                let word = CString::new(*word).expect("word contains a null byte");
+                array.push(word.as_ptr().cast_mut());
+                strings.push(word);
+            }
+            array.push(ptr::null_mut());
+            array
+        };
+        let word = to_array(word);
+        let word_eol = to_array(word_eol);
+        Self {
+            _strings: strings,
+            word,
+            word_eol,
+        }
+    }
+
+    fn word_str(&self, index: usize) -> &str {
+        match self.word.get(index) {
+            // Safety: non-null entries point to strings owned by `self`, which were created from `&str`
+            Some(&word) if !word.is_null() => {
+                unsafe { CStr::from_ptr(word) }.to_str().unwrap_or("")
+            }
+            _ => "",
+        }
+    }
+
+    fn word_ptr(&self) -> *mut *mut c_char {
+        self.word.as_ptr().cast_mut()
+    }
+
+    fn word_eol_ptr(&self) -> *mut *mut c_char {
+        self.word_eol.as_ptr().cast_mut()
+    }
+}
+
+fn event_attrs(ircv3_line: &CStr) -> hexchat_event_attrs {
+    let _ = ircv3_line;
+    hexchat_event_attrs {
+        server_time_utc: OffsetDateTime::now_utc().unix_timestamp(),
+        #[cfg(feature = "__unstable_ircv3_line_in_event_attrs")]
+        ircv3_line: ircv3_line.as_ptr(),
+    }
+}
+
+fn eat_from_int(eat: c_int) -> Eat {
+    match eat {
+        1 => Eat::HexChat,
+        2 => Eat::Plugin,
+        3 => Eat::All,
+        _ => Eat::None,
+    }
+}
+
+/// Records a call to `hexchat_emit_print` or `hexchat_emit_print_attrs`, if `plugin_handle` is the active mock.
+///
+/// Those functions are variadic, which cannot be implemented in stable Rust, so calls to them are intercepted instead.
+///
+/// # Safety
+///
+/// `event_name` must be a null-terminated C string, and `args` must be null or null-terminated C strings.
+pub(crate) unsafe fn intercept_emit_print(
+    plugin_handle: NonNull<hexchat_plugin>,
+    event_name: *const c_char,
+    args: [*const c_char; 5],
+) -> Option<c_int> {
+    if plugin_handle.as_ptr() != ACTIVE_MOCK.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    // Safety: the active mock is valid while its harness exists, which it does if its handle is being used
+    let mock = unsafe { mock(plugin_handle.as_ptr()) };
+    // Safety: forwarded to caller
+    let event = unsafe { to_string(event_name) };
+    let args = args
+        .iter()
+        .take_while(|arg| !arg.is_null())
+        // Safety: forwarded to caller
+        .map(|&arg| unsafe { to_string(arg) })
+        .collect();
+    mock.state
+        .borrow_mut()
+        .output
+        .push(Output::EmitPrint { event, args });
+    Some(1)
+}
+
+/// Gets the `MockPlugin` containing `ph`.
+///
+/// # Safety
+///
+/// `ph` must be the `vtable` field of a valid `MockPlugin`.
+unsafe fn mock<'a>(ph: *mut hexchat_plugin) -> &'a MockPlugin {
+    // Safety: `MockPlugin` is `repr(C)` with `vtable` as its first field; forwarded to caller
+    unsafe { &*ph.cast::<MockPlugin>() }
+}
+
+/// # Safety
+///
+/// `ptr` must be a null-terminated C string.
+unsafe fn to_string(ptr: *const c_char) -> String {
+    // Safety: forwarded to caller
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Converts a preference name as HexChat does, by lowercasing it and replacing characters other than `[a-zA-Z0-9_]` with `_`.
+///
+/// # Safety
+///
+/// `ptr` must be a null-terminated C string.
+unsafe fn pref_name(ptr: *const c_char) -> String {
+    // Safety: forwarded to caller
+    unsafe { CStr::from_ptr(ptr) }
+        .to_bytes()
+        .iter()
+        .map(|&b| {
+            if b.is_ascii_alphanumeric() {
+                char::from(b.to_ascii_lowercase())
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// # Safety
+///
+/// `ph` must be the `vtable` field of a valid `MockPlugin`, and `name` must be a null-terminated C string.
+unsafe fn register_hook(
+    ph: *mut hexchat_plugin,
+    name: *const c_char,
+    priority: c_int,
+    callback: Callback,
+    user_data: *mut c_void,
+) -> *mut hexchat_hook {
+    let hook = NonNull::from(Box::leak(Box::new(0u8))).cast::<hexchat_hook>();
+    let name = if name.is_null() {
+        String::new()
+    } else {
+        // Safety: forwarded to caller
+        unsafe { to_string(name) }
+    };
+    // Safety: forwarded to caller
+    unsafe { mock(ph) }.state.borrow_mut().hooks.push(MockHook {
+        hook,
+        name,
+        priority,
+        callback,
+        user_data,
+    });
+    hook.as_ptr()
+}
+
+/// Creates a `hexchat_plugin` whose functions operate on the `MockPlugin` containing it.
+fn mock_vtable() -> hexchat_plugin {
+    // Note: the mock functions are only ever passed the `hexchat_plugin` created here, inside a `MockPlugin`.
+    // Their `Safety` comments assume this, and that HexChat's documented preconditions are upheld by the caller.
+
+    unsafe extern "C" fn hook_command(
+        ph: *mut hexchat_plugin,
+        name: *const c_char,
+        pri: c_int,
+        callback: CommandCallback,
+        _help_text: *const c_char,
+        userdata: *mut c_void,
+    ) -> *mut hexchat_hook {
+        // Safety: see above
+        unsafe { register_hook(ph, name, pri, Callback::Command(callback), userdata) }
+    }
+
+    unsafe extern "C" fn hook_server(
+        ph: *mut hexchat_plugin,
+        name: *const c_char,
+        pri: c_int,
+        callback: CommandCallback,
+        userdata: *mut c_void,
+    ) -> *mut hexchat_hook {
+        // Safety: see above
+        unsafe { register_hook(ph, name, pri, Callback::Server(callback), userdata) }
+    }
+
+    unsafe extern "C" fn hook_print(
+        ph: *mut hexchat_plugin,
+        name: *const c_char,
+        pri: c_int,
+        callback: PrintCallback,
+        userdata: *mut c_void,
+    ) -> *mut hexchat_hook {
+        // Safety: see above
+        unsafe { register_hook(ph, name, pri, Callback::Print(callback), userdata) }
+    }
+
+    unsafe extern "C" fn hook_timer(
+        ph: *mut hexchat_plugin,
+        _timeout: c_int,
+        callback: TimerCallback,
+        userdata: *mut c_void,
+    ) -> *mut hexchat_hook {
+        // Safety: see above
+        unsafe { register_hook(ph, ptr::null(), 0, Callback::Timer(callback), userdata) }
+    }
+
+    unsafe extern "C" fn hook_fd(
+        _ph: *mut hexchat_plugin,
+        _fd: c_int,
+        _flags: c_int,
+        _callback: unsafe extern "C" fn(c_int, c_int, *mut c_void) -> c_int,
+        _userdata: *mut c_void,
+    ) -> *mut hexchat_hook {
+        ptr::null_mut()
+    }
+
+    unsafe extern "C" fn unhook(ph: *mut hexchat_plugin, hook: *mut hexchat_hook) -> *mut c_void {
+        let Some(hook) = NonNull::new(hook) else {
+            return ptr::null_mut();
+        };
+        // Safety: see above
+        unsafe { mock(ph) }
+            .remove_hook(hook)
+            .unwrap_or(ptr::null_mut())
+    }
+
+    unsafe extern "C" fn print(ph: *mut hexchat_plugin, text: *const c_char) {
+        // Safety: see above
+        let text = unsafe { to_string(text) };
+        // Safety: see above
+        unsafe { mock(ph) }
+            .state
+            .borrow_mut()
+            .output
+            .push(Output::Print(text));
+    }
+
+    unsafe extern "C" fn command(ph: *mut hexchat_plugin, command: *const c_char) {
+        // Safety: see above
+        let command = unsafe { to_string(command) };
+        // Safety: see above
+        unsafe { mock(ph) }
+            .state
+            .borrow_mut()
+            .output
+            .push(Output::Command(command));
+    }
+
+    unsafe extern "C" fn nickcmp(
+        _ph: *mut hexchat_plugin,
+        s1: *const c_char,
+        s2: *const c_char,
+    ) -> c_int {
+        // Safety: see above
+        let (s1, s2) = unsafe { (to_string(s1), to_string(s2)) };
+        match rfc1459::cmp(&s1, &s2) {
+            CmpOrdering::Less => -1,
+            CmpOrdering::Equal => 0,
+            CmpOrdering::Greater => 1,
+        }
+    }
+
+    unsafe extern "C" fn set_context(ph: *mut hexchat_plugin, ctx: *mut hexchat_context) -> c_int {
+        // Safety: see above
+        c_int::from(ctx == unsafe { mock(ph) }.context())
+    }
+
+    unsafe extern "C" fn find_context(
+        ph: *mut hexchat_plugin,
+        servname: *const c_char,
+        channel: *const c_char,
+    ) -> *mut hexchat_context {
+        if servname.is_null() && channel.is_null() {
+            // Safety: see above
+            unsafe { mock(ph) }.context()
+        } else {
+            ptr::null_mut()
+        }
+    }
+
+    unsafe extern "C" fn get_context(ph: *mut hexchat_plugin) -> *mut hexchat_context {
+        // Safety: see above
+        unsafe { mock(ph) }.context()
+    }
+
+    unsafe extern "C" fn get_info(ph: *mut hexchat_plugin, id: *const c_char) -> *const c_char {
+        // Safety: see above
+        let id = unsafe { to_string(id) };
+        // Safety: see above
+        let state = unsafe { mock(ph) }.state.borrow();
+        // the string's heap allocation lives until the info is set again
+        state
+            .info
+            .get(&id)
+            .map_or(ptr::null(), |value| value.as_ptr())
+    }
+
+    unsafe extern "C" fn get_prefs(
+        ph: *mut hexchat_plugin,
+        name: *const c_char,
+        _string: *mut *const c_char,
+        integer: *mut c_int,
+    ) -> c_int {
+        // Safety: see above
+        let name = unsafe { to_string(name) };
+        // Safety: see above
+        let state = unsafe { mock(ph) }.state.borrow();
+        match state.int_prefs.get(&name) {
+            Some(&value) => {
+                // Safety: `integer` is a valid pointer to an int
+                unsafe { *integer = value };
+                // https://hexchat.readthedocs.io/en/latest/plugins.html#c.hexchat_get_prefs
+                2
+            }
+            None => 0,
+        }
+    }
+
+    unsafe extern "C" fn list_get(
+        _ph: *mut hexchat_plugin,
+        _name: *const c_char,
+    ) -> *mut hexchat_list {
+        ptr::null_mut()
+    }
+
+    unsafe extern "C" fn list_free(_ph: *mut hexchat_plugin, _xlist: *mut hexchat_list) {}
+
+    unsafe extern "C" fn list_fields(
+        _ph: *mut hexchat_plugin,
+        _name: *const c_char,
+    ) -> *const *const c_char {
+        ptr::null()
+    }
+
+    unsafe extern "C" fn list_next(_ph: *mut hexchat_plugin, _xlist: *mut hexchat_list) -> c_int {
+        0
+    }
+
+    unsafe extern "C" fn list_str(
+        _ph: *mut hexchat_plugin,
+        _xlist: *mut hexchat_list,
+        _name: *const c_char,
+    ) -> *const c_char {
+        ptr::null()
+    }
+
+    unsafe extern "C" fn list_int(
+        _ph: *mut hexchat_plugin,
+        _xlist: *mut hexchat_list,
+        _name: *const c_char,
+    ) -> c_int {
+        -1
+    }
+
+    unsafe extern "C" fn list_time(
+        _ph: *mut hexchat_plugin,
+        _xlist: *mut hexchat_list,
+        _name: *const c_char,
+    ) -> libc::time_t {
+        -1
+    }
+
+    unsafe extern "C" fn plugingui_add(
+        ph: *mut hexchat_plugin,
+        _filename: *const c_char,
+        _name: *const c_char,
+        _desc: *const c_char,
+        _version: *const c_char,
+        _reserved: *mut c_char,
+    ) -> *mut c_void {
+        // any non-null pointer will do, since it is only passed back to `plugingui_remove`
+        // Safety: see above
+        unsafe { mock(ph) }.context().cast()
+    }
+
+    unsafe extern "C" fn plugingui_remove(_ph: *mut hexchat_plugin, _handle: *mut c_void) {}
+
+    unsafe extern "C" fn read_fd(
+        _ph: *mut hexchat_plugin,
+        _src: *mut c_void,
+        _buf: *mut c_char,
+        _len: *mut c_int,
+    ) -> c_int {
+        0
+    }
+
+    unsafe extern "C" fn gettext(_ph: *mut hexchat_plugin, msgid: *const c_char) -> *mut c_char {
+        msgid.cast_mut()
+    }
+
+    unsafe extern "C" fn send_modes(
+        _ph: *mut hexchat_plugin,
+        _targets: *mut *const c_char,
+        _ntargets: c_int,
+        _modes_per_line: c_int,
+        _sign: c_char,
+        _mode: c_char,
+    ) {
+    }
+
+    unsafe extern "C" fn strip(
+        _ph: *mut hexchat_plugin,
+        str: *const c_char,
+        len: c_int,
+        _flags: c_int,
+    ) -> *mut c_char {
+        // Safety: see above
+        let bytes = unsafe { CStr::from_ptr(str) }.to_bytes();
+        let len = usize::try_from(len).map_or(bytes.len(), |len| len.min(bytes.len()));
+        // Safety: allocating `len + 1` bytes, freed by `hexchat_free`
+        let stripped = unsafe { libc::malloc(len + 1) }.cast::<c_char>();
+        if !stripped.is_null() {
+            // Safety: `stripped` has room for `len` bytes and a null terminator
+            unsafe {
+                ptr::copy_nonoverlapping(bytes.as_ptr().cast::<c_char>(), stripped, len);
+                *stripped.add(len) = 0;
+            }
+        }
+        stripped
+    }
+
+    unsafe extern "C" fn free(_ph: *mut hexchat_plugin, ptr: *mut c_void) {
+        // Safety: only pointers returned from `strip` are passed to `hexchat_free`
+        unsafe { libc::free(ptr) }
+    }
+
+    unsafe extern "C" fn pluginpref_set_str(
+        ph: *mut hexchat_plugin,
+        var: *const c_char,
+        value: *const c_char,
+    ) -> c_int {
+        // Safety: see above
+        let (var, value) = unsafe { (pref_name(var), CStr::from_ptr(value).to_owned()) };
+        // Safety: see above
+        unsafe { mock(ph) }
+            .state
+            .borrow_mut()
+            .pluginprefs
+            .insert(var, value);
+        1
+    }
+
+    unsafe extern "C" fn pluginpref_get_str(
+        ph: *mut hexchat_plugin,
+        var: *const c_char,
+        dest: *mut c_char,
+    ) -> c_int {
+        // Safety: see above
+        let var = unsafe { pref_name(var) };
+        // Safety: see above
+        let state = unsafe { mock(ph) }.state.borrow();
+        match state.pluginprefs.get(&var) {
+            Some(value) => {
+                // HexChat's config file is line-based, and whitespace before each value is skipped
+                let value = value.to_bytes();
+                let value = match value.iter().position(|&b| b != b' ' && b != b'\t') {
+                    Some(start) => &value[start..],
+                    None => &[],
+                };
+                let value = value.split(|&b| b == b'\n').next().unwrap_or_default();
+                // HexChat's buffers are 512 bytes, including the null terminator
+                let len = value.len().min(511);
+                // Safety: `dest` has room for 512 bytes
+                unsafe {
+                    ptr::copy_nonoverlapping(value.as_ptr().cast::<c_char>(), dest, len);
+                    *dest.add(len) = 0;
+                }
+                1
+            }
+            None => 0,
+        }
+    }
+
+    unsafe extern "C" fn pluginpref_set_int(
+        ph: *mut hexchat_plugin,
+        var: *const c_char,
+        value: c_int,
+    ) -> c_int {
+        let value = CString::new(value.to_string()).expect("integers have no null bytes");
+        // Safety: see above
+        let var = unsafe { pref_name(var) };
+        // Safety: see above
+        unsafe { mock(ph) }
+            .state
+            .borrow_mut()
+            .pluginprefs
+            .insert(var, value);
+        1
+    }
+
+    unsafe extern "C" fn pluginpref_get_int(ph: *mut hexchat_plugin, var: *const c_char) -> c_int {
+        // Safety: see above
+        let var = unsafe { pref_name(var) };
+        // Safety: see above
+        let state = unsafe { mock(ph) }.state.borrow();
+        state
+            .pluginprefs
+            .get(&var)
+            .and_then(|value| value.to_str().ok()?.parse().ok())
+            .unwrap_or(-1)
+    }
+
+    unsafe extern "C" fn pluginpref_delete(ph: *mut hexchat_plugin, var: *const c_char) -> c_int {
+        // Safety: see above
+        let var = unsafe { pref_name(var) };
+        // Safety: see above
+        unsafe { mock(ph) }
+            .state
+            .borrow_mut()
+            .pluginprefs
+            .remove(&var);
+        1
+    }
+
+    unsafe extern "C" fn pluginpref_list(ph: *mut hexchat_plugin, dest: *mut c_char) -> c_int {
+        // Safety: see above
+        let state = unsafe { mock(ph) }.state.borrow();
+        let mut list = String::new();
+        for var in state.pluginprefs.keys() {
+            list.push_str(var);
+            list.push(',');
+        }
+        // HexChat's buffer is 4096 bytes, including the null terminator
+        let len = list.len().min(4095);
+        // Safety: `dest` has room for 4096 bytes
+        unsafe {
+            ptr::copy_nonoverlapping(list.as_ptr().cast::<c_char>(), dest, len);
+            *dest.add(len) = 0;
+        }
+        1
+    }
+
+    unsafe extern "C" fn hook_server_attrs(
+        ph: *mut hexchat_plugin,
+        name: *const c_char,
+        pri: c_int,
+        callback: ServerAttrsCallback,
+        userdata: *mut c_void,
+    ) -> *mut hexchat_hook {
+        // Safety: see above
+        unsafe { register_hook(ph, name, pri, Callback::ServerAttrs(callback), userdata) }
+    }
+
+    unsafe extern "C" fn hook_print_attrs(
+        ph: *mut hexchat_plugin,
+        name: *const c_char,
+        pri: c_int,
+        callback: PrintAttrsCallback,
+        userdata: *mut c_void,
+    ) -> *mut hexchat_hook {
+        // Safety: see above
+        unsafe { register_hook(ph, name, pri, Callback::PrintAttrs(callback), userdata) }
+    }
+
+    unsafe extern "C" fn event_attrs_create(_ph: *mut hexchat_plugin) -> *mut hexchat_event_attrs {
+        Box::into_raw(Box::new(event_attrs(c"")))
+    }
+
+    unsafe extern "C" fn event_attrs_free(
+        _ph: *mut hexchat_plugin,
+        attrs: *mut hexchat_event_attrs,
+    ) {
+        // Safety: `attrs` was returned from `event_attrs_create`
+        drop(unsafe { Box::from_raw(attrs) });
+    }
+
+    /// Stands in for the variadic functions, which cannot be implemented in stable Rust.
+    ///
+    /// `hexchat_emit_print` and `hexchat_emit_print_attrs` are intercepted before reaching the mock,
+    /// and `hexchat_printf` and `hexchat_commandf` are never called by `hexavalent`.
+    unsafe extern "C" fn variadic_unsupported(_ph: *mut hexchat_plugin) {
+        eprintln!(
+            "FATAL: `hexavalent` called an unsupported variadic function on the test harness"
+        );
+        std::process::abort()
+    }
+
+    type VariadicVoid = unsafe extern "C" fn(*mut hexchat_plugin, *const c_char, ...);
+    type VariadicInt = unsafe extern "C" fn(*mut hexchat_plugin, *const c_char, ...) -> c_int;
+    type VariadicAttrs = unsafe extern "C" fn(
+        *mut hexchat_plugin,
+        *mut hexchat_event_attrs,
+        *const c_char,
+        ...
+    ) -> c_int;
+
+    type Unsupported = unsafe extern "C" fn(*mut hexchat_plugin);
+
+    hexchat_plugin {
+        hexchat_hook_command: hook_command,
+        hexchat_hook_server: hook_server,
+        hexchat_hook_print: hook_print,
+        hexchat_hook_timer: hook_timer,
+        hexchat_hook_fd: hook_fd,
+        hexchat_unhook: unhook,
+        hexchat_print: print,
+        // Safety: function pointers have the same size; these are never called (see `variadic_unsupported`)
+        hexchat_printf: unsafe {
+            mem::transmute::<Unsupported, VariadicVoid>(variadic_unsupported)
+        },
+        hexchat_command: command,
+        // Safety: as above
+        hexchat_commandf: unsafe {
+            mem::transmute::<Unsupported, VariadicVoid>(variadic_unsupported)
+        },
+        hexchat_nickcmp: nickcmp,
+        hexchat_set_context: set_context,
+        hexchat_find_context: find_context,
+        hexchat_get_context: get_context,
+        hexchat_get_info: get_info,
+        hexchat_get_prefs: get_prefs,
+        hexchat_list_get: list_get,
+        hexchat_list_free: list_free,
+        hexchat_list_fields: list_fields,
+        hexchat_list_next: list_next,
+        hexchat_list_str: list_str,
+        hexchat_list_int: list_int,
+        hexchat_plugingui_add: plugingui_add,
+        hexchat_plugingui_remove: plugingui_remove,
+        // Safety: as above
+        hexchat_emit_print: unsafe {
+            mem::transmute::<Unsupported, VariadicInt>(variadic_unsupported)
+        },
+        hexchat_read_fd: read_fd,
+        hexchat_list_time: list_time,
+        hexchat_gettext: gettext,
+        hexchat_send_modes: send_modes,
+        hexchat_strip: strip,
+        hexchat_free: free,
+        hexchat_pluginpref_set_str: pluginpref_set_str,
+        hexchat_pluginpref_get_str: pluginpref_get_str,
+        hexchat_pluginpref_set_int: pluginpref_set_int,
+        hexchat_pluginpref_get_int: pluginpref_get_int,
+        hexchat_pluginpref_delete: pluginpref_delete,
+        hexchat_pluginpref_list: pluginpref_list,
+        hexchat_hook_server_attrs: hook_server_attrs,
+        hexchat_hook_print_attrs: hook_print_attrs,
+        // Safety: as above
+        hexchat_emit_print_attrs: unsafe {
+            mem::transmute::<Unsupported, VariadicAttrs>(variadic_unsupported)
+        },
+        hexchat_event_attrs_create: event_attrs_create,
+        hexchat_event_attrs_free: event_attrs_free,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::event::print::ChannelMessage;
+    use crate::event::server::Privmsg;
+    use crate::hook::{Priority, Timer};
+    use crate::info::Channel;
+    use crate::InitError;
+
+    #[derive(Default)]
+    struct TestPlugin;
+
+    impl Plugin for TestPlugin {
+        fn init(&self, ph: PluginHandle<'_, Self>) {
+            ph.hook_command(c"high", c"", Priority::High, |_, ph, words| {
+                ph.print(format!("high {}", words[1]));
+                Eat::HexChat
+            });
+            ph.hook_command(c"HIGH", c"", Priority::Low, |_, ph, _| {
+                ph.print("low");
+                Eat::Plugin
+            });
+            ph.hook_server(
+                Privmsg,
+                Priority::Normal,
+                |_, ph, [sender, _, target, text]| {
+                    ph.command(format!("MSG {} {}: {}", target, sender, text));
+                    Eat::None
+                },
+            );
+            ph.hook_timer(std::time::Duration::from_secs(60), |_, ph| {
+                ph.print("tick");
+                Timer::Stop
+            });
+        }
+
+        fn deinit(&self, ph: PluginHandle<'_, Self>) {
+            ph.print("bye");
+        }
+    }
+
+    #[test]
+    fn harness_dispatches_hooks() {
+        let harness = TestHarness::<TestPlugin>::new();
+        // discard the warning about hooking the same command twice
+        harness.take_output();
+
+        assert_eq!(harness.command("high  five"), Eat::All);
+        assert_eq!(
+            harness.take_output(),
+            [
+                Output::Print("high five".to_owned()),
+                Output::Print("low".to_owned())
+            ]
+        );
+
+        harness.server_line(":alice!a@host PRIVMSG #rust :hello there");
+        assert_eq!(
+            harness.take_output(),
+            [Output::Command(
+                "MSG #rust :alice!a@host: :hello there".to_owned()
+            )]
+        );
+
+        assert_eq!(
+            harness.print_event(ChannelMessage, ["a", "b", "", ""]),
+            Eat::None
+        );
+
+        harness.run_timers();
+        harness.run_timers();
+        assert_eq!(harness.take_output(), [Output::Print("tick".to_owned())]);
+
+        harness.set_info("channel", Some("#rust"));
+        harness.with_plugin(|_, ph| {
+            assert_eq!(ph.get_info(Channel).as_str(), "#rust");
+            ph.emit_print(ChannelMessage, ("bob", "hi", "@", ""))
+                .unwrap();
+            ph.pluginpref_set_int(c"count", 3).unwrap();
+            assert_eq!(ph.pluginpref_get_int(c"count"), Ok(3));
+        });
+        assert_eq!(
+            harness.take_output(),
+            [Output::EmitPrint {
+                event: "Channel Message".to_owned(),
+                args: vec![
+                    "bob".to_owned(),
+                    "hi".to_owned(),
+                    "@".to_owned(),
+                    "".to_owned()
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn pluginpref_large_round_trip() {
+        let harness = TestHarness::<TestPlugin>::new();
+        harness.with_plugin(|_, ph| {
+            let value = format!("  leading spaces\n\tand newlines {}", "x".repeat(600));
+            ph.pluginpref_set_large("notes", &value).unwrap();
+            assert_eq!(ph.pluginpref_get_large("notes"), Ok(value));
+
+            // chunk names are reserved, so they cannot be overwritten
+            assert_eq!(ph.pluginpref_set_str("notes__chunk_0", "oops"), Err(()));
+            ph.pluginpref_set_str("notes_0", "unrelated").unwrap();
+            assert_eq!(ph.pluginpref_get_large("notes").unwrap().len(), 631);
+
+            ph.pluginpref_set_large("notes", "short").unwrap();
+            assert_eq!(ph.pluginpref_get_large("notes"), Ok("short".to_owned()));
+            ph.pluginpref_delete_large("notes").unwrap();
+            assert_eq!(ph.pluginpref_list().unwrap(), ["notes_0"]);
+        });
+    }
+
+    #[test]
+    fn whois_keyed_by_server_and_nick() {
+        use crate::event::print::{Disconnected, WhoisEnd, WhoisServerLine};
+
+        #[derive(Default)]
+        struct WhoisPlugin;
+
+        impl Plugin for WhoisPlugin {
+            fn init(&self, ph: PluginHandle<'_, Self>) {
+                ph.hook_whois(|_, ph, whois| {
+                    let server = whois.server().map_or("<none>", |server| server.as_str());
+                    ph.print(format!("{} {}", whois.nick(), server));
+                });
+            }
+        }
+
+        let harness = TestHarness::<WhoisPlugin>::new();
+        harness.set_int_pref("id", Some(1));
+        harness.print_event(WhoisServerLine, ["Alice[m]", "one.example"]);
+        harness.set_int_pref("id", Some(2));
+        harness.print_event(WhoisServerLine, ["alice{m}", "two.example"]);
+        harness.print_event(WhoisServerLine, ["bob", "two.example"]);
+
+        harness.set_int_pref("id", Some(1));
+        harness.print_event(WhoisEnd, ["ALICE[M]"]);
+        assert_eq!(
+            harness.take_output(),
+            [Output::Print("Alice[m] one.example".to_owned())]
+        );
+
+        harness.set_int_pref("id", Some(2));
+        harness.print_event(Disconnected, ["Connection reset"]);
+        harness.print_event(WhoisEnd, ["alice{m}"]);
+        harness.print_event(WhoisEnd, ["bob"]);
+        assert_eq!(
+            harness.take_output(),
+            [
+                Output::Print("alice{m} <none>".to_owned()),
+                Output::Print("bob <none>".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn fn_pointer_hook_unhooked_after_panic_limit() {
+        let harness = TestHarness::<TestPlugin>::new();
+        harness.with_plugin(|_, ph| {
+            ph.set_hook_panic_limit(std::num::NonZeroU32::new(2));
+            ph.hook_command(c"boom", c"", Priority::Normal, |_, _, _| {
+                panic!("command failed");
+            });
+        });
+        harness.take_output();
+
+        assert_eq!(harness.command("boom"), Eat::None);
+        assert_eq!(harness.take_output().len(), 1);
+
+        assert_eq!(harness.command("boom"), Eat::None);
+        assert_eq!(
+            harness.take_output().last(),
+            Some(&Output::Print(
+                "WARNING: `hexavalent` unhooked a callback which panicked too many times"
+                    .to_owned()
+            ))
+        );
+
+        assert_eq!(harness.command("boom"), Eat::None);
+        assert_eq!(harness.take_output(), []);
+    }
+
+    #[test]
+    fn main_thread_queue_survives_panicking_job() {
+        use crate::thread::MainThreadQueue;
+
+        let harness = TestHarness::<TestPlugin>::new();
+        let queue = harness.with_plugin(|_, ph| MainThreadQueue::new(ph));
+        harness.take_output();
+
+        queue.push(|_, _| panic!("job failed")).unwrap();
+        queue.push(|_, ph| ph.print("first")).unwrap();
+        harness.run_timers();
+        assert_eq!(
+            harness.take_output().last(),
+            Some(&Output::Print("first".to_owned()))
+        );
+
+        queue.push(|_, ph| ph.print("second")).unwrap();
+        harness.run_timers();
+        assert_eq!(harness.take_output(), [Output::Print("second".to_owned())]);
+    }
+
+    #[test]
+    fn main_thread_queue_push_fails_after_unload() {
+        use crate::thread::MainThreadQueue;
+
+        let harness = TestHarness::<TestPlugin>::new();
+        let queue = harness.with_plugin(|_, ph| MainThreadQueue::new(ph));
+        drop(harness);
+
+        assert_eq!(queue.push(|_, ph| ph.print("too late")), Err(()));
+    }
+
+    #[test]
+    fn boxed_timer_released_after_panic() {
+        use std::rc::Rc;
+
+        let harness = TestHarness::<TestPlugin>::new();
+        let data = Rc::new(());
+        harness.with_plugin(|_, ph| {
+            let data = Rc::clone(&data);
+            ph.hook_timer_boxed(std::time::Duration::from_secs(1), move |_, _| {
+                let _data = &data;
+                panic!("timer failed");
+            });
+        });
+        assert_eq!(Rc::strong_count(&data), 2);
+
+        harness.run_timers();
+        assert_eq!(Rc::strong_count(&data), 1);
+    }
+
+    #[test]
+    fn io_print_writer_buffers_partial_lines() {
+        use std::io::Write;
+
+        use crate::io::IoPrintWriter;
+
+        let harness = TestHarness::<TestPlugin>::new();
+        harness.take_output();
+        harness.with_plugin(|_, ph| {
+            let mut writer = IoPrintWriter::new(ph);
+            write!(writer, "one ").unwrap();
+            writer.flush().unwrap();
+            assert_eq!(harness.take_output(), []);
+
+            write!(writer, "line\ntwo").unwrap();
+            assert_eq!(
+                harness.take_output(),
+                [Output::Print("one line".to_owned())]
+            );
+
+            drop(writer);
+            assert_eq!(harness.take_output(), [Output::Print("two".to_owned())]);
+        });
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn pluginpref_json_round_trip() {
+        let harness = TestHarness::<TestPlugin>::new();
+        harness.with_plugin(|_, ph| {
+            // long enough that chunks start with spaces
+            let value = vec![" ".repeat(1200), "multi\nline".to_owned()];
+            ph.pluginpref_set_json("lines", &value).unwrap();
+            assert_eq!(ph.pluginpref_get_json::<Vec<String>>("lines"), Ok(value));
+        });
+    }
+
+    #[derive(Default)]
+    struct NetworkPlugin;
+
+    impl Plugin for NetworkPlugin {
+        fn init(&self, ph: PluginHandle<'_, Self>) {
+            ph.hook_server_on("Libera.Chat", Privmsg, Priority::Normal, |_, ph, args| {
+                ph.print(args[3]);
+                Eat::None
+            });
+        }
+    }
+
+    #[test]
+    fn hook_server_on_filters_network() {
+        let harness = TestHarness::<NetworkPlugin>::new();
+
+        harness.set_info("network", Some("libera.chat"));
+        harness.server_line(":alice!a@host PRIVMSG #rust :hello");
+        assert_eq!(harness.take_output(), [Output::Print(":hello".to_owned())]);
+
+        harness.set_info("network", Some("OFTC"));
+        harness.server_line(":alice!a@host PRIVMSG #rust :hello");
+        assert_eq!(harness.take_output(), []);
+    }
+
+    #[derive(Default)]
+    struct FailingPlugin;
+
+    impl Plugin for FailingPlugin {
+        fn try_init(&self, ph: PluginHandle<'_, Self>) -> Result<(), InitError> {
+            ph.hook_command(c"fail", c"", Priority::Normal, |_, _, _| Eat::All);
+            Err(InitError::new("missing dependency"))
+        }
+    }
+
+    #[test]
+    fn harness_rejects_failed_init() {
+        let panic = std::panic::catch_unwind(TestHarness::<FailingPlugin>::new).unwrap_err();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(
+            message.contains("plugin failed to initialize: missing dependency"),
+            "{}",
+            message
+        );
+
+        // the failed plugin's state was cleared, so another plugin can be loaded
+        TestHarness::<TestPlugin>::new();
+    }
+}
@@ -0,0 +1,123 @@
+//! Running code on HexChat's main thread.
+//!
+//! HexChat must only be called from its main thread, so a plugin which does work on a background thread
+//! (e.g. an HTTP request) cannot print the result directly. Instead, the background thread can send a closure
+//! through a [`MainThreadQueue`], which runs it on the main thread with access to the plugin and a [`PluginHandle`].
+
+use std::fmt::{self, Debug, Formatter};
+use std::panic::AssertUnwindSafe;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::time::Duration;
+
+use crate::hook::Timer;
+use crate::state::catch_and_log_unwind;
+use crate::PluginHandle;
+
+type Job<P> = Box<dyn FnOnce(&P, PluginHandle<'_, P>) + Send>;
+
+/// A queue of closures to be run on HexChat's main thread.
+///
+/// `MainThreadQueue` is `Send` and `Clone`, so it can be moved to (or cloned into) background threads.
+/// Closures pushed into the queue are run by a timer hook on the main thread, in the order they were pushed.
+/// A closure which panics is logged like a panicking hook callback, and the remaining closures still run.
+///
+/// The timer hook is unregistered once every clone of the queue has been dropped and all closures have run.
+///
+/// Background threads must not outlive the plugin, since the plugin's code is unloaded along with it.
+/// Join them in [`Plugin::deinit`](crate::Plugin::deinit) if they may still be running.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::thread;
+/// use hexavalent::PluginHandle;
+/// use hexavalent::thread::MainThreadQueue;
+///
+/// fn compute_in_background<P>(ph: PluginHandle<'_, P>) {
+///     let queue = MainThreadQueue::new(ph);
+///     thread::spawn(move || {
+///         let answer = 6 * 7;
+///         let _ = queue.push(move |_plugin, ph| {
+///             ph.print(format!("The answer is {}", answer));
+///         });
+///     });
+/// }
+/// ```
+pub struct MainThreadQueue<P: 'static> {
+    sender: Sender<Job<P>>,
+}
+
+impl<P> MainThreadQueue<P> {
+    /// Creates a queue whose closures are run on the main thread.
+    ///
+    /// The queue is drained by a zero-interval timer, so pushed closures run on the next iteration of HexChat's main loop.
+    /// Use [`MainThreadQueue::with_poll_interval`] to check the queue less often.
+    pub fn new(ph: PluginHandle<'_, P>) -> Self {
+        Self::with_poll_interval(ph, Duration::ZERO)
+    }
+
+    /// Creates a queue whose closures are run on the main thread, checking it every `poll_interval`.
+    ///
+    /// `poll_interval` is the maximum delay before a pushed closure runs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use hexavalent::PluginHandle;
+    /// use hexavalent::thread::MainThreadQueue;
+    ///
+    /// fn make_queue<P>(ph: PluginHandle<'_, P>) -> MainThreadQueue<P> {
+    ///     MainThreadQueue::with_poll_interval(ph, Duration::from_millis(100))
+    /// }
+    /// ```
+    pub fn with_poll_interval(ph: PluginHandle<'_, P>, poll_interval: Duration) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job<P>>();
+
+        ph.hook_timer_boxed(poll_interval, move |plugin, ph| {
+            drain(&receiver, plugin, ph)
+        });
+
+        Self { sender }
+    }
+
+    /// Pushes a closure to be run on the main thread.
+    ///
+    /// Returns `Err` if the timer draining the queue has been unregistered, e.g. because the plugin has been unloaded,
+    /// in which case `f` is dropped without running.
+    pub fn push(&self, f: impl FnOnce(&P, PluginHandle<'_, P>) + Send + 'static) -> Result<(), ()> {
+        self.sender.send(Box::new(f)).map_err(|_| ())
+    }
+}
+
+impl<P> Clone for MainThreadQueue<P> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<P> Debug for MainThreadQueue<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MainThreadQueue").finish_non_exhaustive()
+    }
+}
+
+/// Runs all pending closures, stopping the timer once every sender has been dropped.
+///
+/// Each closure's panics are caught separately, so that one failing closure does not stop the timer.
+fn drain<P>(receiver: &Receiver<Job<P>>, plugin: &P, ph: PluginHandle<'_, P>) -> Timer {
+    loop {
+        match receiver.try_recv() {
+            Ok(job) => {
+                let _ = catch_and_log_unwind(
+                    "MainThreadQueue job",
+                    AssertUnwindSafe(|| job(plugin, ph)),
+                );
+            }
+            Err(TryRecvError::Empty) => return Timer::Continue,
+            Err(TryRecvError::Disconnected) => return Timer::Stop,
+        }
+    }
+}
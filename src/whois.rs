@@ -0,0 +1,166 @@
+//! Collected `/WHOIS` replies.
+
+use crate::str::{HexStr, HexString};
+
+/// The lines of a single `/WHOIS` reply.
+///
+/// Passed to the callback of [`PluginHandle::hook_whois`](crate::PluginHandle::hook_whois).
+///
+/// Each field is `None` (or empty) if the server did not send the corresponding line.
+///
+/// # Examples
+///
+/// ```rust
+/// use hexavalent::PluginHandle;
+///
+/// fn hook_idle_checker<P>(ph: PluginHandle<'_, P>) {
+///     ph.hook_whois(|plugin, ph, whois| {
+///         if let Some(reason) = whois.away_reason() {
+///             ph.print(format!("{} is away: {}", whois.nick(), reason));
+///         }
+///         if let Some(idle) = whois.idle() {
+///             ph.print(format!("{} has been idle for {}", whois.nick(), idle));
+///         }
+///     });
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WhoisResult {
+    nick: HexString,
+    user: Option<HexString>,
+    host: Option<HexString>,
+    real_name: Option<HexString>,
+    server: Option<HexString>,
+    channels: Vec<HexString>,
+    idle: Option<HexString>,
+    signon: Option<HexString>,
+    away_reason: Option<HexString>,
+    account: Option<HexString>,
+    real_host: Option<HexString>,
+    real_ip: Option<HexString>,
+    other: Vec<HexString>,
+}
+
+impl WhoisResult {
+    pub(crate) fn new(nick: &HexStr) -> Self {
+        Self {
+            nick: nick.to_owned(),
+            user: None,
+            host: None,
+            real_name: None,
+            server: None,
+            channels: Vec::new(),
+            idle: None,
+            signon: None,
+            away_reason: None,
+            account: None,
+            real_host: None,
+            real_ip: None,
+            other: Vec::new(),
+        }
+    }
+
+    pub(crate) fn set_name_line(&mut self, user: &HexStr, host: &HexStr, real_name: &HexStr) {
+        self.user = Some(user.to_owned());
+        self.host = Some(host.to_owned());
+        self.real_name = Some(real_name.to_owned());
+    }
+
+    pub(crate) fn set_server(&mut self, server: &HexStr) {
+        self.server = Some(server.to_owned());
+    }
+
+    pub(crate) fn add_channel_line(&mut self, line: &HexStr) {
+        self.channels.push(line.to_owned());
+    }
+
+    pub(crate) fn set_idle(&mut self, idle: &HexStr, signon: Option<&HexStr>) {
+        self.idle = Some(idle.to_owned());
+        self.signon = signon.map(ToOwned::to_owned);
+    }
+
+    pub(crate) fn set_away(&mut self, reason: &HexStr) {
+        self.away_reason = Some(reason.to_owned());
+    }
+
+    pub(crate) fn set_account(&mut self, account: &HexStr) {
+        self.account = Some(account.to_owned());
+    }
+
+    pub(crate) fn set_real_host(&mut self, real_host: &HexStr, real_ip: &HexStr) {
+        self.real_host = Some(real_host.to_owned());
+        self.real_ip = Some(real_ip.to_owned());
+    }
+
+    pub(crate) fn add_other_line(&mut self, line: &HexStr) {
+        self.other.push(line.to_owned());
+    }
+
+    /// The nick which the reply is about.
+    pub fn nick(&self) -> &HexStr {
+        &self.nick
+    }
+
+    /// Username, from the `WhoIs Name Line` event.
+    pub fn user(&self) -> Option<&HexStr> {
+        self.user.as_deref()
+    }
+
+    /// Hostname, from the `WhoIs Name Line` event.
+    pub fn host(&self) -> Option<&HexStr> {
+        self.host.as_deref()
+    }
+
+    /// Realname, from the `WhoIs Name Line` event.
+    pub fn real_name(&self) -> Option<&HexStr> {
+        self.real_name.as_deref()
+    }
+
+    /// Server information, from the `WhoIs Server Line` event.
+    pub fn server(&self) -> Option<&HexStr> {
+        self.server.as_deref()
+    }
+
+    /// Channel membership lines, from the `WhoIs Channel/Oper Line` event.
+    ///
+    /// Each line is usually a space-separated list of channels with prefixes, e.g. `"@#rust #hexchat"`,
+    /// but may also be a message like `"is an IRC operator"`.
+    pub fn channels(&self) -> &[HexString] {
+        &self.channels
+    }
+
+    /// Idle time, from the `WhoIs Idle Line` or `WhoIs Idle Line with Signon` event.
+    pub fn idle(&self) -> Option<&HexStr> {
+        self.idle.as_deref()
+    }
+
+    /// Signon time, from the `WhoIs Idle Line with Signon` event.
+    pub fn signon(&self) -> Option<&HexStr> {
+        self.signon.as_deref()
+    }
+
+    /// Away reason, from the `WhoIs Away Line` event.
+    pub fn away_reason(&self) -> Option<&HexStr> {
+        self.away_reason.as_deref()
+    }
+
+    /// Account name, from the `WhoIs Authenticated` event.
+    pub fn account(&self) -> Option<&HexStr> {
+        self.account.as_deref()
+    }
+
+    /// Real `user@host`, from the `WhoIs Real Host` event.
+    pub fn real_host(&self) -> Option<&HexStr> {
+        self.real_host.as_deref()
+    }
+
+    /// Real IP, from the `WhoIs Real Host` event.
+    pub fn real_ip(&self) -> Option<&HexStr> {
+        self.real_ip.as_deref()
+    }
+
+    /// Other lines, from the `WhoIs Identified` and `WhoIs Special` events.
+    pub fn other(&self) -> &[HexString] {
+        &self.other
+    }
+}